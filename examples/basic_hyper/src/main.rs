@@ -1,37 +1,33 @@
+use hyper_tls::HttpsConnector;
 use pear_client::client::http::HttpPearClient;
+use pear_client::client::pear::PearClient;
+use pear_client::common::package::PackageListing;
+use pear_client::common::stability::Stability;
 use pear_client::context::{Context, PearUrl};
 use pear_client::query::get_package_list::GetPackageListQuery;
 use pear_client::tower_service::Service;
-use pear_client::url::Url;
-use hyper_tls::HttpsConnector;
-use pear_client::common::package::PackageListing;
-use pear_client::common::release::{Release, ReleaseListing};
-use pear_client::compact_str::CompactString;
-use pear_client::query::get_release::GetReleaseQuery;
-use pear_client::query::get_release_list::GetReleaseListQuery;
-
 
 #[tokio::main]
 async fn main() {
   let connector = HttpsConnector::new();
-  let client = hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new()).build(connector);
-  let mut client = HttpPearClient::new(client);
+  let transport = hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new()).build(connector);
+  let pear_url = PearUrl::from_host("pecl.php.net").unwrap();
   {
-    let context = Context::new().set_pear_url(PearUrl(Url::parse("https://pecl.php.net/").unwrap()));
+    // low-level API: build the query and send it through the tower service
+    let mut client = HttpPearClient::new(transport.clone());
+    let context = Context::new().set_pear_url(pear_url.clone());
     let query = GetPackageListQuery::<_>::new().set_context(context);
     let res: PackageListing = client.call(&query).await.unwrap();
     dbg!(&res.items[..10]);
   }
   {
-    let context = Context::new().set_pear_url(PearUrl(Url::parse("https://pecl.php.net/").unwrap()));
-    let query = GetReleaseListQuery::<_>::new(CompactString::new("protobuf")).set_context(context);
-    let res: ReleaseListing = client.call(&query).await.unwrap();
-    dbg!(&res.items[..10]);
-  }
-  {
-    let context = Context::new().set_pear_url(PearUrl(Url::parse("https://pecl.php.net/").unwrap()));
-    let query = GetReleaseQuery::<_>::new(CompactString::new("protobuf"), CompactString::new("4.27.0")).set_context(context);
-    let res: Release = client.call(&query).await.unwrap();
-    dbg!(&res);
+    // high-level API: the client is bound to the channel
+    let client = PearClient::http(transport, pear_url);
+    let package = client.package("protobuf");
+    let releases = package.releases().await.unwrap();
+    dbg!(&releases.items[..10]);
+    let latest = releases.latest(&Stability::Stable).unwrap();
+    let release = package.release(latest.version.as_str()).await.unwrap();
+    dbg!(&release);
   }
 }
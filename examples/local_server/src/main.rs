@@ -0,0 +1,26 @@
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto::Builder;
+use hyper_util::service::TowerToHyperService;
+use pear_client::server::{DirectoryRepository, PearServer};
+use tokio::net::TcpListener;
+
+/// Serve the channel generated in the directory from the first argument (e.g. with `pear-client generate-channel`)
+#[tokio::main]
+async fn main() {
+  let root = std::env::args().nth(1).unwrap_or_else(|| String::from("."));
+  let server = PearServer::new(DirectoryRepository::new(root));
+  let listener = TcpListener::bind("127.0.0.1:8080").await.unwrap();
+  eprintln!("listening on http://{}/", listener.local_addr().unwrap());
+  loop {
+    let (stream, _) = listener.accept().await.unwrap();
+    let service = TowerToHyperService::new(server.clone());
+    tokio::spawn(async move {
+      if let Err(e) = Builder::new(TokioExecutor::new())
+        .serve_connection(TokioIo::new(stream), service)
+        .await
+      {
+        eprintln!("connection error: {e}");
+      }
+    });
+  }
+}
@@ -0,0 +1,484 @@
+//! Extraction of release archives (`.tgz`)
+//!
+//! PEAR archives contain a `package.xml` file at the root, and the release files in a `{name}-{version}` directory.
+//! Archives built for both installer generations contain a `package.xml` version 1.0 and a `package2.xml` version 2.x;
+//! version 2.x is preferred when available.
+
+use crate::common::from_xml::FromXmlError;
+use crate::common::package_xml::{PackageDir, PackageXml, PackageXml1, PackageXml1FromRcDomError};
+use compact_str::{format_compact, CompactString};
+use flate2::read::GzDecoder;
+use md5::{Digest, Md5};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// Package description read from an archive
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ArchivePackageXml {
+  V1(PackageXml1),
+  V2(Box<PackageXml>),
+}
+
+impl ArchivePackageXml {
+  pub fn name(&self) -> &str {
+    match self {
+      Self::V1(p) => p.name.as_str(),
+      Self::V2(p) => p.name.as_str(),
+    }
+  }
+
+  pub fn version(&self) -> &str {
+    match self {
+      Self::V1(p) => p.version.as_str(),
+      Self::V2(p) => p.version.release.as_str(),
+    }
+  }
+
+  pub fn contents(&self) -> &PackageDir {
+    match self {
+      Self::V1(p) => &p.contents,
+      Self::V2(p) => &p.contents,
+    }
+  }
+
+  /// Read a `package.xml` document, in any supported version
+  pub fn try_from_xml(input: &[u8]) -> Result<Self, FromXmlError> {
+    match PackageXml::try_from_xml(input) {
+      Ok(p) => Ok(Self::V2(Box::new(p))),
+      Err(FromXmlError::PackageXml(e)) => match PackageXml1::try_from_xml(input) {
+        Ok(p) => Ok(Self::V1(p)),
+        // report the v2 error, unless the document is a v1 document
+        Err(FromXmlError::PackageXml1(e1)) if !is_unsupported_v1(&e1) => Err(FromXmlError::PackageXml1(e1)),
+        Err(_) => Err(FromXmlError::PackageXml(e)),
+      },
+      Err(e) => Err(e),
+    }
+  }
+}
+
+fn is_unsupported_v1(e: &PackageXml1FromRcDomError) -> bool {
+  matches!(
+    e,
+    PackageXml1FromRcDomError::RootNotFound | PackageXml1FromRcDomError::UnsupportedFormat(_)
+  )
+}
+
+/// Result of a successful extraction
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExtractedRelease {
+  pub package_xml: ArchivePackageXml,
+  /// Directory containing the release files, `{dest}/{name}-{version}`
+  pub root: PathBuf,
+  /// Paths of the extracted files, relative to the destination directory
+  pub files: Vec<PathBuf>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum ExtractArchiveError {
+  #[error("failed to read archive: {0}")]
+  Read(String),
+  #[error("failed to write extracted file {0:?}: {1}")]
+  Write(CompactString, String),
+  #[error("archive entry escapes the destination directory: {0:?}")]
+  UnsafePath(CompactString),
+  #[error("archive entry {0:?} has an unsupported type, only files and directories are allowed")]
+  UnsupportedEntry(CompactString),
+  #[error("archive does not contain a package.xml file")]
+  MissingPackageXml,
+  #[error("invalid package.xml file")]
+  PackageXml(#[source] FromXmlError),
+  #[error("file {0:?} listed in package.xml is missing from the archive")]
+  MissingFile(CompactString),
+  #[error("checksum mismatch for {path:?}: expected md5 {expected}, got {actual}")]
+  Checksum {
+    path: CompactString,
+    expected: CompactString,
+    actual: CompactString,
+  },
+}
+
+/// Extract a gzip-compressed release archive into `dest`
+///
+/// The archive is read once, as a stream. All the entries are written under `dest`; entries that are absolute or
+/// contain `..` are rejected, as well as links and special files. Once the archive is fully read, the MD5 checksum of
+/// every file listed in `package.xml` is checked.
+///
+/// On error, `dest` may contain partially extracted files and should be discarded.
+pub fn extract_release_archive<R: Read>(input: R, dest: &Path) -> Result<ExtractedRelease, ExtractArchiveError> {
+  let mut archive = tar::Archive::new(GzDecoder::new(input));
+  let entries = archive
+    .entries()
+    .map_err(|e| ExtractArchiveError::Read(e.to_string()))?;
+
+  let mut package_xml: Option<Vec<u8>> = None;
+  let mut package2_xml: Option<Vec<u8>> = None;
+  // path relative to `dest` => md5 checksum
+  let mut checksums: BTreeMap<PathBuf, CompactString> = BTreeMap::new();
+  for entry in entries {
+    let mut entry = entry.map_err(|e| ExtractArchiveError::Read(e.to_string()))?;
+    let raw_path = entry.path_bytes();
+    let display_path = CompactString::from_utf8_lossy(&raw_path);
+    let path = safe_path(
+      &entry
+        .path()
+        .map_err(|_| ExtractArchiveError::UnsafePath(display_path.clone()))?,
+    )
+    .ok_or_else(|| ExtractArchiveError::UnsafePath(display_path.clone()))?;
+    let entry_type = entry.header().entry_type();
+    if entry_type.is_dir() {
+      create_dir(&dest.join(&path), &display_path)?;
+      continue;
+    }
+    if !entry_type.is_file() {
+      // global and extended headers are handled by `tar`, skip other metadata entries
+      if entry_type.is_pax_global_extensions() || entry_type.is_pax_local_extensions() {
+        continue;
+      }
+      return Err(ExtractArchiveError::UnsupportedEntry(display_path));
+    }
+
+    let target = dest.join(&path);
+    if let Some(parent) = target.parent() {
+      create_dir(parent, &display_path)?;
+    }
+    let is_package_xml = path == Path::new("package.xml");
+    let is_package2_xml = path == Path::new("package2.xml");
+    let write_error = |e: io::Error| ExtractArchiveError::Write(display_path.clone(), e.to_string());
+    let file = fs::File::create(&target).map_err(write_error)?;
+    let mut writer = HashWriter {
+      inner: file,
+      hasher: Md5::new(),
+      copy: if is_package_xml || is_package2_xml {
+        Some(Vec::new())
+      } else {
+        None
+      },
+    };
+    io::copy(&mut entry, &mut writer).map_err(|e| ExtractArchiveError::Read(e.to_string()))?;
+    writer.inner.flush().map_err(write_error)?;
+    let digest = writer.hasher.finalize();
+    if is_package_xml {
+      package_xml = writer.copy;
+    } else if is_package2_xml {
+      package2_xml = writer.copy;
+    }
+    checksums.insert(path, hex_lower(&digest));
+  }
+
+  let package_xml = match package2_xml.or(package_xml) {
+    Some(input) => ArchivePackageXml::try_from_xml(&input).map_err(ExtractArchiveError::PackageXml)?,
+    None => return Err(ExtractArchiveError::MissingPackageXml),
+  };
+  let root_name = format_compact!("{}-{}", package_xml.name(), package_xml.version());
+  let root = safe_path(Path::new(root_name.as_str())).ok_or(ExtractArchiveError::UnsafePath(root_name.clone()))?;
+  for (path, file) in package_xml.contents().files() {
+    let relative = match safe_path(Path::new(&path)) {
+      Some(p) => root.join(p),
+      None => return Err(ExtractArchiveError::UnsafePath(CompactString::new(&path))),
+    };
+    let actual = match checksums.get(&relative) {
+      Some(actual) => actual,
+      None => return Err(ExtractArchiveError::MissingFile(CompactString::new(&path))),
+    };
+    if let Some(expected) = &file.md5sum {
+      if !expected.trim().eq_ignore_ascii_case(actual) {
+        return Err(ExtractArchiveError::Checksum {
+          path: CompactString::new(&path),
+          expected: expected.clone(),
+          actual: actual.clone(),
+        });
+      }
+    }
+  }
+
+  Ok(ExtractedRelease {
+    package_xml,
+    root: dest.join(root),
+    files: checksums.into_keys().collect(),
+  })
+}
+
+/// `package.xml` file of an archive
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ArchivePackageXmlFile {
+  pub package_xml: ArchivePackageXml,
+  /// Raw content of the file: `package2.xml` when available, `package.xml` otherwise
+  pub raw: Vec<u8>,
+}
+
+/// Read the `package.xml` file of a gzip-compressed release archive, without extracting it
+///
+/// The release files are not checked.
+pub fn read_release_package_xml<R: Read>(input: R) -> Result<ArchivePackageXmlFile, ExtractArchiveError> {
+  let mut archive = tar::Archive::new(GzDecoder::new(input));
+  let entries = archive
+    .entries()
+    .map_err(|e| ExtractArchiveError::Read(e.to_string()))?;
+
+  let mut package_xml: Option<Vec<u8>> = None;
+  let mut package2_xml: Option<Vec<u8>> = None;
+  for entry in entries {
+    let mut entry = entry.map_err(|e| ExtractArchiveError::Read(e.to_string()))?;
+    if !entry.header().entry_type().is_file() {
+      continue;
+    }
+    let path = match entry.path().ok().and_then(|p| safe_path(&p)) {
+      Some(path) => path,
+      None => continue,
+    };
+    let target = if path == Path::new("package.xml") {
+      &mut package_xml
+    } else if path == Path::new("package2.xml") {
+      &mut package2_xml
+    } else {
+      continue;
+    };
+    let mut raw = Vec::new();
+    entry
+      .read_to_end(&mut raw)
+      .map_err(|e| ExtractArchiveError::Read(e.to_string()))?;
+    *target = Some(raw);
+  }
+
+  let raw = package2_xml
+    .or(package_xml)
+    .ok_or(ExtractArchiveError::MissingPackageXml)?;
+  let package_xml = ArchivePackageXml::try_from_xml(&raw).map_err(ExtractArchiveError::PackageXml)?;
+  Ok(ArchivePackageXmlFile { package_xml, raw })
+}
+
+/// Normalize a relative path, rejecting absolute paths and parent components
+///
+/// Returns `None` if the path is unsafe or empty.
+fn safe_path(path: &Path) -> Option<PathBuf> {
+  let mut out = PathBuf::new();
+  for component in path.components() {
+    match component {
+      Component::Normal(c) => out.push(c),
+      Component::CurDir => {}
+      Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+    }
+  }
+  if out.as_os_str().is_empty() {
+    None
+  } else {
+    Some(out)
+  }
+}
+
+fn create_dir(path: &Path, display_path: &CompactString) -> Result<(), ExtractArchiveError> {
+  fs::create_dir_all(path).map_err(|e| ExtractArchiveError::Write(display_path.clone(), e.to_string()))
+}
+
+fn hex_lower(bytes: &[u8]) -> CompactString {
+  let mut out = CompactString::with_capacity(bytes.len() * 2);
+  for b in bytes {
+    out.push_str(&format_compact!("{b:02x}"));
+  }
+  out
+}
+
+/// Writer computing the MD5 checksum of the written data, optionally keeping a copy
+struct HashWriter<W> {
+  inner: W,
+  hasher: Md5,
+  copy: Option<Vec<u8>>,
+}
+
+impl<W: Write> Write for HashWriter<W> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let written = self.inner.write(buf)?;
+    self.hasher.update(&buf[..written]);
+    if let Some(copy) = &mut self.copy {
+      copy.extend_from_slice(&buf[..written]);
+    }
+    Ok(written)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    self.inner.flush()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use flate2::write::GzEncoder;
+  use flate2::Compression;
+
+  const PACKAGE_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<package packagerversion="1.10.13" version="2.0" xmlns="http://pear.php.net/dtd/package-2.0">
+ <name>demo</name>
+ <channel>pecl.php.net</channel>
+ <summary>Demo</summary>
+ <description>Demo extension</description>
+ <lead>
+  <name>Demo</name>
+  <user>demo</user>
+  <email>demo@example.com</email>
+  <active>yes</active>
+ </lead>
+ <date>2024-05-21</date>
+ <version>
+  <release>1.0.0</release>
+  <api>1.0.0</api>
+ </version>
+ <stability>
+  <release>stable</release>
+  <api>stable</api>
+ </stability>
+ <license uri="https://opensource.org/licenses/BSD-3-Clause">BSD-3-Clause</license>
+ <notes>-</notes>
+ <contents>
+  <dir name="/">
+   <file md5sum="MD5_CONFIG" name="config.m4" role="src" />
+   <dir name="src">
+    <file md5sum="MD5_DEMO" name="demo.c" role="src" />
+   </dir>
+  </dir>
+ </contents>
+ <dependencies>
+  <required>
+   <php>
+    <min>8.0.0</min>
+   </php>
+   <pearinstaller>
+    <min>1.4.0</min>
+   </pearinstaller>
+  </required>
+ </dependencies>
+ <providesextension>demo</providesextension>
+ <extsrcrelease />
+</package>
+"#;
+
+  const PACKAGE_XML1: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<package version="1.0">
+ <name>demo</name>
+ <summary>Demo</summary>
+ <release>
+  <version>1.0.0</version>
+  <state>stable</state>
+  <filelist>
+   <dir name="/">
+    <file md5sum="MD5_CONFIG" name="config.m4" role="src" />
+    <file md5sum="MD5_DEMO" name="src/demo.c" />
+   </dir>
+  </filelist>
+ </release>
+</package>
+"#;
+
+  fn md5_hex(data: &[u8]) -> String {
+    hex_lower(&Md5::digest(data)).to_string()
+  }
+
+  fn package_xml(template: &str, demo: &[u8]) -> Vec<u8> {
+    template
+      .replace("MD5_CONFIG", &md5_hex(b"config"))
+      .replace("MD5_DEMO", &md5_hex(demo))
+      .into_bytes()
+  }
+
+  fn archive(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for (path, data) in entries {
+      let mut header = tar::Header::new_gnu();
+      header.set_size(data.len() as u64);
+      header.set_mode(0o644);
+      header.set_entry_type(tar::EntryType::Regular);
+      // write the path directly, `set_path` rejects `..`
+      let name = &mut header.as_old_mut().name;
+      name[..path.len()].copy_from_slice(path.as_bytes());
+      header.set_cksum();
+      builder.append(&header, *data).unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap()
+  }
+
+  #[test]
+  pub fn test_extract_release_archive() {
+    let package = package_xml(PACKAGE_XML, b"demo");
+    let input = archive(&[
+      ("package.xml", &package),
+      ("demo-1.0.0/config.m4", b"config"),
+      ("demo-1.0.0/src/demo.c", b"demo"),
+    ]);
+    let dest = tempfile::tempdir().unwrap();
+    let actual = extract_release_archive(input.as_slice(), dest.path()).unwrap();
+    assert!(matches!(actual.package_xml, ArchivePackageXml::V2(_)));
+    assert_eq!(actual.root, dest.path().join("demo-1.0.0"));
+    assert_eq!(fs::read(actual.root.join("src/demo.c")).unwrap(), b"demo");
+    assert_eq!(actual.files.len(), 3);
+  }
+
+  #[test]
+  pub fn test_extract_release_archive_v1() {
+    let package = package_xml(PACKAGE_XML1, b"demo");
+    let input = archive(&[
+      ("package.xml", &package),
+      ("demo-1.0.0/config.m4", b"config"),
+      ("demo-1.0.0/src/demo.c", b"demo"),
+    ]);
+    let dest = tempfile::tempdir().unwrap();
+    let actual = extract_release_archive(input.as_slice(), dest.path()).unwrap();
+    assert_eq!(actual.package_xml.name(), "demo");
+    assert!(matches!(actual.package_xml, ArchivePackageXml::V1(_)));
+  }
+
+  #[test]
+  pub fn test_extract_release_archive_checksum() {
+    let package = package_xml(PACKAGE_XML, b"demo");
+    let input = archive(&[
+      ("package.xml", &package),
+      ("demo-1.0.0/config.m4", b"config"),
+      ("demo-1.0.0/src/demo.c", b"tampered"),
+    ]);
+    let dest = tempfile::tempdir().unwrap();
+    let actual = extract_release_archive(input.as_slice(), dest.path());
+    let expected = ExtractArchiveError::Checksum {
+      path: CompactString::new("src/demo.c"),
+      expected: CompactString::new(md5_hex(b"demo")),
+      actual: CompactString::new(md5_hex(b"tampered")),
+    };
+    assert_eq!(actual, Err(expected));
+  }
+
+  #[test]
+  pub fn test_extract_release_archive_traversal() {
+    let package = package_xml(PACKAGE_XML, b"demo");
+    let input = archive(&[("package.xml", &package), ("demo-1.0.0/../../evil", b"evil")]);
+    let dest = tempfile::tempdir().unwrap();
+    let actual = extract_release_archive(input.as_slice(), &dest.path().join("out"));
+    assert_eq!(
+      actual,
+      Err(ExtractArchiveError::UnsafePath(CompactString::new(
+        "demo-1.0.0/../../evil"
+      )))
+    );
+    assert!(!dest.path().join("evil").exists());
+  }
+
+  #[test]
+  pub fn test_read_release_package_xml() {
+    let package = package_xml(PACKAGE_XML, b"demo");
+    let package1 = package_xml(PACKAGE_XML1, b"demo");
+    let input = archive(&[
+      ("package.xml", &package1),
+      ("package2.xml", &package),
+      ("demo-1.0.0/config.m4", b"config"),
+    ]);
+    let actual = read_release_package_xml(input.as_slice()).unwrap();
+    assert!(matches!(actual.package_xml, ArchivePackageXml::V2(_)));
+    assert_eq!(actual.raw, package);
+
+    let input = archive(&[("demo-1.0.0/config.m4", b"config")]);
+    assert_eq!(
+      read_release_package_xml(input.as_slice()),
+      Err(ExtractArchiveError::MissingPackageXml)
+    );
+  }
+}
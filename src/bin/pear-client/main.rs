@@ -0,0 +1,354 @@
+//! Command line client for PEAR channels
+//!
+//! ```text
+//! pear-client --channel https://pecl.php.net/ releases protobuf --format json
+//! ```
+
+mod output;
+
+use crate::output::{Format, Output};
+use bytes::Bytes;
+use clap::{Args, Parser, Subcommand};
+use compact_str::CompactString;
+use futures::StreamExt;
+use http_body_util::Full;
+use hyper_tls::HttpsConnector;
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::rt::TokioExecutor;
+use pear_client::client::http::{HttpPearClient, HttpPearClientError};
+use pear_client::common::stability::Stability;
+use pear_client::context::{Context, EmptyContext, PearUrl};
+use pear_client::mirror::{mirror_channel, MirrorConfig, MirrorError};
+use pear_client::query::download_release::DownloadReleaseQuery;
+use pear_client::query::get_latest_version::GetLatestVersionQuery;
+use pear_client::query::get_package_info::GetPackageInfoQuery;
+use pear_client::query::get_package_list::GetPackageListQuery;
+use pear_client::query::get_release::GetReleaseQuery;
+use pear_client::query::get_release_list::GetReleaseListQuery;
+use pear_client::static_channel::{generate_static_channel, GenerateChannelError, StaticChannelConfig};
+use pear_client::tower_service::Service;
+use pear_client::url::Url;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use tokio::io::AsyncWriteExt;
+
+type Transport = hyper_util::client::legacy::Client<HttpsConnector<HttpConnector>, Full<Bytes>>;
+type Client = HttpPearClient<Transport>;
+
+#[derive(Debug, Parser)]
+#[command(name = "pear-client", version, about = "Query PEAR and PECL channels")]
+struct Cli {
+  /// Channel URL, or bare host name
+  #[arg(long, global = true, default_value = "https://pecl.php.net/")]
+  channel: String,
+  /// Output format
+  #[arg(long, global = true, value_enum, default_value_t = Format::Table)]
+  format: Format,
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+  /// List all the packages of the channel
+  Packages,
+  /// Show the package info
+  Info(PackageArgs),
+  /// List the releases of a package
+  Releases(ReleasesArgs),
+  /// Show a release
+  Release(ReleaseArgs),
+  /// Show the latest version of a package
+  Latest(LatestArgs),
+  /// Download the archive of a release
+  Download(DownloadArgs),
+  /// Generate a static channel from a directory of release archives
+  GenerateChannel(GenerateChannelArgs),
+  /// Mirror the channel to a local directory, only fetching the releases missing from a previous run
+  Mirror(MirrorArgs),
+}
+
+#[derive(Debug, Args)]
+struct PackageArgs {
+  package: String,
+}
+
+#[derive(Debug, Args)]
+struct ReleasesArgs {
+  package: String,
+  /// Only list releases with at least this stability
+  #[arg(long)]
+  stability: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct ReleaseArgs {
+  package: String,
+  version: String,
+}
+
+#[derive(Debug, Args)]
+struct LatestArgs {
+  package: String,
+  /// Latest release with this stability, e.g. `stable` or `beta`, instead of the latest release overall
+  #[arg(long, value_parser = ["stable", "beta", "alpha", "devel"])]
+  stability: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct DownloadArgs {
+  package: String,
+  version: String,
+  /// Output file, defaults to the file name of the archive URL (usually `{package}-{version}.tgz`) in the current
+  /// directory
+  #[arg(long, short)]
+  output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+struct GenerateChannelArgs {
+  /// Directory containing the `.tgz` release archives
+  input: PathBuf,
+  /// Output directory
+  output: PathBuf,
+  /// Public URL of the channel, or bare host name
+  #[arg(long)]
+  url: String,
+  /// Channel name, defaults to the host of the URL
+  #[arg(long)]
+  name: Option<String>,
+  /// Channel summary, defaults to the channel name
+  #[arg(long)]
+  summary: Option<String>,
+  /// Suggested alias, e.g. `pecl`
+  #[arg(long)]
+  alias: Option<String>,
+  /// Category of all the packages
+  #[arg(long, default_value = "Default")]
+  category: String,
+}
+
+#[derive(Debug, Args)]
+struct MirrorArgs {
+  /// Output directory
+  output: PathBuf,
+  /// Maximum number of requests in flight
+  #[arg(long, default_value_t = 8)]
+  concurrency: usize,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum CliError {
+  #[error("invalid channel {0:?}: {1}")]
+  Channel(String, url::ParseError),
+  #[error(transparent)]
+  Client(#[from] HttpPearClientError),
+  #[error("failed to write {0:?}: {1}")]
+  Write(PathBuf, std::io::Error),
+  #[error(transparent)]
+  Generate(#[from] GenerateChannelError),
+  #[error(transparent)]
+  Mirror(#[from] MirrorError),
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+  let cli = Cli::parse();
+  match run(cli).await {
+    Ok((output, code)) => {
+      print!("{output}");
+      code
+    }
+    Err(e) => {
+      eprintln!("error: {e}");
+      let mut source = std::error::Error::source(&e);
+      while let Some(e) = source {
+        eprintln!("caused by: {e}");
+        source = e.source();
+      }
+      ExitCode::FAILURE
+    }
+  }
+}
+
+fn parse_channel(channel: &str) -> Result<PearUrl, CliError> {
+  match Url::parse(channel) {
+    Ok(url) => Ok(PearUrl(url)),
+    Err(url::ParseError::RelativeUrlWithoutBase) => {
+      PearUrl::from_host(channel).map_err(|e| CliError::Channel(channel.to_string(), e))
+    }
+    Err(e) => Err(CliError::Channel(channel.to_string(), e)),
+  }
+}
+
+fn stability(value: Option<String>) -> Option<Stability> {
+  value.map(|s| Stability::new(&s))
+}
+
+fn text(value: impl ToString) -> Option<String> {
+  Some(value.to_string())
+}
+
+/// Run the command, returns its rendered output and the exit code
+async fn run(cli: Cli) -> Result<(String, ExitCode), CliError> {
+  let pear_url = parse_channel(&cli.channel)?;
+  let context: Context<PearUrl> = EmptyContext::new().set_pear_url(pear_url.clone());
+  let connector = HttpsConnector::new();
+  let transport: Transport = hyper_util::client::legacy::Client::builder(TokioExecutor::new()).build(connector);
+  let mut client: Client = HttpPearClient::new(transport.clone());
+  let mut code = ExitCode::SUCCESS;
+
+  let output = match cli.command {
+    Command::Packages => {
+      let query = GetPackageListQuery::new().set_context(context);
+      let res = client.call(&query).await?;
+      let mut out = Output::list(vec!["name"]);
+      for name in res.items {
+        out.push(vec![text(name)]);
+      }
+      out
+    }
+    Command::Info(args) => {
+      let query = GetPackageInfoQuery::new(CompactString::new(args.package)).set_context(context);
+      let res = client.call(&query).await?;
+      let deprecation = res
+        .deprecation
+        .map(|d| format!("{}/{}", d.recommended_channel, d.recommended_package));
+      Output::record(vec![
+        ("name", text(res.name)),
+        ("channel", text(res.channel)),
+        ("category", text(res.category)),
+        ("license", text(res.license)),
+        ("license_uri", res.license_uri.map(String::from)),
+        ("summary", text(res.summary)),
+        ("description", text(res.description)),
+        ("parent_package", res.parent_package.map(String::from)),
+        ("deprecated_by", deprecation),
+      ])
+    }
+    Command::Releases(args) => {
+      let query = GetReleaseListQuery::new(CompactString::new(args.package)).set_context(context);
+      let res = client.call(&query).await?;
+      let min = stability(args.stability).unwrap_or(Stability::Other(CompactString::default()));
+      let mut out = Output::list(vec!["version", "stability"]);
+      for release in res.at_least(&min) {
+        out.push(vec![text(&release.version), text(&release.stability)]);
+      }
+      out
+    }
+    Command::Release(args) => {
+      let query =
+        GetReleaseQuery::new(CompactString::new(args.package), CompactString::new(args.version)).set_context(context);
+      let res = client.call(&query).await?;
+      Output::record(vec![
+        ("package", text(res.package.name)),
+        ("channel", text(res.channel)),
+        ("version", text(res.version)),
+        ("stability", text(res.status)),
+        ("license", text(res.license)),
+        ("maintainer", text(res.maintainer)),
+        ("summary", text(res.summary)),
+        ("time", text(res.time)),
+        ("size", text(res.archive.size)),
+        ("archive", text(res.archive.link)),
+      ])
+    }
+    Command::Latest(args) => {
+      let query =
+        GetLatestVersionQuery::new(CompactString::new(args.package), stability(args.stability)).set_context(context);
+      let res = client.call(&query).await?;
+      Output::record(vec![("version", text(res))])
+    }
+    Command::Download(args) => {
+      // read the release first, for its archive link and size
+      let query = GetReleaseQuery::new(CompactString::new(&args.package), CompactString::new(&args.version))
+        .set_context(context.clone());
+      let release = client.call(&query).await?;
+      let query = DownloadReleaseQuery::from_release(&release).set_context(context);
+      let url = query.url(&pear_url);
+      // the file name is the last segment of the archive URL
+      let path = args.output.unwrap_or_else(|| PathBuf::from(query.file_name().as_str()));
+      let mut stream = client.call(&query).await?;
+      let write_error = |e| CliError::Write(path.clone(), e);
+      let mut file = tokio::fs::File::create(&path).await.map_err(write_error)?;
+      let written: Result<(), CliError> = async {
+        while let Some(chunk) = stream.next().await {
+          file.write_all(&chunk?).await.map_err(write_error)?;
+        }
+        file.flush().await.map_err(write_error)
+      }
+      .await;
+      if let Err(e) = written {
+        // do not leave a truncated or corrupted archive behind
+        drop(file);
+        let _ = tokio::fs::remove_file(&path).await;
+        return Err(e);
+      }
+      Output::record(vec![
+        ("url", text(url)),
+        ("path", text(path.display())),
+        ("size", text(stream.received())),
+      ])
+    }
+    Command::GenerateChannel(args) => {
+      let mut config = StaticChannelConfig::new(parse_channel(&args.url)?);
+      if let Some(name) = args.name {
+        config.summary = CompactString::new(&name);
+        config.name = CompactString::new(name);
+      }
+      if let Some(summary) = args.summary {
+        config.summary = CompactString::new(summary);
+      }
+      config.suggested_alias = args.alias.map(CompactString::new);
+      config.category = CompactString::new(args.category);
+      let res = generate_static_channel(&config, &args.input, &args.output)?;
+      Output::record(vec![
+        ("channel", text(config.name)),
+        ("output", text(args.output.display())),
+        ("packages", text(res.packages.join(", "))),
+        ("releases", text(res.releases)),
+        ("files", text(res.files.len())),
+      ])
+    }
+    Command::Mirror(args) => {
+      let mut config = MirrorConfig::new(pear_url);
+      config.concurrency = args.concurrency;
+      // the mirror writes files with blocking calls, keep them off the other tasks of this worker
+      let res = tokio::task::block_in_place(|| {
+        tokio::runtime::Handle::current().block_on(mirror_channel(transport, &config, &args.output))
+      })?;
+      for failure in &res.failures {
+        let mut message = failure.error.to_string();
+        let mut source = std::error::Error::source(&failure.error);
+        while let Some(e) = source {
+          message = format!("{message}: {e}");
+          source = e.source();
+        }
+        match (&failure.package, &failure.version) {
+          (Some(package), Some(version)) => eprintln!("warning: failed to mirror {package} {version}: {message}"),
+          (Some(package), None) => eprintln!("warning: failed to mirror {package}: {message}"),
+          _ => eprintln!("warning: failed to mirror the channel documents: {message}"),
+        }
+      }
+      if !res.is_complete() {
+        // the report is still printed, but the mirror is not usable as is
+        eprintln!(
+          "error: {} packages or releases could not be mirrored",
+          res.failures.len()
+        );
+        code = ExitCode::FAILURE;
+      }
+      Output::record(vec![
+        ("channel", text(&config.pear_url.0)),
+        ("output", text(args.output.display())),
+        ("packages", text(res.packages)),
+        ("releases", text(res.releases)),
+        ("fetched", text(res.fetched.len())),
+        ("skipped", text(res.skipped)),
+        ("downloaded_bytes", text(res.downloaded_bytes)),
+        ("failed", text(res.failures.len())),
+      ])
+    }
+  };
+  Ok((output.render(cli.format), code))
+}
@@ -0,0 +1,123 @@
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+  Table,
+  Json,
+  Yaml,
+}
+
+/// Command result, as a list of records with the same columns
+pub struct Output {
+  pub columns: Vec<&'static str>,
+  pub rows: Vec<Vec<Option<String>>>,
+  /// The result is a single record, not a list
+  pub single: bool,
+}
+
+impl Output {
+  pub fn list(columns: Vec<&'static str>) -> Self {
+    Self {
+      columns,
+      rows: Vec::new(),
+      single: false,
+    }
+  }
+
+  pub fn record(fields: Vec<(&'static str, Option<String>)>) -> Self {
+    let (columns, row) = fields.into_iter().unzip();
+    Self {
+      columns,
+      rows: vec![row],
+      single: true,
+    }
+  }
+
+  pub fn push(&mut self, row: Vec<Option<String>>) {
+    debug_assert_eq!(row.len(), self.columns.len());
+    self.rows.push(row);
+  }
+
+  pub fn render(&self, format: Format) -> String {
+    match format {
+      Format::Table if self.single => self.render_fields(),
+      Format::Table => self.render_table(),
+      Format::Json => {
+        let mut out = serde_json::to_string_pretty(&self.to_value()).expect("serializing JSON values never fails");
+        out.push('\n');
+        out
+      }
+      Format::Yaml => serde_yaml::to_string(&self.to_value()).expect("serializing JSON values never fails"),
+    }
+  }
+
+  fn to_value(&self) -> Value {
+    let mut records = self.rows.iter().map(|row| {
+      let record: Map<String, Value> = self
+        .columns
+        .iter()
+        .zip(row)
+        .map(|(column, value)| {
+          let value = match value {
+            Some(value) => Value::String(value.clone()),
+            None => Value::Null,
+          };
+          (column.to_string(), value)
+        })
+        .collect();
+      Value::Object(record)
+    });
+    if self.single {
+      records.next().unwrap_or(Value::Null)
+    } else {
+      Value::Array(records.collect())
+    }
+  }
+
+  /// One `column: value` line per field
+  fn render_fields(&self) -> String {
+    let width = self.columns.iter().map(|c| c.len()).max().unwrap_or(0);
+    let mut out = String::new();
+    for row in &self.rows {
+      for (column, value) in self.columns.iter().zip(row) {
+        let value = value.as_deref().unwrap_or("-");
+        // indent multiline values, e.g. descriptions
+        let value = value.trim().replace('\n', &format!("\n{:width$}  ", ""));
+        writeln!(out, "{column:width$}  {value}").unwrap();
+      }
+    }
+    out
+  }
+
+  fn render_table(&self) -> String {
+    let cell = |value: &Option<String>| value.as_deref().unwrap_or("-").replace('\n', " ");
+    let mut widths: Vec<usize> = self.columns.iter().map(|c| c.len()).collect();
+    for row in &self.rows {
+      for (width, value) in widths.iter_mut().zip(row) {
+        *width = (*width).max(cell(value).chars().count());
+      }
+    }
+    let mut out = String::new();
+    let header: Vec<String> = self.columns.iter().map(|c| c.to_uppercase()).collect();
+    write_line(&mut out, &widths, header.iter().map(String::as_str));
+    for row in &self.rows {
+      let cells: Vec<String> = row.iter().map(cell).collect();
+      write_line(&mut out, &widths, cells.iter().map(String::as_str));
+    }
+    out
+  }
+}
+
+fn write_line<'a>(out: &mut String, widths: &[usize], cells: impl Iterator<Item = &'a str>) {
+  let mut line = String::new();
+  for (i, (cell, width)) in cells.zip(widths).enumerate() {
+    if i > 0 {
+      line.push_str("  ");
+    }
+    write!(line, "{cell:width$}").unwrap();
+  }
+  out.push_str(line.trim_end());
+  out.push('\n');
+}
@@ -0,0 +1,266 @@
+//! Synchronous facade over [`HttpPearClient`]
+//!
+//! Each call runs the request to completion on a private single-threaded Tokio runtime, so this client must not be used
+//! from within an async context.
+
+use crate::client::http::{HttpPearClient, HttpPearClientError};
+use crate::common::category::{CategoryInfo, CategoryListing, CategoryPackageListing, CategoryPackagesInfo};
+use crate::common::channel::ChannelInfo;
+use crate::common::dependency::ReleaseDependencies;
+use crate::common::maintainer::{MaintainerInfo, MaintainerListing, PackageMaintainers};
+use crate::common::package::{PackageInfo, PackageListing};
+use crate::common::package_xml::PackageXml;
+use crate::common::release::{Release, ReleaseListing, ReleaseListing2, ReleaseV2};
+use crate::common::stability::Stability;
+use crate::common::version::PearVersion;
+use crate::context::{Context, EmptyContext, GetRef, PearUrl};
+use crate::query::download_release::DownloadReleaseQuery;
+use crate::query::get_category_info::GetCategoryInfoQuery;
+use crate::query::get_category_list::GetCategoryListQuery;
+use crate::query::get_category_package_list::GetCategoryPackageListQuery;
+use crate::query::get_category_packages_info::GetCategoryPackagesInfoQuery;
+use crate::query::get_channel::GetChannelQuery;
+use crate::query::get_latest_version::GetLatestVersionQuery;
+use crate::query::get_maintainer_info::GetMaintainerInfoQuery;
+use crate::query::get_maintainer_list::GetMaintainerListQuery;
+use crate::query::get_package_info::GetPackageInfoQuery;
+use crate::query::get_package_list::GetPackageListQuery;
+use crate::query::get_package_maintainers2::GetPackageMaintainers2Query;
+use crate::query::get_package_xml::GetPackageXmlQuery;
+use crate::query::get_release::GetReleaseQuery;
+use crate::query::get_release_dependencies::GetReleaseDependenciesQuery;
+use crate::query::get_release_list::GetReleaseListQuery;
+use crate::query::get_release_list2::GetReleaseList2Query;
+use crate::query::get_release_v2::GetReleaseV2Query;
+use bytes::Bytes;
+use compact_str::CompactString;
+use futures::StreamExt;
+use http::{Request, Response};
+use http_body::Body;
+use http_body_util::Full;
+use hyper_tls::HttpsConnector;
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::rt::TokioExecutor;
+use std::error::Error as StdError;
+use std::io::{self, Write};
+use tokio::runtime::Runtime;
+use tower_service::Service;
+
+/// Default transport: hyper client with TLS support
+pub type DefaultTransport = hyper_util::client::legacy::Client<HttpsConnector<HttpConnector>, Full<Bytes>>;
+
+/// Blocking client for a single channel
+pub struct BlockingPearClient<TyInner = DefaultTransport> {
+  runtime: Runtime,
+  client: HttpPearClient<TyInner>,
+  context: Context<PearUrl>,
+}
+
+impl BlockingPearClient<DefaultTransport> {
+  /// Create a client for the channel at `pear_url`, using the default transport
+  pub fn new(pear_url: PearUrl) -> io::Result<Self> {
+    let inner = hyper_util::client::legacy::Client::builder(TokioExecutor::new()).build(HttpsConnector::new());
+    Self::with_inner(inner, pear_url)
+  }
+}
+
+impl<TyInner> BlockingPearClient<TyInner> {
+  /// Create a client for the channel at `pear_url`, using a custom transport
+  pub fn with_inner(inner: TyInner, pear_url: PearUrl) -> io::Result<Self> {
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    Ok(Self {
+      runtime,
+      client: HttpPearClient::new(inner),
+      context: EmptyContext::new().set_pear_url(pear_url),
+    })
+  }
+
+  pub fn pear_url(&self) -> &PearUrl {
+    self.context.get_ref()
+  }
+}
+
+impl<TyInner, TyBody> BlockingPearClient<TyInner>
+where
+  TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + 'static,
+  TyInner::Error: StdError,
+  TyInner::Future: Send,
+  TyBody: Body + Send,
+  TyBody::Data: Send,
+  TyBody::Error: StdError,
+{
+  /// Send a query and wait for its response
+  pub fn call<Query, Res>(&mut self, query: &Query) -> Result<Res, HttpPearClientError>
+  where
+    for<'req> HttpPearClient<TyInner>: Service<&'req Query, Response = Res, Error = HttpPearClientError>,
+  {
+    let client = &mut self.client;
+    self.runtime.block_on(async move {
+      futures::future::poll_fn(|cx| client.poll_ready(cx)).await?;
+      client.call(query).await
+    })
+  }
+
+  pub fn channel(&mut self) -> Result<ChannelInfo, HttpPearClientError> {
+    let query = GetChannelQuery::new().set_context(self.context.clone());
+    self.call(&query)
+  }
+
+  pub fn package_list(&mut self) -> Result<PackageListing, HttpPearClientError> {
+    let query = GetPackageListQuery::new().set_context(self.context.clone());
+    self.call(&query)
+  }
+
+  pub fn package_info(&mut self, package: &str) -> Result<PackageInfo, HttpPearClientError> {
+    let query = GetPackageInfoQuery::new(CompactString::new(package)).set_context(self.context.clone());
+    self.call(&query)
+  }
+
+  /// Maintainers of a package, with their role (`maintainers2.xml`)
+  pub fn package_maintainers(&mut self, package: &str) -> Result<PackageMaintainers, HttpPearClientError> {
+    let query = GetPackageMaintainers2Query::new(CompactString::new(package)).set_context(self.context.clone());
+    self.call(&query)
+  }
+
+  pub fn release_list(&mut self, package: &str) -> Result<ReleaseListing, HttpPearClientError> {
+    let query = GetReleaseListQuery::new(CompactString::new(package)).set_context(self.context.clone());
+    self.call(&query)
+  }
+
+  pub fn release_list2(&mut self, package: &str) -> Result<ReleaseListing2, HttpPearClientError> {
+    let query = GetReleaseList2Query::new(CompactString::new(package)).set_context(self.context.clone());
+    self.call(&query)
+  }
+
+  /// Latest version with the provided stability, or the latest version overall for `None`
+  pub fn latest_version(
+    &mut self,
+    package: &str,
+    stability: Option<Stability>,
+  ) -> Result<PearVersion, HttpPearClientError> {
+    let query = GetLatestVersionQuery::new(CompactString::new(package), stability).set_context(self.context.clone());
+    self.call(&query)
+  }
+
+  pub fn release(&mut self, package: &str, version: &str) -> Result<Release, HttpPearClientError> {
+    let query =
+      GetReleaseQuery::new(CompactString::new(package), CompactString::new(version)).set_context(self.context.clone());
+    self.call(&query)
+  }
+
+  pub fn release_v2(&mut self, package: &str, version: &str) -> Result<ReleaseV2, HttpPearClientError> {
+    let query = GetReleaseV2Query::new(CompactString::new(package), CompactString::new(version))
+      .set_context(self.context.clone());
+    self.call(&query)
+  }
+
+  pub fn package_xml(&mut self, package: &str, version: &str) -> Result<PackageXml, HttpPearClientError> {
+    let query = GetPackageXmlQuery::new(CompactString::new(package), CompactString::new(version))
+      .set_context(self.context.clone());
+    self.call(&query)
+  }
+
+  pub fn release_dependencies(
+    &mut self,
+    package: &str,
+    version: &str,
+  ) -> Result<ReleaseDependencies, HttpPearClientError> {
+    let query = GetReleaseDependenciesQuery::new(CompactString::new(package), CompactString::new(version))
+      .set_context(self.context.clone());
+    self.call(&query)
+  }
+
+  pub fn category_list(&mut self) -> Result<CategoryListing, HttpPearClientError> {
+    let query = GetCategoryListQuery::new().set_context(self.context.clone());
+    self.call(&query)
+  }
+
+  pub fn category_info(&mut self, category: &str) -> Result<CategoryInfo, HttpPearClientError> {
+    let query = GetCategoryInfoQuery::new(CompactString::new(category)).set_context(self.context.clone());
+    self.call(&query)
+  }
+
+  pub fn category_package_list(&mut self, category: &str) -> Result<CategoryPackageListing, HttpPearClientError> {
+    let query = GetCategoryPackageListQuery::new(CompactString::new(category)).set_context(self.context.clone());
+    self.call(&query)
+  }
+
+  pub fn category_packages_info(&mut self, category: &str) -> Result<CategoryPackagesInfo, HttpPearClientError> {
+    let query = GetCategoryPackagesInfoQuery::new(CompactString::new(category)).set_context(self.context.clone());
+    self.call(&query)
+  }
+
+  pub fn maintainer_list(&mut self) -> Result<MaintainerListing, HttpPearClientError> {
+    let query = GetMaintainerListQuery::new().set_context(self.context.clone());
+    self.call(&query)
+  }
+
+  pub fn maintainer_info(&mut self, handle: &str) -> Result<MaintainerInfo, HttpPearClientError> {
+    let query = GetMaintainerInfoQuery::new(CompactString::new(handle)).set_context(self.context.clone());
+    self.call(&query)
+  }
+
+  /// Download the archive of `release` into `out`, checking its size
+  ///
+  /// Returns the number of bytes written.
+  pub fn download_release<W: Write>(&mut self, release: &Release, out: &mut W) -> Result<u64, HttpPearClientError> {
+    let query = DownloadReleaseQuery::from_release(release).set_context(self.context.clone());
+    let client = &mut self.client;
+    self.runtime.block_on(async move {
+      futures::future::poll_fn(|cx| Service::<&DownloadReleaseQuery<Context<PearUrl>>>::poll_ready(client, cx)).await?;
+      let mut stream = client.call(&query).await?;
+      while let Some(chunk) = stream.next().await {
+        out
+          .write_all(&chunk?)
+          .map_err(|e| HttpPearClientError::Other(format!("failed to write archive: {e}")))?;
+      }
+      Ok(stream.received())
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::client::fixture::FixtureService;
+  use url::Url;
+
+  fn client() -> BlockingPearClient<FixtureService> {
+    let inner = FixtureService::new([
+      (
+        "/rest/r/protobuf/4.27.0.xml",
+        &include_bytes!("../../test-resources/get_release/pecl_protobuf_4.27.0/input.xml")[..],
+      ),
+      ("/rest/r/protobuf/stable.txt", b"4.27.0\n"),
+      ("/get/protobuf-4.27.0.tgz", b"archive"),
+    ]);
+    BlockingPearClient::with_inner(inner, PearUrl(Url::parse("https://pecl.php.net/").unwrap())).unwrap()
+  }
+
+  #[test]
+  pub fn test_blocking_release() {
+    let mut client = client();
+    let actual = client.release("protobuf", "4.27.0").unwrap();
+    assert_eq!(actual.version.as_str(), "4.27.0");
+    let actual = client.latest_version("protobuf", Some(Stability::Stable)).unwrap();
+    assert_eq!(actual.as_str(), "4.27.0");
+    assert_eq!(client.package_info("protobuf"), Err(HttpPearClientError::NotFound));
+  }
+
+  #[test]
+  pub fn test_blocking_download_release() {
+    let mut client = client();
+    let mut release = client.release("protobuf", "4.27.0").unwrap();
+    release.archive.size = 7;
+    let mut out = Vec::new();
+    assert_eq!(client.download_release(&release, &mut out), Ok(7));
+    assert_eq!(out, b"archive");
+
+    // the archive is downloaded from the release link
+    release.archive.link = Url::parse("https://pecl.php.net/get/missing-4.27.0").unwrap();
+    assert_eq!(
+      client.download_release(&release, &mut Vec::new()),
+      Err(HttpPearClientError::NotFound)
+    );
+  }
+}
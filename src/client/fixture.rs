@@ -0,0 +1,51 @@
+//! Test transport serving fixture files by path, shared by the client tests
+
+use bytes::Bytes;
+use core::convert::Infallible;
+use core::task::{Context, Poll};
+use futures::future::{ready, Ready};
+use http::{Request, Response, StatusCode};
+use http_body_util::Full;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tower_service::Service;
+
+/// Inner service replying with the fixture at the request path, or `404 Not Found`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FixtureService {
+  files: Arc<BTreeMap<&'static str, Bytes>>,
+}
+
+impl FixtureService {
+  pub fn new(files: impl IntoIterator<Item = (&'static str, &'static [u8])>) -> Self {
+    Self {
+      files: Arc::new(
+        files
+          .into_iter()
+          .map(|(path, body)| (path, Bytes::from_static(body)))
+          .collect(),
+      ),
+    }
+  }
+}
+
+impl Service<Request<Full<Bytes>>> for FixtureService {
+  type Response = Response<Full<Bytes>>;
+  type Error = Infallible;
+  type Future = Ready<Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    Poll::Ready(Ok(()))
+  }
+
+  fn call(&mut self, req: Request<Full<Bytes>>) -> Self::Future {
+    let res = match self.files.get(req.uri().path()) {
+      Some(body) => Response::new(Full::new(body.clone())),
+      None => Response::builder()
+        .status(StatusCode::NOT_FOUND)
+        .body(Full::new(Bytes::new()))
+        .unwrap(),
+    };
+    ready(Ok(res))
+  }
+}
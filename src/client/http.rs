@@ -1,21 +1,46 @@
+use crate::common::category::{CategoryInfo, CategoryListing, CategoryPackageListing, CategoryPackagesInfo};
+use crate::common::channel::ChannelInfo;
+use crate::common::dependency::{ReleaseDependencies, ReleaseDependenciesFromPhpError};
+use crate::common::from_xml::FromXmlError;
+use crate::common::maintainer::{MaintainerInfo, MaintainerListing, PackageMaintainers};
+use crate::common::package::{PackageInfo, PackageListing};
+use crate::common::package_xml::PackageXml;
+use crate::common::release::{Release, ReleaseListing, ReleaseListing2, ReleaseV2};
+use crate::common::version::PearVersion;
 use crate::context::{GetRef, PearUrl};
+use crate::query::download_release::DownloadReleaseQuery;
+use crate::query::get_category_info::GetCategoryInfoQuery;
+use crate::query::get_category_list::GetCategoryListQuery;
+use crate::query::get_category_package_list::GetCategoryPackageListQuery;
+use crate::query::get_category_packages_info::GetCategoryPackagesInfoQuery;
+use crate::query::get_channel::GetChannelQuery;
+use crate::query::get_latest_version::{GetLatestVersionQuery, UnsupportedLatestStability};
+use crate::query::get_maintainer_info::GetMaintainerInfoQuery;
+use crate::query::get_maintainer_list::GetMaintainerListQuery;
+use crate::query::get_package_info::GetPackageInfoQuery;
+use crate::query::get_package_list::GetPackageListQuery;
+use crate::query::get_package_maintainers::GetPackageMaintainersQuery;
+use crate::query::get_package_maintainers2::GetPackageMaintainers2Query;
+use crate::query::get_package_xml::GetPackageXmlQuery;
+use crate::query::get_release::GetReleaseQuery;
+use crate::query::get_release_dependencies::GetReleaseDependenciesQuery;
+use crate::query::get_release_list::GetReleaseListQuery;
+use crate::query::get_release_list2::GetReleaseList2Query;
+use crate::query::get_release_v2::GetReleaseV2Query;
 use crate::url_util::UrlExt;
-use bytes::Bytes;
+use bytes::{Buf, Bytes};
+use compact_str::{format_compact, CompactString};
+use core::pin::Pin;
 use core::task::{Context, Poll};
 use futures::future::BoxFuture;
-use http::{Method, Request, Response,};
+use futures::Stream;
+use http::{Method, Request, Response, StatusCode};
 use http_body::Body;
 use http_body_util::{BodyExt, Full};
 use std::error::Error as StdError;
-use compact_str::format_compact;
 use tower_service::Service;
-use crate::common::package::{PackageInfo, PackageListing};
-use crate::common::release::{Release, ReleaseListing};
-use crate::query::get_package_info::GetPackageInfoQuery;
-use crate::query::get_package_list::GetPackageListQuery;
-use crate::query::get_release::GetReleaseQuery;
-use crate::query::get_release_list::GetReleaseListQuery;
 
+#[derive(Debug, Clone)]
 pub struct HttpPearClient<TyInner> {
   inner: TyInner,
 }
@@ -36,17 +61,271 @@ pub enum HttpPearClientError {
   #[error("failed to receive response: {0}")]
   Receive(String),
   #[error("failed to parse response: {0}")]
-  ResponseFormat(String, Bytes),
+  ResponseFormat(#[source] FromXmlError, Bytes),
+  #[error("failed to parse dependencies response: {0}")]
+  DependenciesFormat(#[source] ReleaseDependenciesFromPhpError, Bytes),
+  #[error("failed to parse version response")]
+  VersionFormat(Bytes),
+  #[error(transparent)]
+  LatestStability(#[from] UnsupportedLatestStability),
+  #[error("archive size mismatch: expected {expected} bytes, received {actual} bytes")]
+  ArchiveSize { expected: u64, actual: u64 },
   #[error("operation is forbidden for provided auth")]
   Forbidden,
   #[error("resource already exists")]
   Conflict,
   #[error("resource not found")]
   NotFound,
+  #[error("unexpected redirection with status {}", .0.status)]
+  Redirect(HttpResponseHead),
+  #[error("server error with status {}", .0.status)]
+  Server(HttpResponseHead),
+  #[error("unexpected response status {}", .0.status)]
+  UnexpectedStatus(HttpResponseHead),
   #[error("unexpected error: {0}")]
   Other(String),
 }
 
+/// Status and headers of an unexpected HTTP response
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HttpResponseHead {
+  pub status: u16,
+  pub headers: Vec<(CompactString, Bytes)>,
+}
+
+impl HttpResponseHead {
+  fn from_response<TyBody>(res: &Response<TyBody>) -> Self {
+    Self {
+      status: res.status().as_u16(),
+      headers: res
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+          (
+            CompactString::new(name.as_str()),
+            Bytes::copy_from_slice(value.as_bytes()),
+          )
+        })
+        .collect(),
+    }
+  }
+}
+
+/// Read a single-line version file, such as `latest.txt`
+///
+/// An empty file means that there is no matching release.
+fn read_version_text(body: Bytes) -> Result<PearVersion, HttpPearClientError> {
+  let version = match core::str::from_utf8(body.as_ref()) {
+    Ok(text) => text.trim(),
+    Err(_) => return Err(HttpPearClientError::VersionFormat(body)),
+  };
+  if version.is_empty() {
+    return Err(HttpPearClientError::NotFound);
+  }
+  if version.contains(|c: char| c.is_whitespace() || c.is_control()) {
+    return Err(HttpPearClientError::VersionFormat(body));
+  }
+  match PearVersion::new(version) {
+    Ok(version) => Ok(version),
+    Err(_) => Err(HttpPearClientError::VersionFormat(body)),
+  }
+}
+
+/// Check the response status
+pub(crate) fn check_status<TyBody>(res: &Response<TyBody>) -> Result<(), HttpPearClientError> {
+  let status = res.status();
+  if status.is_success() {
+    return Ok(());
+  }
+  Err(match status {
+    StatusCode::NOT_FOUND => HttpPearClientError::NotFound,
+    StatusCode::FORBIDDEN => HttpPearClientError::Forbidden,
+    StatusCode::CONFLICT => HttpPearClientError::Conflict,
+    s if s.is_redirection() => HttpPearClientError::Redirect(HttpResponseHead::from_response(res)),
+    s if s.is_server_error() => HttpPearClientError::Server(HttpResponseHead::from_response(res)),
+    _ => HttpPearClientError::UnexpectedStatus(HttpResponseHead::from_response(res)),
+  })
+}
+
+/// Check the response status and read the full body of a successful response
+pub(crate) async fn read_ok_body<TyBody>(res: Response<TyBody>) -> Result<Bytes, HttpPearClientError>
+where
+  TyBody: Body,
+  TyBody::Error: StdError,
+{
+  check_status(&res)?;
+  let body = res
+    .into_body()
+    .collect()
+    .await
+    .map_err(|e| HttpPearClientError::Receive(format!("{e:?}")))?;
+  Ok(body.to_bytes())
+}
+
+/// Body of a release archive, streamed from the inner service
+///
+/// Yields the chunks of the body as they are received. If an expected size is set, the stream fails with
+/// [`HttpPearClientError::ArchiveSize`] as soon as too many bytes are received, or at the end if too few were.
+pub struct ReleaseArchiveStream<TyBody> {
+  body: Pin<Box<TyBody>>,
+  expected: Option<u64>,
+  received: u64,
+  done: bool,
+}
+
+impl<TyBody> ReleaseArchiveStream<TyBody> {
+  pub(crate) fn new(body: TyBody, expected: Option<u64>) -> Self {
+    Self {
+      body: Box::pin(body),
+      expected,
+      received: 0,
+      done: false,
+    }
+  }
+
+  /// Expected size in bytes
+  pub fn expected_size(&self) -> Option<u64> {
+    self.expected
+  }
+
+  /// Number of bytes received so far
+  pub fn received(&self) -> u64 {
+    self.received
+  }
+
+  fn fail(&mut self) -> Poll<Option<Result<Bytes, HttpPearClientError>>> {
+    self.done = true;
+    Poll::Ready(Some(Err(HttpPearClientError::ArchiveSize {
+      expected: self.expected.unwrap_or(0),
+      actual: self.received,
+    })))
+  }
+}
+
+impl<TyBody> Stream for ReleaseArchiveStream<TyBody>
+where
+  TyBody: Body,
+  TyBody::Error: StdError,
+{
+  type Item = Result<Bytes, HttpPearClientError>;
+
+  fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    loop {
+      if self.done {
+        return Poll::Ready(None);
+      }
+      let frame = match self.body.as_mut().poll_frame(cx) {
+        Poll::Pending => return Poll::Pending,
+        Poll::Ready(frame) => frame,
+      };
+      match frame {
+        None => {
+          if self.expected.is_some_and(|expected| expected != self.received) {
+            return self.fail();
+          }
+          self.done = true;
+          return Poll::Ready(None);
+        }
+        Some(Err(e)) => {
+          self.done = true;
+          return Poll::Ready(Some(Err(HttpPearClientError::Receive(format!("{e:?}")))));
+        }
+        Some(Ok(frame)) => {
+          // skip trailers and empty chunks
+          let mut data = match frame.into_data() {
+            Ok(data) if data.has_remaining() => data,
+            _ => continue,
+          };
+          let chunk = data.copy_to_bytes(data.remaining());
+          self.received += chunk.len() as u64;
+          if self.expected.is_some_and(|expected| self.received > expected) {
+            return self.fail();
+          }
+          return Poll::Ready(Some(Ok(chunk)));
+        }
+      }
+    }
+  }
+}
+
+impl<'req, Cx, TyInner, TyBody> Service<&'req DownloadReleaseQuery<Cx>> for HttpPearClient<TyInner>
+where
+  Cx: GetRef<PearUrl>,
+  TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + 'req,
+  TyInner::Error: StdError,
+  TyInner::Future: Send,
+  TyBody: Body + Send,
+  TyBody::Data: Send,
+  TyBody::Error: StdError,
+{
+  type Response = ReleaseArchiveStream<TyBody>;
+  type Error = HttpPearClientError;
+  type Future = BoxFuture<'req, Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self
+      .inner
+      .poll_ready(cx)
+      .map_err(|e| HttpPearClientError::PollReady(format!("{e:?}")))
+  }
+
+  fn call(&mut self, req: &'req DownloadReleaseQuery<Cx>) -> Self::Future {
+    let url = req.url(req.context.get_ref());
+    let size = req.size;
+
+    let req = Request::builder()
+      .method(Method::GET)
+      .uri(url.as_str())
+      .body(Full::new(Bytes::new()))
+      .unwrap();
+    let res = self.inner.call(req);
+    Box::pin(async move {
+      let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
+      check_status(&res)?;
+      Ok(ReleaseArchiveStream::new(res.into_body(), size))
+    })
+  }
+}
+
+impl<'req, Cx, TyInner, TyBody> Service<&'req GetChannelQuery<Cx>> for HttpPearClient<TyInner>
+where
+  Cx: GetRef<PearUrl>,
+  TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + 'req,
+  TyInner::Error: StdError,
+  TyInner::Future: Send,
+  TyBody: Body + Send,
+  TyBody::Data: Send,
+  TyBody::Error: StdError,
+{
+  type Response = ChannelInfo;
+  type Error = HttpPearClientError;
+  type Future = BoxFuture<'req, Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self
+      .inner
+      .poll_ready(cx)
+      .map_err(|e| HttpPearClientError::PollReady(format!("{e:?}")))
+  }
+
+  fn call(&mut self, req: &'req GetChannelQuery<Cx>) -> Self::Future {
+    let url = req.context.get_ref().url_join_root(["channel.xml"]);
+
+    let req = Request::builder()
+      .method(Method::GET)
+      .uri(url.as_str())
+      .body(Full::new(Bytes::new()))
+      .unwrap();
+    let res = self.inner.call(req);
+    Box::pin(async move {
+      let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
+      let body: Bytes = read_ok_body(res).await?;
+      ChannelInfo::try_from_xml(body.as_ref()).map_err(|e| HttpPearClientError::ResponseFormat(e, body))
+    })
+  }
+}
+
 impl<'req, Cx, TyInner, TyBody> Service<&'req GetPackageListQuery<Cx>> for HttpPearClient<TyInner>
 where
   Cx: GetRef<PearUrl>,
@@ -79,14 +358,8 @@ where
     let res = self.inner.call(req);
     Box::pin(async move {
       let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
-      let body = res
-        .into_body()
-        .collect()
-        .await
-        .map_err(|e| HttpPearClientError::Receive(format!("{e:?}")))?;
-      let body: Bytes = body.to_bytes();
-      let result = PackageListing::from_xml(body.as_ref());
-      Ok(result)
+      let body: Bytes = read_ok_body(res).await?;
+      PackageListing::try_from_xml(body.as_ref()).map_err(|e| HttpPearClientError::ResponseFormat(e, body))
     })
   }
 }
@@ -113,7 +386,10 @@ where
   }
 
   fn call(&mut self, req: &'req GetReleaseListQuery<Cx>) -> Self::Future {
-    let url = req.context.get_ref().url_join(["r", req.package.as_str(), "allreleases.xml"]);
+    let url = req
+      .context
+      .get_ref()
+      .url_join(["r", req.package.as_str(), "allreleases.xml"]);
 
     let req = Request::builder()
       .method(Method::GET)
@@ -123,19 +399,13 @@ where
     let res = self.inner.call(req);
     Box::pin(async move {
       let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
-      let body = res
-        .into_body()
-        .collect()
-        .await
-        .map_err(|e| HttpPearClientError::Receive(format!("{e:?}")))?;
-      let body: Bytes = body.to_bytes();
-      let result = ReleaseListing::from_xml(body.as_ref());
-      Ok(result)
+      let body: Bytes = read_ok_body(res).await?;
+      ReleaseListing::try_from_xml(body.as_ref()).map_err(|e| HttpPearClientError::ResponseFormat(e, body))
     })
   }
 }
 
-impl<'req, Cx, TyInner, TyBody> Service<&'req GetPackageInfoQuery<Cx>> for HttpPearClient<TyInner>
+impl<'req, Cx, TyInner, TyBody> Service<&'req GetReleaseList2Query<Cx>> for HttpPearClient<TyInner>
 where
   Cx: GetRef<PearUrl>,
   TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + 'req,
@@ -145,7 +415,7 @@ where
   TyBody::Data: Send,
   TyBody::Error: StdError,
 {
-  type Response = PackageInfo;
+  type Response = ReleaseListing2;
   type Error = HttpPearClientError;
   type Future = BoxFuture<'req, Result<Self::Response, Self::Error>>;
 
@@ -156,8 +426,11 @@ where
       .map_err(|e| HttpPearClientError::PollReady(format!("{e:?}")))
   }
 
-  fn call(&mut self, req: &'req GetPackageInfoQuery<Cx>) -> Self::Future {
-    let url = req.context.get_ref().url_join(["p", req.package.as_str(), "info.xml"]);
+  fn call(&mut self, req: &'req GetReleaseList2Query<Cx>) -> Self::Future {
+    let url = req
+      .context
+      .get_ref()
+      .url_join(["r", req.package.as_str(), "allreleases2.xml"]);
 
     let req = Request::builder()
       .method(Method::GET)
@@ -167,19 +440,13 @@ where
     let res = self.inner.call(req);
     Box::pin(async move {
       let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
-      let body = res
-        .into_body()
-        .collect()
-        .await
-        .map_err(|e| HttpPearClientError::Receive(format!("{e:?}")))?;
-      let body: Bytes = body.to_bytes();
-      let result = PackageInfo::from_xml(body.as_ref());
-      Ok(result)
+      let body: Bytes = read_ok_body(res).await?;
+      ReleaseListing2::try_from_xml(body.as_ref()).map_err(|e| HttpPearClientError::ResponseFormat(e, body))
     })
   }
 }
 
-impl<'req, Cx, TyInner, TyBody> Service<&'req GetReleaseQuery<Cx>> for HttpPearClient<TyInner>
+impl<'req, Cx, TyInner, TyBody> Service<&'req GetCategoryListQuery<Cx>> for HttpPearClient<TyInner>
 where
   Cx: GetRef<PearUrl>,
   TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + 'req,
@@ -189,7 +456,7 @@ where
   TyBody::Data: Send,
   TyBody::Error: StdError,
 {
-  type Response = Release;
+  type Response = CategoryListing;
   type Error = HttpPearClientError;
   type Future = BoxFuture<'req, Result<Self::Response, Self::Error>>;
 
@@ -200,8 +467,169 @@ where
       .map_err(|e| HttpPearClientError::PollReady(format!("{e:?}")))
   }
 
-  fn call(&mut self, req: &'req GetReleaseQuery<Cx>) -> Self::Future {
-    let url = req.context.get_ref().url_join(["r", req.package.as_str(), &format_compact!("{}.xml", req.version.as_str())]);
+  fn call(&mut self, req: &'req GetCategoryListQuery<Cx>) -> Self::Future {
+    let url = req.context.get_ref().url_join(["c", "categories.xml"]);
+
+    let req = Request::builder()
+      .method(Method::GET)
+      .uri(url.as_str())
+      .body(Full::new(Bytes::new()))
+      .unwrap();
+    let res = self.inner.call(req);
+    Box::pin(async move {
+      let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
+      let body: Bytes = read_ok_body(res).await?;
+      CategoryListing::try_from_xml(body.as_ref()).map_err(|e| HttpPearClientError::ResponseFormat(e, body))
+    })
+  }
+}
+
+impl<'req, Cx, TyInner, TyBody> Service<&'req GetCategoryInfoQuery<Cx>> for HttpPearClient<TyInner>
+where
+  Cx: GetRef<PearUrl>,
+  TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + 'req,
+  TyInner::Error: StdError,
+  TyInner::Future: Send,
+  TyBody: Body + Send,
+  TyBody::Data: Send,
+  TyBody::Error: StdError,
+{
+  type Response = CategoryInfo;
+  type Error = HttpPearClientError;
+  type Future = BoxFuture<'req, Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self
+      .inner
+      .poll_ready(cx)
+      .map_err(|e| HttpPearClientError::PollReady(format!("{e:?}")))
+  }
+
+  fn call(&mut self, req: &'req GetCategoryInfoQuery<Cx>) -> Self::Future {
+    let url = req
+      .context
+      .get_ref()
+      .url_join_php_encoded(["c", req.category.as_str(), "info.xml"]);
+
+    let req = Request::builder()
+      .method(Method::GET)
+      .uri(url.as_str())
+      .body(Full::new(Bytes::new()))
+      .unwrap();
+    let res = self.inner.call(req);
+    Box::pin(async move {
+      let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
+      let body: Bytes = read_ok_body(res).await?;
+      CategoryInfo::try_from_xml(body.as_ref()).map_err(|e| HttpPearClientError::ResponseFormat(e, body))
+    })
+  }
+}
+
+impl<'req, Cx, TyInner, TyBody> Service<&'req GetCategoryPackageListQuery<Cx>> for HttpPearClient<TyInner>
+where
+  Cx: GetRef<PearUrl>,
+  TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + 'req,
+  TyInner::Error: StdError,
+  TyInner::Future: Send,
+  TyBody: Body + Send,
+  TyBody::Data: Send,
+  TyBody::Error: StdError,
+{
+  type Response = CategoryPackageListing;
+  type Error = HttpPearClientError;
+  type Future = BoxFuture<'req, Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self
+      .inner
+      .poll_ready(cx)
+      .map_err(|e| HttpPearClientError::PollReady(format!("{e:?}")))
+  }
+
+  fn call(&mut self, req: &'req GetCategoryPackageListQuery<Cx>) -> Self::Future {
+    let url = req
+      .context
+      .get_ref()
+      .url_join_php_encoded(["c", req.category.as_str(), "packages.xml"]);
+
+    let req = Request::builder()
+      .method(Method::GET)
+      .uri(url.as_str())
+      .body(Full::new(Bytes::new()))
+      .unwrap();
+    let res = self.inner.call(req);
+    Box::pin(async move {
+      let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
+      let body: Bytes = read_ok_body(res).await?;
+      CategoryPackageListing::try_from_xml(body.as_ref()).map_err(|e| HttpPearClientError::ResponseFormat(e, body))
+    })
+  }
+}
+
+impl<'req, Cx, TyInner, TyBody> Service<&'req GetCategoryPackagesInfoQuery<Cx>> for HttpPearClient<TyInner>
+where
+  Cx: GetRef<PearUrl>,
+  TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + 'req,
+  TyInner::Error: StdError,
+  TyInner::Future: Send,
+  TyBody: Body + Send,
+  TyBody::Data: Send,
+  TyBody::Error: StdError,
+{
+  type Response = CategoryPackagesInfo;
+  type Error = HttpPearClientError;
+  type Future = BoxFuture<'req, Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self
+      .inner
+      .poll_ready(cx)
+      .map_err(|e| HttpPearClientError::PollReady(format!("{e:?}")))
+  }
+
+  fn call(&mut self, req: &'req GetCategoryPackagesInfoQuery<Cx>) -> Self::Future {
+    let url = req
+      .context
+      .get_ref()
+      .url_join_php_encoded(["c", req.category.as_str(), "packagesinfo.xml"]);
+
+    let req = Request::builder()
+      .method(Method::GET)
+      .uri(url.as_str())
+      .body(Full::new(Bytes::new()))
+      .unwrap();
+    let res = self.inner.call(req);
+    Box::pin(async move {
+      let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
+      let body: Bytes = read_ok_body(res).await?;
+      CategoryPackagesInfo::try_from_xml(body.as_ref()).map_err(|e| HttpPearClientError::ResponseFormat(e, body))
+    })
+  }
+}
+
+impl<'req, Cx, TyInner, TyBody> Service<&'req GetPackageInfoQuery<Cx>> for HttpPearClient<TyInner>
+where
+  Cx: GetRef<PearUrl>,
+  TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + 'req,
+  TyInner::Error: StdError,
+  TyInner::Future: Send,
+  TyBody: Body + Send,
+  TyBody::Data: Send,
+  TyBody::Error: StdError,
+{
+  type Response = PackageInfo;
+  type Error = HttpPearClientError;
+  type Future = BoxFuture<'req, Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self
+      .inner
+      .poll_ready(cx)
+      .map_err(|e| HttpPearClientError::PollReady(format!("{e:?}")))
+  }
+
+  fn call(&mut self, req: &'req GetPackageInfoQuery<Cx>) -> Self::Future {
+    let url = req.context.get_ref().url_join(["p", req.package.as_str(), "info.xml"]);
 
     let req = Request::builder()
       .method(Method::GET)
@@ -211,14 +639,548 @@ where
     let res = self.inner.call(req);
     Box::pin(async move {
       let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
-      let body = res
-        .into_body()
-        .collect()
-        .await
-        .map_err(|e| HttpPearClientError::Receive(format!("{e:?}")))?;
-      let body: Bytes = body.to_bytes();
-      let result = Release::from_xml(body.as_ref());
-      Ok(result)
+      let body: Bytes = read_ok_body(res).await?;
+      PackageInfo::try_from_xml(body.as_ref()).map_err(|e| HttpPearClientError::ResponseFormat(e, body))
     })
   }
 }
+
+impl<'req, Cx, TyInner, TyBody> Service<&'req GetMaintainerListQuery<Cx>> for HttpPearClient<TyInner>
+where
+  Cx: GetRef<PearUrl>,
+  TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + 'req,
+  TyInner::Error: StdError,
+  TyInner::Future: Send,
+  TyBody: Body + Send,
+  TyBody::Data: Send,
+  TyBody::Error: StdError,
+{
+  type Response = MaintainerListing;
+  type Error = HttpPearClientError;
+  type Future = BoxFuture<'req, Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self
+      .inner
+      .poll_ready(cx)
+      .map_err(|e| HttpPearClientError::PollReady(format!("{e:?}")))
+  }
+
+  fn call(&mut self, req: &'req GetMaintainerListQuery<Cx>) -> Self::Future {
+    let url = req.context.get_ref().url_join(["m", "allmaintainers.xml"]);
+
+    let req = Request::builder()
+      .method(Method::GET)
+      .uri(url.as_str())
+      .body(Full::new(Bytes::new()))
+      .unwrap();
+    let res = self.inner.call(req);
+    Box::pin(async move {
+      let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
+      let body: Bytes = read_ok_body(res).await?;
+      MaintainerListing::try_from_xml(body.as_ref()).map_err(|e| HttpPearClientError::ResponseFormat(e, body))
+    })
+  }
+}
+
+impl<'req, Cx, TyInner, TyBody> Service<&'req GetMaintainerInfoQuery<Cx>> for HttpPearClient<TyInner>
+where
+  Cx: GetRef<PearUrl>,
+  TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + 'req,
+  TyInner::Error: StdError,
+  TyInner::Future: Send,
+  TyBody: Body + Send,
+  TyBody::Data: Send,
+  TyBody::Error: StdError,
+{
+  type Response = MaintainerInfo;
+  type Error = HttpPearClientError;
+  type Future = BoxFuture<'req, Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self
+      .inner
+      .poll_ready(cx)
+      .map_err(|e| HttpPearClientError::PollReady(format!("{e:?}")))
+  }
+
+  fn call(&mut self, req: &'req GetMaintainerInfoQuery<Cx>) -> Self::Future {
+    let url = req.context.get_ref().url_join(["m", req.handle.as_str(), "info.xml"]);
+
+    let req = Request::builder()
+      .method(Method::GET)
+      .uri(url.as_str())
+      .body(Full::new(Bytes::new()))
+      .unwrap();
+    let res = self.inner.call(req);
+    Box::pin(async move {
+      let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
+      let body: Bytes = read_ok_body(res).await?;
+      MaintainerInfo::try_from_xml(body.as_ref()).map_err(|e| HttpPearClientError::ResponseFormat(e, body))
+    })
+  }
+}
+
+impl<'req, Cx, TyInner, TyBody> Service<&'req GetPackageMaintainersQuery<Cx>> for HttpPearClient<TyInner>
+where
+  Cx: GetRef<PearUrl>,
+  TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + 'req,
+  TyInner::Error: StdError,
+  TyInner::Future: Send,
+  TyBody: Body + Send,
+  TyBody::Data: Send,
+  TyBody::Error: StdError,
+{
+  type Response = PackageMaintainers;
+  type Error = HttpPearClientError;
+  type Future = BoxFuture<'req, Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self
+      .inner
+      .poll_ready(cx)
+      .map_err(|e| HttpPearClientError::PollReady(format!("{e:?}")))
+  }
+
+  fn call(&mut self, req: &'req GetPackageMaintainersQuery<Cx>) -> Self::Future {
+    let url = req
+      .context
+      .get_ref()
+      .url_join(["p", req.package.as_str(), "maintainers.xml"]);
+
+    let req = Request::builder()
+      .method(Method::GET)
+      .uri(url.as_str())
+      .body(Full::new(Bytes::new()))
+      .unwrap();
+    let res = self.inner.call(req);
+    Box::pin(async move {
+      let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
+      let body: Bytes = read_ok_body(res).await?;
+      PackageMaintainers::try_from_xml(body.as_ref()).map_err(|e| HttpPearClientError::ResponseFormat(e, body))
+    })
+  }
+}
+
+impl<'req, Cx, TyInner, TyBody> Service<&'req GetPackageMaintainers2Query<Cx>> for HttpPearClient<TyInner>
+where
+  Cx: GetRef<PearUrl>,
+  TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + 'req,
+  TyInner::Error: StdError,
+  TyInner::Future: Send,
+  TyBody: Body + Send,
+  TyBody::Data: Send,
+  TyBody::Error: StdError,
+{
+  type Response = PackageMaintainers;
+  type Error = HttpPearClientError;
+  type Future = BoxFuture<'req, Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self
+      .inner
+      .poll_ready(cx)
+      .map_err(|e| HttpPearClientError::PollReady(format!("{e:?}")))
+  }
+
+  fn call(&mut self, req: &'req GetPackageMaintainers2Query<Cx>) -> Self::Future {
+    let url = req
+      .context
+      .get_ref()
+      .url_join(["p", req.package.as_str(), "maintainers2.xml"]);
+
+    let req = Request::builder()
+      .method(Method::GET)
+      .uri(url.as_str())
+      .body(Full::new(Bytes::new()))
+      .unwrap();
+    let res = self.inner.call(req);
+    Box::pin(async move {
+      let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
+      let body: Bytes = read_ok_body(res).await?;
+      PackageMaintainers::try_from_xml(body.as_ref()).map_err(|e| HttpPearClientError::ResponseFormat(e, body))
+    })
+  }
+}
+
+impl<'req, Cx, TyInner, TyBody> Service<&'req GetReleaseQuery<Cx>> for HttpPearClient<TyInner>
+where
+  Cx: GetRef<PearUrl>,
+  TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + 'req,
+  TyInner::Error: StdError,
+  TyInner::Future: Send,
+  TyBody: Body + Send,
+  TyBody::Data: Send,
+  TyBody::Error: StdError,
+{
+  type Response = Release;
+  type Error = HttpPearClientError;
+  type Future = BoxFuture<'req, Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self
+      .inner
+      .poll_ready(cx)
+      .map_err(|e| HttpPearClientError::PollReady(format!("{e:?}")))
+  }
+
+  fn call(&mut self, req: &'req GetReleaseQuery<Cx>) -> Self::Future {
+    let url = req.context.get_ref().url_join([
+      "r",
+      req.package.as_str(),
+      &format_compact!("{}.xml", req.version.as_str()),
+    ]);
+    let pear_url = req.context.get_ref().clone();
+
+    let req = Request::builder()
+      .method(Method::GET)
+      .uri(url.as_str())
+      .body(Full::new(Bytes::new()))
+      .unwrap();
+    let res = self.inner.call(req);
+    Box::pin(async move {
+      let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
+      let body: Bytes = read_ok_body(res).await?;
+      Release::try_from_xml_with_base(body.as_ref(), &pear_url)
+        .map_err(|e| HttpPearClientError::ResponseFormat(e, body))
+    })
+  }
+}
+
+impl<'req, Cx, TyInner, TyBody> Service<&'req GetReleaseV2Query<Cx>> for HttpPearClient<TyInner>
+where
+  Cx: GetRef<PearUrl>,
+  TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + 'req,
+  TyInner::Error: StdError,
+  TyInner::Future: Send,
+  TyBody: Body + Send,
+  TyBody::Data: Send,
+  TyBody::Error: StdError,
+{
+  type Response = ReleaseV2;
+  type Error = HttpPearClientError;
+  type Future = BoxFuture<'req, Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self
+      .inner
+      .poll_ready(cx)
+      .map_err(|e| HttpPearClientError::PollReady(format!("{e:?}")))
+  }
+
+  fn call(&mut self, req: &'req GetReleaseV2Query<Cx>) -> Self::Future {
+    let url = req.context.get_ref().url_join([
+      "r",
+      req.package.as_str(),
+      &format_compact!("v2.{}.xml", req.version.as_str()),
+    ]);
+    let pear_url = req.context.get_ref().clone();
+
+    let req = Request::builder()
+      .method(Method::GET)
+      .uri(url.as_str())
+      .body(Full::new(Bytes::new()))
+      .unwrap();
+    let res = self.inner.call(req);
+    Box::pin(async move {
+      let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
+      let body: Bytes = read_ok_body(res).await?;
+      ReleaseV2::try_from_xml_with_base(body.as_ref(), &pear_url)
+        .map_err(|e| HttpPearClientError::ResponseFormat(e, body))
+    })
+  }
+}
+
+impl<'req, Cx, TyInner, TyBody> Service<&'req GetLatestVersionQuery<Cx>> for HttpPearClient<TyInner>
+where
+  Cx: GetRef<PearUrl>,
+  TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + 'req,
+  TyInner::Error: StdError,
+  TyInner::Future: Send,
+  TyBody: Body + Send,
+  TyBody::Data: Send,
+  TyBody::Error: StdError,
+{
+  type Response = PearVersion;
+  type Error = HttpPearClientError;
+  type Future = BoxFuture<'req, Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self
+      .inner
+      .poll_ready(cx)
+      .map_err(|e| HttpPearClientError::PollReady(format!("{e:?}")))
+  }
+
+  fn call(&mut self, req: &'req GetLatestVersionQuery<Cx>) -> Self::Future {
+    let file_name = match req.file_name() {
+      Ok(file_name) => file_name,
+      Err(e) => return Box::pin(futures::future::ready(Err(e.into()))),
+    };
+    let url = req.context.get_ref().url_join(["r", req.package.as_str(), file_name]);
+
+    let req = Request::builder()
+      .method(Method::GET)
+      .uri(url.as_str())
+      .body(Full::new(Bytes::new()))
+      .unwrap();
+    let res = self.inner.call(req);
+    Box::pin(async move {
+      let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
+      let body: Bytes = read_ok_body(res).await?;
+      read_version_text(body)
+    })
+  }
+}
+
+impl<'req, Cx, TyInner, TyBody> Service<&'req GetReleaseDependenciesQuery<Cx>> for HttpPearClient<TyInner>
+where
+  Cx: GetRef<PearUrl>,
+  TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + 'req,
+  TyInner::Error: StdError,
+  TyInner::Future: Send,
+  TyBody: Body + Send,
+  TyBody::Data: Send,
+  TyBody::Error: StdError,
+{
+  type Response = ReleaseDependencies;
+  type Error = HttpPearClientError;
+  type Future = BoxFuture<'req, Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self
+      .inner
+      .poll_ready(cx)
+      .map_err(|e| HttpPearClientError::PollReady(format!("{e:?}")))
+  }
+
+  fn call(&mut self, req: &'req GetReleaseDependenciesQuery<Cx>) -> Self::Future {
+    let url = req.context.get_ref().url_join([
+      "r",
+      req.package.as_str(),
+      &format_compact!("deps.{}.txt", req.version.as_str()),
+    ]);
+
+    let req = Request::builder()
+      .method(Method::GET)
+      .uri(url.as_str())
+      .body(Full::new(Bytes::new()))
+      .unwrap();
+    let res = self.inner.call(req);
+    Box::pin(async move {
+      let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
+      let body: Bytes = read_ok_body(res).await?;
+      ReleaseDependencies::from_php_serialized(body.as_ref())
+        .map_err(|e| HttpPearClientError::DependenciesFormat(e, body))
+    })
+  }
+}
+
+impl<'req, Cx, TyInner, TyBody> Service<&'req GetPackageXmlQuery<Cx>> for HttpPearClient<TyInner>
+where
+  Cx: GetRef<PearUrl>,
+  TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + 'req,
+  TyInner::Error: StdError,
+  TyInner::Future: Send,
+  TyBody: Body + Send,
+  TyBody::Data: Send,
+  TyBody::Error: StdError,
+{
+  type Response = PackageXml;
+  type Error = HttpPearClientError;
+  type Future = BoxFuture<'req, Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self
+      .inner
+      .poll_ready(cx)
+      .map_err(|e| HttpPearClientError::PollReady(format!("{e:?}")))
+  }
+
+  fn call(&mut self, req: &'req GetPackageXmlQuery<Cx>) -> Self::Future {
+    let url = req.context.get_ref().url_join([
+      "r",
+      req.package.as_str(),
+      &format_compact!("package.{}.xml", req.version.as_str()),
+    ]);
+
+    let req = Request::builder()
+      .method(Method::GET)
+      .uri(url.as_str())
+      .body(Full::new(Bytes::new()))
+      .unwrap();
+    let res = self.inner.call(req);
+    Box::pin(async move {
+      let res: Response<TyBody> = res.await.map_err(|e| HttpPearClientError::Send(format!("{e:?}")))?;
+      let body: Bytes = read_ok_body(res).await?;
+      PackageXml::try_from_xml(body.as_ref()).map_err(|e| HttpPearClientError::ResponseFormat(e, body))
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::common::stability::Stability;
+  use crate::context::Context as PearContext;
+  use core::convert::Infallible;
+  use futures::future::{ready, Ready};
+  use futures::TryStreamExt;
+  use url::Url;
+
+  /// Inner service always replying with the same status and body
+  struct StaticService {
+    status: StatusCode,
+    body: &'static [u8],
+  }
+
+  impl Service<Request<Full<Bytes>>> for StaticService {
+    type Response = Response<Full<Bytes>>;
+    type Error = Infallible;
+    type Future = Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+      Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _req: Request<Full<Bytes>>) -> Self::Future {
+      let res = Response::builder()
+        .status(self.status)
+        .header("content-type", "text/html")
+        .body(Full::new(Bytes::from_static(self.body)))
+        .unwrap();
+      ready(Ok(res))
+    }
+  }
+
+  fn get_release(status: StatusCode, body: &'static [u8]) -> Result<Release, HttpPearClientError> {
+    let mut client = HttpPearClient::new(StaticService { status, body });
+    let context = PearContext::new().set_pear_url(PearUrl(Url::parse("https://pecl.php.net/").unwrap()));
+    let query = GetReleaseQuery::new(CompactString::new("protobuf"), CompactString::new("0.0.0")).set_context(context);
+    futures::executor::block_on(client.call(&query))
+  }
+
+  fn get_latest(
+    status: StatusCode,
+    body: &'static [u8],
+    stability: Stability,
+  ) -> Result<PearVersion, HttpPearClientError> {
+    let mut client = HttpPearClient::new(StaticService { status, body });
+    let context = PearContext::new().set_pear_url(PearUrl(Url::parse("https://pecl.php.net/").unwrap()));
+    let query = GetLatestVersionQuery::new(CompactString::new("protobuf"), Some(stability)).set_context(context);
+    futures::executor::block_on(client.call(&query))
+  }
+
+  fn download(body: &'static [u8], size: Option<u64>) -> Result<Vec<u8>, HttpPearClientError> {
+    let mut client = HttpPearClient::new(StaticService {
+      status: StatusCode::OK,
+      body,
+    });
+    let context = PearContext::new().set_pear_url(PearUrl(Url::parse("https://pecl.php.net/").unwrap()));
+    let query = DownloadReleaseQuery::new(CompactString::new("protobuf"), CompactString::new("4.27.0"), size)
+      .set_context(context);
+    futures::executor::block_on(async {
+      let stream = client.call(&query).await?;
+      let chunks: Vec<Bytes> = stream.try_collect().await?;
+      Ok(chunks.concat())
+    })
+  }
+
+  #[test]
+  pub fn test_ok() {
+    let actual = get_release(
+      StatusCode::OK,
+      include_bytes!("../../test-resources/get_release/pecl_protobuf_4.27.0/input.xml"),
+    );
+    assert_eq!(actual.unwrap().version.as_str(), "4.27.0");
+  }
+
+  #[test]
+  pub fn test_not_found() {
+    let actual = get_release(StatusCode::NOT_FOUND, b"File not found");
+    assert_eq!(actual, Err(HttpPearClientError::NotFound));
+  }
+
+  #[test]
+  pub fn test_latest_version() {
+    assert_eq!(
+      get_latest(StatusCode::OK, b"4.27.0\n", Stability::Beta),
+      Ok(PearVersion::new("4.27.0").unwrap())
+    );
+    assert_eq!(
+      get_latest(StatusCode::OK, b"", Stability::Beta),
+      Err(HttpPearClientError::NotFound)
+    );
+    assert_eq!(
+      get_latest(StatusCode::NOT_FOUND, b"File not found", Stability::Beta),
+      Err(HttpPearClientError::NotFound)
+    );
+    assert_eq!(
+      get_latest(StatusCode::OK, b"4.27.0\n", Stability::new("stabel")),
+      Err(HttpPearClientError::LatestStability(UnsupportedLatestStability(
+        CompactString::new("stabel")
+      )))
+    );
+    assert_eq!(
+      get_latest(StatusCode::OK, b"4.27.0\n", Stability::Snapshot),
+      Err(HttpPearClientError::LatestStability(UnsupportedLatestStability(
+        CompactString::new("snapshot")
+      )))
+    );
+  }
+
+  #[test]
+  pub fn test_download_release() {
+    assert_eq!(download(b"archive", Some(7)), Ok(b"archive".to_vec()));
+    assert_eq!(download(b"archive", None), Ok(b"archive".to_vec()));
+    assert_eq!(
+      download(b"archive", Some(8)),
+      Err(HttpPearClientError::ArchiveSize { expected: 8, actual: 7 })
+    );
+    assert_eq!(
+      download(b"archive", Some(6)),
+      Err(HttpPearClientError::ArchiveSize { expected: 6, actual: 7 })
+    );
+  }
+
+  #[test]
+  #[cfg(feature = "server")]
+  pub fn test_download_release_from_link() {
+    use crate::server::{MemoryRepository, PearServer};
+
+    let input = include_str!("../../test-resources/get_release/pecl_protobuf_4.27.0/input.xml").replace(
+      "https://pecl.php.net/get/protobuf-4.27.0",
+      "https://download.example.com/package/protobuf-4.27.0",
+    );
+    let mut release = Release::from_xml(input.as_bytes());
+    release.archive.size = 7;
+    let query = DownloadReleaseQuery::from_release(&release)
+      .set_context(PearContext::new().set_pear_url(PearUrl(Url::parse("https://pecl.php.net/").unwrap())));
+    assert_eq!(
+      query.url(query.context.get_ref()).as_str(),
+      "https://download.example.com/package/protobuf-4.27.0.tgz"
+    );
+    assert_eq!(query.file_name(), "protobuf-4.27.0.tgz");
+
+    // the server ignores the host, only the path tells the archive link apart from `/get/`
+    let mut repository = MemoryRepository::new();
+    repository.insert("package/protobuf-4.27.0.tgz", "archive");
+    let mut client = HttpPearClient::new(PearServer::new(repository));
+    let actual: Result<Vec<u8>, HttpPearClientError> = futures::executor::block_on(async {
+      let stream = client.call(&query).await?;
+      let chunks: Vec<Bytes> = stream.try_collect().await?;
+      Ok(chunks.concat())
+    });
+    assert_eq!(actual, Ok(b"archive".to_vec()));
+  }
+
+  #[test]
+  pub fn test_server_error() {
+    let actual = get_release(StatusCode::BAD_GATEWAY, b"<html><body>Bad Gateway</body></html>");
+    let expected = HttpResponseHead {
+      status: 502,
+      headers: vec![(CompactString::new("content-type"), Bytes::from_static(b"text/html"))],
+    };
+    assert_eq!(actual, Err(HttpPearClientError::Server(expected)));
+  }
+}
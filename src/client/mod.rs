@@ -1 +1,6 @@
-pub mod http;
\ No newline at end of file
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(test)]
+mod fixture;
+pub mod http;
+pub mod pear;
@@ -0,0 +1,372 @@
+//! High-level client bound to a channel
+//!
+//! [`PearClient`] builds the queries and sets their context, then sends them through the query-level service (usually
+//! [`HttpPearClient`]). Use the services directly for full control over the queries.
+//!
+//! See the `basic_hyper` example.
+
+use crate::client::http::{HttpPearClient, ReleaseArchiveStream};
+use crate::common::category::{CategoryInfo, CategoryListing, CategoryPackageListing, CategoryPackagesInfo};
+use crate::common::channel::ChannelInfo;
+use crate::common::dependency::ReleaseDependencies;
+use crate::common::maintainer::{MaintainerInfo, MaintainerListing, PackageMaintainers};
+use crate::common::package::{PackageInfo, PackageListing};
+use crate::common::package_xml::PackageXml;
+use crate::common::release::{Release, ReleaseListing, ReleaseListing2, ReleaseV2};
+use crate::common::stability::Stability;
+use crate::common::version::PearVersion;
+use crate::context::{Context, EmptyContext, GetRef, PearUrl};
+use crate::query::download_release::DownloadReleaseQuery;
+use crate::query::get_category_info::GetCategoryInfoQuery;
+use crate::query::get_category_list::GetCategoryListQuery;
+use crate::query::get_category_package_list::GetCategoryPackageListQuery;
+use crate::query::get_category_packages_info::GetCategoryPackagesInfoQuery;
+use crate::query::get_channel::GetChannelQuery;
+use crate::query::get_latest_version::GetLatestVersionQuery;
+use crate::query::get_maintainer_info::GetMaintainerInfoQuery;
+use crate::query::get_maintainer_list::GetMaintainerListQuery;
+use crate::query::get_package_info::GetPackageInfoQuery;
+use crate::query::get_package_list::GetPackageListQuery;
+use crate::query::get_package_maintainers2::GetPackageMaintainers2Query;
+use crate::query::get_package_xml::GetPackageXmlQuery;
+use crate::query::get_release::GetReleaseQuery;
+use crate::query::get_release_dependencies::GetReleaseDependenciesQuery;
+use crate::query::get_release_list::GetReleaseListQuery;
+use crate::query::get_release_list2::GetReleaseList2Query;
+use crate::query::get_release_v2::GetReleaseV2Query;
+use compact_str::CompactString;
+use tower_service::Service;
+
+/// Context of the queries sent by [`PearClient`]
+pub type ChannelContext = Context<PearUrl>;
+
+/// Client bound to a channel
+///
+/// The inner service is cloned for each request, so it should be cheap to clone (e.g. share its state with `Arc`).
+#[derive(Debug, Clone)]
+pub struct PearClient<S> {
+  inner: S,
+  context: ChannelContext,
+}
+
+impl<S> PearClient<S> {
+  pub fn new(inner: S, pear_url: PearUrl) -> Self {
+    Self {
+      inner,
+      context: EmptyContext::new().set_pear_url(pear_url),
+    }
+  }
+
+  pub fn pear_url(&self) -> &PearUrl {
+    self.context.get_ref()
+  }
+
+  /// Context to use for queries sent directly to the inner service
+  pub fn context(&self) -> &ChannelContext {
+    &self.context
+  }
+
+  pub fn inner(&self) -> &S {
+    &self.inner
+  }
+
+  pub fn into_inner(self) -> S {
+    self.inner
+  }
+
+  pub fn package(&self, name: &str) -> PackageClient<'_, S> {
+    PackageClient {
+      client: self,
+      name: CompactString::new(name),
+    }
+  }
+
+  pub fn category(&self, name: &str) -> CategoryClient<'_, S> {
+    CategoryClient {
+      client: self,
+      name: CompactString::new(name),
+    }
+  }
+}
+
+impl<TyInner> PearClient<HttpPearClient<TyInner>> {
+  /// Create a client sending its queries over HTTP, using `transport` to send the requests
+  pub fn http(transport: TyInner, pear_url: PearUrl) -> Self {
+    Self::new(HttpPearClient::new(transport), pear_url)
+  }
+}
+
+impl<S: Clone> PearClient<S> {
+  /// Send a query through a clone of the inner service
+  pub async fn call<Query, Res, Err>(&self, query: &Query) -> Result<Res, Err>
+  where
+    for<'req> S: Service<&'req Query, Response = Res, Error = Err>,
+  {
+    let mut inner = self.inner.clone();
+    futures::future::poll_fn(|cx| Service::<&Query>::poll_ready(&mut inner, cx)).await?;
+    inner.call(query).await
+  }
+
+  pub async fn channel<Err>(&self) -> Result<ChannelInfo, Err>
+  where
+    for<'req> S: Service<&'req GetChannelQuery<ChannelContext>, Response = ChannelInfo, Error = Err>,
+  {
+    self
+      .call(&GetChannelQuery::new().set_context(self.context.clone()))
+      .await
+  }
+
+  pub async fn packages<Err>(&self) -> Result<PackageListing, Err>
+  where
+    for<'req> S: Service<&'req GetPackageListQuery<ChannelContext>, Response = PackageListing, Error = Err>,
+  {
+    self
+      .call(&GetPackageListQuery::new().set_context(self.context.clone()))
+      .await
+  }
+
+  pub async fn categories<Err>(&self) -> Result<CategoryListing, Err>
+  where
+    for<'req> S: Service<&'req GetCategoryListQuery<ChannelContext>, Response = CategoryListing, Error = Err>,
+  {
+    self
+      .call(&GetCategoryListQuery::new().set_context(self.context.clone()))
+      .await
+  }
+
+  pub async fn maintainers<Err>(&self) -> Result<MaintainerListing, Err>
+  where
+    for<'req> S: Service<&'req GetMaintainerListQuery<ChannelContext>, Response = MaintainerListing, Error = Err>,
+  {
+    self
+      .call(&GetMaintainerListQuery::new().set_context(self.context.clone()))
+      .await
+  }
+
+  pub async fn maintainer<Err>(&self, handle: &str) -> Result<MaintainerInfo, Err>
+  where
+    for<'req> S: Service<&'req GetMaintainerInfoQuery<ChannelContext>, Response = MaintainerInfo, Error = Err>,
+  {
+    let query = GetMaintainerInfoQuery::new(CompactString::new(handle)).set_context(self.context.clone());
+    self.call(&query).await
+  }
+
+  /// Download the archive of `release`, checking its size
+  pub async fn download<TyBody, Err>(&self, release: &Release) -> Result<ReleaseArchiveStream<TyBody>, Err>
+  where
+    for<'req> S:
+      Service<&'req DownloadReleaseQuery<ChannelContext>, Response = ReleaseArchiveStream<TyBody>, Error = Err>,
+  {
+    self
+      .call(&DownloadReleaseQuery::from_release(release).set_context(self.context.clone()))
+      .await
+  }
+}
+
+/// Package of a [`PearClient`] channel
+#[derive(Debug, Clone)]
+pub struct PackageClient<'a, S> {
+  client: &'a PearClient<S>,
+  name: CompactString,
+}
+
+impl<S: Clone> PackageClient<'_, S> {
+  pub fn name(&self) -> &str {
+    self.name.as_str()
+  }
+
+  pub async fn info<Err>(&self) -> Result<PackageInfo, Err>
+  where
+    for<'req> S: Service<&'req GetPackageInfoQuery<ChannelContext>, Response = PackageInfo, Error = Err>,
+  {
+    self
+      .client
+      .call(&GetPackageInfoQuery::new(self.name.clone()).set_context(self.context()))
+      .await
+  }
+
+  /// Maintainers of the package, with their role (`maintainers2.xml`)
+  pub async fn maintainers<Err>(&self) -> Result<PackageMaintainers, Err>
+  where
+    for<'req> S: Service<&'req GetPackageMaintainers2Query<ChannelContext>, Response = PackageMaintainers, Error = Err>,
+  {
+    self
+      .client
+      .call(&GetPackageMaintainers2Query::new(self.name.clone()).set_context(self.context()))
+      .await
+  }
+
+  pub async fn releases<Err>(&self) -> Result<ReleaseListing, Err>
+  where
+    for<'req> S: Service<&'req GetReleaseListQuery<ChannelContext>, Response = ReleaseListing, Error = Err>,
+  {
+    self
+      .client
+      .call(&GetReleaseListQuery::new(self.name.clone()).set_context(self.context()))
+      .await
+  }
+
+  /// Releases with their minimum PHP version (`allreleases2.xml`)
+  pub async fn releases2<Err>(&self) -> Result<ReleaseListing2, Err>
+  where
+    for<'req> S: Service<&'req GetReleaseList2Query<ChannelContext>, Response = ReleaseListing2, Error = Err>,
+  {
+    self
+      .client
+      .call(&GetReleaseList2Query::new(self.name.clone()).set_context(self.context()))
+      .await
+  }
+
+  /// Latest version with the provided stability, or the latest version overall for `None`
+  pub async fn latest<Err>(&self, stability: Option<Stability>) -> Result<PearVersion, Err>
+  where
+    for<'req> S: Service<&'req GetLatestVersionQuery<ChannelContext>, Response = PearVersion, Error = Err>,
+  {
+    let query = GetLatestVersionQuery::new(self.name.clone(), stability).set_context(self.context());
+    self.client.call(&query).await
+  }
+
+  pub async fn release<Err>(&self, version: &str) -> Result<Release, Err>
+  where
+    for<'req> S: Service<&'req GetReleaseQuery<ChannelContext>, Response = Release, Error = Err>,
+  {
+    let query = GetReleaseQuery::new(self.name.clone(), CompactString::new(version)).set_context(self.context());
+    self.client.call(&query).await
+  }
+
+  pub async fn release_v2<Err>(&self, version: &str) -> Result<ReleaseV2, Err>
+  where
+    for<'req> S: Service<&'req GetReleaseV2Query<ChannelContext>, Response = ReleaseV2, Error = Err>,
+  {
+    let query = GetReleaseV2Query::new(self.name.clone(), CompactString::new(version)).set_context(self.context());
+    self.client.call(&query).await
+  }
+
+  pub async fn package_xml<Err>(&self, version: &str) -> Result<PackageXml, Err>
+  where
+    for<'req> S: Service<&'req GetPackageXmlQuery<ChannelContext>, Response = PackageXml, Error = Err>,
+  {
+    let query = GetPackageXmlQuery::new(self.name.clone(), CompactString::new(version)).set_context(self.context());
+    self.client.call(&query).await
+  }
+
+  pub async fn dependencies<Err>(&self, version: &str) -> Result<ReleaseDependencies, Err>
+  where
+    for<'req> S:
+      Service<&'req GetReleaseDependenciesQuery<ChannelContext>, Response = ReleaseDependencies, Error = Err>,
+  {
+    let query =
+      GetReleaseDependenciesQuery::new(self.name.clone(), CompactString::new(version)).set_context(self.context());
+    self.client.call(&query).await
+  }
+
+  fn context(&self) -> ChannelContext {
+    self.client.context.clone()
+  }
+}
+
+/// Category of a [`PearClient`] channel
+#[derive(Debug, Clone)]
+pub struct CategoryClient<'a, S> {
+  client: &'a PearClient<S>,
+  name: CompactString,
+}
+
+impl<S: Clone> CategoryClient<'_, S> {
+  pub fn name(&self) -> &str {
+    self.name.as_str()
+  }
+
+  pub async fn info<Err>(&self) -> Result<CategoryInfo, Err>
+  where
+    for<'req> S: Service<&'req GetCategoryInfoQuery<ChannelContext>, Response = CategoryInfo, Error = Err>,
+  {
+    self
+      .client
+      .call(&GetCategoryInfoQuery::new(self.name.clone()).set_context(self.context()))
+      .await
+  }
+
+  pub async fn packages<Err>(&self) -> Result<CategoryPackageListing, Err>
+  where
+    for<'req> S:
+      Service<&'req GetCategoryPackageListQuery<ChannelContext>, Response = CategoryPackageListing, Error = Err>,
+  {
+    self
+      .client
+      .call(&GetCategoryPackageListQuery::new(self.name.clone()).set_context(self.context()))
+      .await
+  }
+
+  /// Info, releases and dependencies of all the packages in the category
+  pub async fn packages_info<Err>(&self) -> Result<CategoryPackagesInfo, Err>
+  where
+    for<'req> S:
+      Service<&'req GetCategoryPackagesInfoQuery<ChannelContext>, Response = CategoryPackagesInfo, Error = Err>,
+  {
+    self
+      .client
+      .call(&GetCategoryPackagesInfoQuery::new(self.name.clone()).set_context(self.context()))
+      .await
+  }
+
+  fn context(&self) -> ChannelContext {
+    self.client.context.clone()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::client::fixture::FixtureService;
+  use crate::client::http::HttpPearClientError;
+  use bytes::Bytes;
+  use futures::TryStreamExt;
+  use url::Url;
+
+  fn client() -> PearClient<HttpPearClient<FixtureService>> {
+    let inner = FixtureService::new([
+      (
+        "/rest/r/protobuf/allreleases.xml",
+        &include_bytes!("../../test-resources/get_release_list/pecl_protobuf/input.xml")[..],
+      ),
+      (
+        "/rest/r/protobuf/4.27.0.xml",
+        include_bytes!("../../test-resources/get_release/pecl_protobuf_4.27.0/input.xml"),
+      ),
+      ("/get/protobuf-4.27.0.tgz", b"archive"),
+    ]);
+    PearClient::http(inner, PearUrl(Url::parse("https://pecl.php.net/").unwrap()))
+  }
+
+  #[test]
+  pub fn test_pear_client() {
+    let client = client();
+    let package = client.package("protobuf");
+    futures::executor::block_on(async {
+      let releases = package.releases().await.unwrap();
+      assert_eq!(releases.package.as_str(), "protobuf");
+      let release = package.release(releases.items[0].version.as_str()).await.unwrap();
+      assert_eq!(release.version.as_str(), "4.27.0");
+      assert_eq!(package.info().await, Err(HttpPearClientError::NotFound));
+    });
+  }
+
+  #[test]
+  pub fn test_pear_client_download() {
+    let client = client();
+    futures::executor::block_on(async {
+      let mut release = client.package("protobuf").release("4.27.0").await.unwrap();
+      release.archive.size = 7;
+      let chunks: Vec<Bytes> = client.download(&release).await.unwrap().try_collect().await.unwrap();
+      assert_eq!(chunks.concat(), b"archive");
+
+      // the archive is downloaded from the release link
+      release.archive.link = Url::parse("https://pecl.php.net/get/missing-4.27.0").unwrap();
+      assert!(matches!(
+        client.download(&release).await,
+        Err(HttpPearClientError::NotFound)
+      ));
+    });
+  }
+}
@@ -0,0 +1,716 @@
+use crate::common::dependency::{ReleaseDependencies, ReleaseDependenciesFromPhpError};
+use crate::common::from_xml::FromXmlError;
+use crate::common::package::{PackageInfo, PackageInfoFromXmlNodeError};
+use crate::common::release::{ShortRelease, ShortReleaseFromXmlNodeError};
+use crate::common::version::PearVersion;
+use crate::xml_util::{find_root, get_link_attr, get_text, parse_rc_dom, RestXmlWriter};
+use compact_str::CompactString;
+use markup5ever_rcdom::{Node, NodeData, RcDom};
+
+/// List of all categories, from `/rest/c/categories.xml`
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CategoryListing<Str = CompactString> {
+  pub channel: Str,
+  pub items: Vec<CategoryRef<Str>>,
+}
+
+/// Category name, with the link to its `info.xml`
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CategoryRef<Str = CompactString> {
+  pub name: Str,
+  pub link: Str,
+}
+
+impl<Str: AsRef<str>> CategoryListing<Str> {
+  /// Write as a `categories.xml` document
+  pub fn to_xml(&self) -> String {
+    let mut writer = RestXmlWriter::new(
+      "a",
+      "http://pear.php.net/dtd/rest.allcategories",
+      "http://pear.php.net/dtd/rest.allcategories http://pear.php.net/dtd/rest.allcategories.xsd",
+    );
+    writer.unindented_text("ch", self.channel.as_ref());
+    for item in &self.items {
+      writer.link_text("c", item.link.as_ref(), item.name.as_ref());
+    }
+    writer.finish()
+  }
+}
+
+impl CategoryListing<CompactString> {
+  /// Read from an XML document, panics if the input is invalid.
+  ///
+  /// See [`Self::try_from_xml`] for the fallible version.
+  pub fn from_xml(input: &[u8]) -> Self {
+    Self::try_from_xml(input).unwrap()
+  }
+
+  pub fn try_from_xml(input: &[u8]) -> Result<Self, FromXmlError> {
+    let dom = parse_rc_dom(input)?;
+    Ok(Self::from_rc_dom(dom)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum CategoryListingFromRcDomError {
+  #[error("failed to find root node")]
+  RootNotFound,
+  #[error("failed to read listing from XML Node")]
+  Read(#[from] CategoryListingFromXmlNodeError),
+}
+
+impl CategoryListing<CompactString> {
+  pub fn from_rc_dom(dom: RcDom) -> Result<Self, CategoryListingFromRcDomError> {
+    let doc = dom.document;
+    let root = find_root(&doc, "a").map_err(|_| CategoryListingFromRcDomError::RootNotFound)?;
+    Ok(Self::from_xml_node(&root)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum CategoryListingFromXmlNodeError {
+  #[error("unexpected child node type at index {0}")]
+  ChildType(usize),
+  #[error("channel node <ch> is malformed at index {0}")]
+  ReadChannel(usize),
+  #[error("channel node <ch> is missing")]
+  MissingChannel,
+  #[error("channel node <ch> is duplicated")]
+  DuplicateChannel,
+  #[error("category node <c> is malformed at index {0}")]
+  ReadCategory(usize),
+  #[error("category node <c> is missing attribute `xlink:href` at index {0}")]
+  MissingCategoryLink(usize),
+}
+
+impl CategoryListing<CompactString> {
+  pub fn from_xml_node(node: &Node) -> Result<Self, CategoryListingFromXmlNodeError> {
+    use CategoryListingFromXmlNodeError as E;
+
+    let mut channel: Option<CompactString> = None;
+    let mut items: Vec<CategoryRef<CompactString>> = Vec::new();
+
+    for (i, handle) in node.children.borrow().iter().enumerate() {
+      let node: &Node = handle;
+      match &node.data {
+        NodeData::Element { name, attrs, .. } => {
+          if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("ch") {
+            let new = get_text(node).map_err(|_| E::ReadChannel(i))?;
+            let old = channel.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateChannel);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("c") {
+            if channel.is_none() {
+              return Err(E::MissingChannel);
+            }
+            let attrs = &*attrs.borrow();
+            let new_name = get_text(node).map_err(|_| E::ReadCategory(i))?;
+            let new_link = get_link_attr(attrs)
+              .map_err(|_| E::ReadCategory(i))?
+              .ok_or(E::MissingCategoryLink(i))?;
+            items.push(CategoryRef {
+              name: new_name,
+              link: CompactString::new(new_link.value.as_ref()),
+            });
+          } else {
+            return Err(E::ChildType(i));
+          }
+        }
+        NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+        _ => return Err(E::ChildType(i)),
+      }
+    }
+
+    Ok(Self {
+      channel: channel.ok_or(E::MissingChannel)?,
+      items,
+    })
+  }
+}
+
+/// Category information, from `/rest/c/{category}/info.xml`
+///
+/// <https://pear.php.net/dtd/rest.category.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CategoryInfo<Str = CompactString> {
+  pub name: Str,
+  pub channel: Str,
+  pub alias: Str,
+  pub description: Str,
+}
+
+impl<Str: AsRef<str>> CategoryInfo<Str> {
+  /// Write as a `/rest/c/{category}/info.xml` document
+  pub fn to_xml(&self) -> String {
+    let mut writer = RestXmlWriter::new(
+      "c",
+      "http://pear.php.net/dtd/rest.category",
+      "http://pear.php.net/dtd/rest.category http://pear.php.net/dtd/rest.category.xsd",
+    );
+    writer
+      .text("n", self.name.as_ref())
+      .text("c", self.channel.as_ref())
+      .text("a", self.alias.as_ref())
+      .text("d", self.description.as_ref());
+    writer.finish()
+  }
+}
+
+impl CategoryInfo<CompactString> {
+  /// Read from an XML document, panics if the input is invalid.
+  ///
+  /// See [`Self::try_from_xml`] for the fallible version.
+  pub fn from_xml(input: &[u8]) -> Self {
+    Self::try_from_xml(input).unwrap()
+  }
+
+  pub fn try_from_xml(input: &[u8]) -> Result<Self, FromXmlError> {
+    let dom = parse_rc_dom(input)?;
+    Ok(Self::from_rc_dom(dom)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum CategoryInfoFromRcDomError {
+  #[error("failed to find root node")]
+  RootNotFound,
+  #[error("failed to read category from XML Node")]
+  Read(#[from] CategoryInfoFromXmlNodeError),
+}
+
+impl CategoryInfo<CompactString> {
+  pub fn from_rc_dom(dom: RcDom) -> Result<Self, CategoryInfoFromRcDomError> {
+    let doc = dom.document;
+    let root = find_root(&doc, "c").map_err(|_| CategoryInfoFromRcDomError::RootNotFound)?;
+    Ok(Self::from_xml_node(&root)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum CategoryInfoFromXmlNodeError {
+  #[error("unexpected child node type at index {0}")]
+  ChildType(usize),
+  #[error("name node <n> is malformed at index {0}")]
+  ReadName(usize),
+  #[error("name node <n> is missing")]
+  MissingName,
+  #[error("name node <n> is duplicated")]
+  DuplicateName,
+  #[error("channel node <c> is malformed at index {0}")]
+  ReadChannel(usize),
+  #[error("channel node <c> is missing")]
+  MissingChannel,
+  #[error("channel node <c> is duplicated")]
+  DuplicateChannel,
+  #[error("alias node <a> is malformed at index {0}")]
+  ReadAlias(usize),
+  #[error("alias node <a> is missing")]
+  MissingAlias,
+  #[error("alias node <a> is duplicated")]
+  DuplicateAlias,
+  #[error("description node <d> is malformed at index {0}")]
+  ReadDescription(usize),
+  #[error("description node <d> is missing")]
+  MissingDescription,
+  #[error("description node <d> is duplicated")]
+  DuplicateDescription,
+}
+
+impl CategoryInfo<CompactString> {
+  pub fn from_xml_node(node: &Node) -> Result<Self, CategoryInfoFromXmlNodeError> {
+    use CategoryInfoFromXmlNodeError as E;
+
+    let mut category_name: Option<CompactString> = None;
+    let mut channel: Option<CompactString> = None;
+    let mut alias: Option<CompactString> = None;
+    let mut description: Option<CompactString> = None;
+
+    for (i, handle) in node.children.borrow().iter().enumerate() {
+      let node: &Node = handle;
+      match &node.data {
+        NodeData::Element { name, .. } => {
+          if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("n") {
+            let new = get_text(node).map_err(|_| E::ReadName(i))?;
+            let old = category_name.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateName);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("c") {
+            if category_name.is_none() {
+              return Err(E::MissingName);
+            }
+            let new = get_text(node).map_err(|_| E::ReadChannel(i))?;
+            let old = channel.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateChannel);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("a") {
+            if channel.is_none() {
+              return Err(E::MissingChannel);
+            }
+            let new = get_text(node).map_err(|_| E::ReadAlias(i))?;
+            let old = alias.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateAlias);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("d") {
+            if alias.is_none() {
+              return Err(E::MissingAlias);
+            }
+            let new = get_text(node).map_err(|_| E::ReadDescription(i))?;
+            let old = description.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateDescription);
+            }
+          } else {
+            return Err(E::ChildType(i));
+          }
+        }
+        NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+        _ => return Err(E::ChildType(i)),
+      }
+    }
+
+    Ok(Self {
+      name: category_name.ok_or(E::MissingName)?,
+      channel: channel.ok_or(E::MissingChannel)?,
+      alias: alias.ok_or(E::MissingAlias)?,
+      description: description.ok_or(E::MissingDescription)?,
+    })
+  }
+}
+
+/// List of the packages in a category, from `/rest/c/{category}/packages.xml`
+///
+/// <https://pear.php.net/dtd/rest.categorypackages.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CategoryPackageListing<Str = CompactString> {
+  pub items: Vec<CategoryPackageRef<Str>>,
+}
+
+/// Package name, with the link to its package info
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CategoryPackageRef<Str = CompactString> {
+  pub name: Str,
+  pub link: Str,
+}
+
+impl<Str: AsRef<str>> CategoryPackageListing<Str> {
+  /// Write as a `/rest/c/{category}/packages.xml` document
+  pub fn to_xml(&self) -> String {
+    let mut writer = RestXmlWriter::new(
+      "l",
+      "http://pear.php.net/dtd/rest.categorypackages",
+      "http://pear.php.net/dtd/rest.categorypackages http://pear.php.net/dtd/rest.categorypackages.xsd",
+    );
+    for item in &self.items {
+      writer.link_text("p", item.link.as_ref(), item.name.as_ref());
+    }
+    writer.finish()
+  }
+}
+
+impl CategoryPackageListing<CompactString> {
+  /// Read from an XML document, panics if the input is invalid.
+  ///
+  /// See [`Self::try_from_xml`] for the fallible version.
+  pub fn from_xml(input: &[u8]) -> Self {
+    Self::try_from_xml(input).unwrap()
+  }
+
+  pub fn try_from_xml(input: &[u8]) -> Result<Self, FromXmlError> {
+    let dom = parse_rc_dom(input)?;
+    Ok(Self::from_rc_dom(dom)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum CategoryPackageListingFromRcDomError {
+  #[error("failed to find root node")]
+  RootNotFound,
+  #[error("failed to read listing from XML Node")]
+  Read(#[from] CategoryPackageListingFromXmlNodeError),
+}
+
+impl CategoryPackageListing<CompactString> {
+  pub fn from_rc_dom(dom: RcDom) -> Result<Self, CategoryPackageListingFromRcDomError> {
+    let doc = dom.document;
+    let root = find_root(&doc, "l").map_err(|_| CategoryPackageListingFromRcDomError::RootNotFound)?;
+    Ok(Self::from_xml_node(&root)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum CategoryPackageListingFromXmlNodeError {
+  #[error("unexpected child node type at index {0}")]
+  ChildType(usize),
+  #[error("package node <p> is malformed at index {0}")]
+  ReadPackage(usize),
+  #[error("package node <p> is missing attribute `xlink:href` at index {0}")]
+  MissingPackageLink(usize),
+}
+
+impl CategoryPackageListing<CompactString> {
+  pub fn from_xml_node(node: &Node) -> Result<Self, CategoryPackageListingFromXmlNodeError> {
+    use CategoryPackageListingFromXmlNodeError as E;
+
+    let mut items: Vec<CategoryPackageRef<CompactString>> = Vec::new();
+
+    for (i, handle) in node.children.borrow().iter().enumerate() {
+      let node: &Node = handle;
+      match &node.data {
+        NodeData::Element { name, attrs, .. } if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("p") => {
+          let attrs = &*attrs.borrow();
+          let new_name = get_text(node).map_err(|_| E::ReadPackage(i))?;
+          let new_link = get_link_attr(attrs)
+            .map_err(|_| E::ReadPackage(i))?
+            .ok_or(E::MissingPackageLink(i))?;
+          items.push(CategoryPackageRef {
+            name: new_name,
+            link: CompactString::new(new_link.value.as_ref()),
+          });
+        }
+        NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+        _ => return Err(E::ChildType(i)),
+      }
+    }
+
+    Ok(Self { items })
+  }
+}
+
+/// Full information on the packages of a category, from `/rest/c/{category}/packagesinfo.xml`
+///
+/// <https://pear.php.net/dtd/rest.categorypackageinfo.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CategoryPackagesInfo<Str = CompactString> {
+  pub items: Vec<CategoryPackageInfo<Str>>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CategoryPackageInfo<Str = CompactString> {
+  pub info: PackageInfo<Str>,
+  pub releases: Vec<ShortRelease>,
+  /// Dependencies of each release
+  pub dependencies: Vec<CategoryReleaseDependencies<Str>>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CategoryReleaseDependencies<Str = CompactString> {
+  pub version: PearVersion,
+  pub dependencies: ReleaseDependencies<Str>,
+}
+
+impl<Str: AsRef<str>> CategoryPackagesInfo<Str> {
+  /// Write as a `/rest/c/{category}/packagesinfo.xml` document
+  pub fn to_xml(&self) -> String {
+    let mut writer = RestXmlWriter::new(
+      "f",
+      "http://pear.php.net/dtd/rest.categorypackageinfo",
+      "http://pear.php.net/dtd/rest.categorypackageinfo http://pear.php.net/dtd/rest.categorypackageinfo.xsd",
+    );
+    for item in &self.items {
+      writer.open("pi", &[]).open("p", &[]);
+      item.info.write_xml(&mut writer);
+      writer.close().open("a", &[]);
+      for release in &item.releases {
+        release.write_xml(&mut writer);
+      }
+      writer.close();
+      for deps in &item.dependencies {
+        let serialized = deps.dependencies.to_php_serialized();
+        writer
+          .open("deps", &[])
+          .text("v", deps.version.as_str())
+          .text("d", &String::from_utf8_lossy(&serialized))
+          .close();
+      }
+      writer.close();
+    }
+    writer.finish()
+  }
+}
+
+impl CategoryPackagesInfo<CompactString> {
+  /// Read from an XML document, panics if the input is invalid.
+  ///
+  /// See [`Self::try_from_xml`] for the fallible version.
+  pub fn from_xml(input: &[u8]) -> Self {
+    Self::try_from_xml(input).unwrap()
+  }
+
+  pub fn try_from_xml(input: &[u8]) -> Result<Self, FromXmlError> {
+    let dom = parse_rc_dom(input)?;
+    Ok(Self::from_rc_dom(dom)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum CategoryPackagesInfoFromRcDomError {
+  #[error("failed to find root node")]
+  RootNotFound,
+  #[error("failed to read packages info from XML Node")]
+  Read(#[from] CategoryPackagesInfoFromXmlNodeError),
+}
+
+impl CategoryPackagesInfo<CompactString> {
+  pub fn from_rc_dom(dom: RcDom) -> Result<Self, CategoryPackagesInfoFromRcDomError> {
+    let doc = dom.document;
+    let root = find_root(&doc, "f").map_err(|_| CategoryPackagesInfoFromRcDomError::RootNotFound)?;
+    Ok(Self::from_xml_node(&root)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum CategoryPackagesInfoFromXmlNodeError {
+  #[error("unexpected child node type at index {0}")]
+  ChildType(usize),
+  #[error("failed to read package info at index {0}")]
+  ReadPackageInfo(#[source] CategoryPackageInfoFromXmlNodeError, usize),
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum CategoryPackageInfoFromXmlNodeError {
+  #[error("unexpected child node type at index {0}")]
+  ChildType(usize),
+  #[error("failed to read package node <p> at index {1}")]
+  ReadPackage(#[source] PackageInfoFromXmlNodeError, usize),
+  #[error("package node <p> is missing")]
+  MissingPackage,
+  #[error("package node <p> is duplicated")]
+  DuplicatePackage,
+  #[error("releases node <a> is duplicated")]
+  DuplicateReleases,
+  #[error("failed to read release at index {1}")]
+  ReadRelease(#[source] ShortReleaseFromXmlNodeError, usize),
+  #[error("unexpected child node type in releases node <a> at index {0}")]
+  ReleasesChildType(usize),
+  #[error("dependencies node <deps> is malformed at index {0}")]
+  ReadDependencies(usize),
+  #[error("failed to decode dependencies at index {1}")]
+  DecodeDependencies(#[source] ReleaseDependenciesFromPhpError, usize),
+}
+
+impl CategoryPackagesInfo<CompactString> {
+  pub fn from_xml_node(node: &Node) -> Result<Self, CategoryPackagesInfoFromXmlNodeError> {
+    use CategoryPackagesInfoFromXmlNodeError as E;
+
+    let mut items: Vec<CategoryPackageInfo<CompactString>> = Vec::new();
+
+    for (i, handle) in node.children.borrow().iter().enumerate() {
+      let node: &Node = handle;
+      match &node.data {
+        NodeData::Element { name, .. } if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("pi") => {
+          let pi = CategoryPackageInfo::from_xml_node(node).map_err(|e| E::ReadPackageInfo(e, i))?;
+          items.push(pi);
+        }
+        NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+        _ => return Err(E::ChildType(i)),
+      }
+    }
+
+    Ok(Self { items })
+  }
+}
+
+impl CategoryPackageInfo<CompactString> {
+  pub fn from_xml_node(node: &Node) -> Result<Self, CategoryPackageInfoFromXmlNodeError> {
+    use CategoryPackageInfoFromXmlNodeError as E;
+
+    let mut info: Option<PackageInfo<CompactString>> = None;
+    let mut releases: Option<Vec<ShortRelease>> = None;
+    let mut dependencies: Vec<CategoryReleaseDependencies<CompactString>> = Vec::new();
+
+    for (i, handle) in node.children.borrow().iter().enumerate() {
+      let node: &Node = handle;
+      match &node.data {
+        NodeData::Element { name, .. } => {
+          if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("p") {
+            let new = PackageInfo::from_xml_node(node).map_err(|e| E::ReadPackage(e, i))?;
+            let old = info.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicatePackage);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("a") {
+            if info.is_none() {
+              return Err(E::MissingPackage);
+            }
+            let new = read_releases(node)?;
+            let old = releases.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateReleases);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("deps") {
+            if info.is_none() {
+              return Err(E::MissingPackage);
+            }
+            dependencies.push(read_dependencies(node, i)?);
+          } else {
+            return Err(E::ChildType(i));
+          }
+        }
+        NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+        _ => return Err(E::ChildType(i)),
+      }
+    }
+
+    Ok(Self {
+      info: info.ok_or(E::MissingPackage)?,
+      releases: releases.unwrap_or_default(),
+      dependencies,
+    })
+  }
+}
+
+/// Read the `<r>` releases of an embedded release listing, the package and channel nodes are ignored.
+fn read_releases(node: &Node) -> Result<Vec<ShortRelease>, CategoryPackageInfoFromXmlNodeError> {
+  use CategoryPackageInfoFromXmlNodeError as E;
+
+  let mut releases = Vec::new();
+  for (i, handle) in node.children.borrow().iter().enumerate() {
+    let node: &Node = handle;
+    match &node.data {
+      NodeData::Element { name, .. } => {
+        if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("r") {
+          releases.push(ShortRelease::from_xml_node(node).map_err(|e| E::ReadRelease(e, i))?);
+        } else if name.prefix.is_none()
+          && (name.local.eq_str_ignore_ascii_case("p") || name.local.eq_str_ignore_ascii_case("c"))
+        {
+          continue;
+        } else {
+          return Err(E::ReleasesChildType(i));
+        }
+      }
+      NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+      _ => return Err(E::ReleasesChildType(i)),
+    }
+  }
+  Ok(releases)
+}
+
+/// Read a `<deps>` node, holding the version `<v>` and PHP serialized dependencies `<d>`
+fn read_dependencies(
+  node: &Node,
+  index: usize,
+) -> Result<CategoryReleaseDependencies<CompactString>, CategoryPackageInfoFromXmlNodeError> {
+  use CategoryPackageInfoFromXmlNodeError as E;
+
+  let mut version: Option<CompactString> = None;
+  let mut serialized: Option<CompactString> = None;
+  for handle in node.children.borrow().iter() {
+    let node: &Node = handle;
+    match &node.data {
+      NodeData::Element { name, .. } => {
+        let target = if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("v") {
+          &mut version
+        } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("d") {
+          &mut serialized
+        } else {
+          return Err(E::ReadDependencies(index));
+        };
+        let new = get_text(node).map_err(|_| E::ReadDependencies(index))?;
+        if target.replace(new).is_some() {
+          return Err(E::ReadDependencies(index));
+        }
+      }
+      NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+      _ => return Err(E::ReadDependencies(index)),
+    }
+  }
+  let version = version.ok_or(E::ReadDependencies(index))?;
+  let version = PearVersion::new(version.as_str()).map_err(|_| E::ReadDependencies(index))?;
+  let serialized = serialized.ok_or(E::ReadDependencies(index))?;
+  let dependencies =
+    ReleaseDependencies::from_php_serialized(serialized.as_bytes()).map_err(|e| E::DecodeDependencies(e, index))?;
+  Ok(CategoryReleaseDependencies { version, dependencies })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  pub fn test_category_listing_from_xml() {
+    let input = include_bytes!("../../test-resources/get_category_list/pecl/input.xml");
+    let actual = CategoryListing::from_xml(input);
+    assert_eq!(actual.channel.as_str(), "pecl.php.net");
+    let tools = actual.items.iter().find(|c| c.name == "Tools and Utilities").unwrap();
+    assert_eq!(tools.link.as_str(), "/rest/c/Tools+and+Utilities/info.xml");
+  }
+
+  #[test]
+  pub fn test_category_info_from_xml() {
+    let input = include_bytes!("../../test-resources/get_category_info/pecl_tools_and_utilities/input.xml");
+    let actual = CategoryInfo::from_xml(input);
+    assert_eq!(actual.name.as_str(), "Tools and Utilities");
+    assert_eq!(actual.channel.as_str(), "pecl.php.net");
+  }
+
+  #[test]
+  pub fn test_category_package_listing_from_xml() {
+    let input = include_bytes!("../../test-resources/get_category_package_list/pecl_tools_and_utilities/input.xml");
+    let actual = CategoryPackageListing::from_xml(input);
+    assert!(actual
+      .items
+      .iter()
+      .any(|p| p.name == "protobuf" && p.link == "/rest/p/protobuf"));
+  }
+
+  #[test]
+  pub fn test_category_packages_info_from_xml() {
+    let input = include_bytes!("../../test-resources/get_category_packages_info/pecl_tools_and_utilities/input.xml");
+    let actual = CategoryPackagesInfo::from_xml(input);
+    assert_eq!(actual.items.len(), 2);
+    let protobuf = &actual.items[1];
+    assert_eq!(protobuf.info.name.as_str(), "protobuf");
+    assert_eq!(protobuf.releases.len(), 2);
+    assert_eq!(protobuf.dependencies[0].version.as_str(), "4.27.0");
+    assert_eq!(
+      protobuf.dependencies[0].dependencies.required.php.min.as_deref(),
+      Some("8.0.0")
+    );
+  }
+
+  #[test]
+  pub fn test_categories_to_xml() {
+    let input = include_bytes!("../../test-resources/get_category_list/pecl/input.xml");
+    let listing = CategoryListing::from_xml(input);
+    assert_eq!(CategoryListing::from_xml(listing.to_xml().as_bytes()), listing);
+
+    let input = include_bytes!("../../test-resources/get_category_info/pecl_tools_and_utilities/input.xml");
+    let info = CategoryInfo::from_xml(input);
+    assert_eq!(CategoryInfo::from_xml(info.to_xml().as_bytes()), info);
+
+    let input = include_bytes!("../../test-resources/get_category_package_list/pecl_tools_and_utilities/input.xml");
+    let packages = CategoryPackageListing::from_xml(input);
+    assert_eq!(CategoryPackageListing::from_xml(packages.to_xml().as_bytes()), packages);
+  }
+
+  #[test]
+  pub fn test_category_packages_info_to_xml() {
+    let input = include_bytes!("../../test-resources/get_category_packages_info/pecl_tools_and_utilities/input.xml");
+    let info = CategoryPackagesInfo::from_xml(input);
+    let actual = info.to_xml();
+    assert!(actual.contains("\n <pi>\n  <p>\n   <n>protobuf</n>\n"));
+    assert!(actual.contains("\n  <a>\n   <r><v>4.27.0</v><s>stable</s></r>\n"));
+    assert_eq!(CategoryPackagesInfo::from_xml(actual.as_bytes()), info);
+  }
+}
@@ -0,0 +1,533 @@
+use crate::common::from_xml::FromXmlError;
+use crate::context::PearUrl;
+use crate::xml_util::{find_root, get_plain_attr, get_text, parse_rc_dom, RestXmlWriter};
+use compact_str::CompactString;
+use markup5ever_rcdom::{Node, NodeData, RcDom};
+use url::Url;
+use xml5ever::Attribute;
+
+/// Channel definition, from `channel.xml`
+///
+/// <https://pear.php.net/dtd/channel-1.0.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChannelInfo<Str = CompactString> {
+  /// Channel name, this is also the host of the primary server
+  pub name: Str,
+  pub suggested_alias: Option<Str>,
+  pub summary: Str,
+  pub local_alias: Option<Str>,
+  /// Package validation class used by the installer
+  pub validate_package: Option<ValidatePackage<Str>>,
+  pub primary: ChannelServer<Str>,
+  pub mirrors: Vec<ChannelMirror<Str>>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ValidatePackage<Str = CompactString> {
+  pub name: Str,
+  pub version: Option<Str>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChannelServer<Str = CompactString> {
+  pub port: Option<u16>,
+  pub ssl: bool,
+  /// REST base URLs, one per supported protocol version
+  pub rest: Vec<RestBaseUrl<Str>>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChannelMirror<Str = CompactString> {
+  pub host: Str,
+  pub server: ChannelServer<Str>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RestBaseUrl<Str = CompactString> {
+  /// Protocol version, e.g. `REST1.0`
+  pub protocol: Str,
+  pub url: Str,
+}
+
+impl<Str: AsRef<str>> ChannelServer<Str> {
+  /// Get the REST base URL for the provided protocol version (e.g. `REST1.0`)
+  pub fn rest_base_url(&self, protocol: &str) -> Option<&Str> {
+    self
+      .rest
+      .iter()
+      .find(|b| b.protocol.as_ref().eq_ignore_ascii_case(protocol))
+      .map(|b| &b.url)
+  }
+}
+
+impl<Str: AsRef<str>> ChannelInfo<Str> {
+  /// Get the REST base URL of the primary server for the provided protocol version (e.g. `REST1.0`)
+  pub fn rest_base_url(&self, protocol: &str) -> Option<&Str> {
+    self.primary.rest_base_url(protocol)
+  }
+
+  /// Get the channel URL to use in the request context.
+  ///
+  /// The REST queries append `rest/...` to the channel URL, so this is derived from the `REST1.0` base URL of the
+  /// primary server by removing its last `rest` segment. Returns `None` if there is no such base URL.
+  pub fn pear_url(&self) -> Option<PearUrl> {
+    let base = self.rest_base_url("REST1.0")?;
+    let mut url = Url::parse(base.as_ref()).ok()?;
+    if url.path_segments()?.rfind(|s| !s.is_empty()) != Some("rest") {
+      return None;
+    }
+    {
+      let mut segments = url.path_segments_mut().ok()?;
+      segments.pop_if_empty();
+      segments.pop();
+      segments.push("");
+    }
+    Some(PearUrl(url))
+  }
+}
+
+impl<Str: AsRef<str>> ChannelInfo<Str> {
+  /// Write as a `channel.xml` document
+  pub fn to_xml(&self) -> String {
+    let mut writer = RestXmlWriter::with_root_attrs(
+      "channel",
+      &[
+        ("version", "1.0"),
+        ("xmlns", "http://pear.php.net/channel-1.0"),
+        ("xmlns:xsi", "http://www.w3.org/2001/XMLSchema-instance"),
+        (
+          "xsi:schemaLocation",
+          "http://pear.php.net/channel-1.0 http://pear.php.net/dtd/channel-1.0.xsd",
+        ),
+      ],
+    );
+    writer.text("name", self.name.as_ref());
+    if let Some(alias) = &self.suggested_alias {
+      writer.text("suggestedalias", alias.as_ref());
+    }
+    writer.text("summary", self.summary.as_ref());
+    if let Some(alias) = &self.local_alias {
+      writer.text("localalias", alias.as_ref());
+    }
+    if let Some(validate) = &self.validate_package {
+      match &validate.version {
+        Some(version) => writer.text_with_attrs(
+          "validatepackage",
+          &[("version", version.as_ref())],
+          validate.name.as_ref(),
+        ),
+        None => writer.text("validatepackage", validate.name.as_ref()),
+      };
+    }
+    writer.open("servers", &[]);
+    self.primary.write_xml(&mut writer, "primary", None);
+    for mirror in &self.mirrors {
+      mirror
+        .server
+        .write_xml(&mut writer, "mirror", Some(mirror.host.as_ref()));
+    }
+    writer.finish()
+  }
+}
+
+impl<Str: AsRef<str>> ChannelServer<Str> {
+  /// Write the `<primary>` or `<mirror>` node
+  fn write_xml(&self, writer: &mut RestXmlWriter, name: &'static str, host: Option<&str>) {
+    let port = self.port.map(|port| port.to_string());
+    let mut attrs = Vec::new();
+    if let Some(host) = host {
+      attrs.push(("host", host));
+    }
+    if let Some(port) = &port {
+      attrs.push(("port", port.as_str()));
+    }
+    if self.ssl {
+      attrs.push(("ssl", "yes"));
+    }
+    writer.open(name, &attrs).open("rest", &[]);
+    for base_url in &self.rest {
+      writer.text_with_attrs(
+        "baseurl",
+        &[("type", base_url.protocol.as_ref())],
+        base_url.url.as_ref(),
+      );
+    }
+    writer.close().close();
+  }
+}
+
+impl ChannelInfo<CompactString> {
+  /// Read from an XML document, panics if the input is invalid.
+  ///
+  /// See [`Self::try_from_xml`] for the fallible version.
+  pub fn from_xml(input: &[u8]) -> Self {
+    Self::try_from_xml(input).unwrap()
+  }
+
+  pub fn try_from_xml(input: &[u8]) -> Result<Self, FromXmlError> {
+    let dom = parse_rc_dom(input)?;
+    Ok(Self::from_rc_dom(dom)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum ChannelInfoFromRcDomError {
+  #[error("failed to find root node")]
+  RootNotFound,
+  #[error("failed to read channel from XML Node")]
+  Read(#[from] ChannelInfoFromXmlNodeError),
+}
+
+impl ChannelInfo<CompactString> {
+  pub fn from_rc_dom(dom: RcDom) -> Result<Self, ChannelInfoFromRcDomError> {
+    let doc = dom.document;
+    let root = find_root(&doc, "channel").map_err(|_| ChannelInfoFromRcDomError::RootNotFound)?;
+    Ok(Self::from_xml_node(&root)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum ChannelInfoFromXmlNodeError {
+  #[error("unexpected child node type at index {0}")]
+  ChildType(usize),
+  #[error("name node <name> is malformed at index {0}")]
+  ReadName(usize),
+  #[error("name node <name> is missing")]
+  MissingName,
+  #[error("name node <name> is duplicated")]
+  DuplicateName,
+  #[error("suggested alias node <suggestedalias> is malformed at index {0}")]
+  ReadSuggestedAlias(usize),
+  #[error("suggested alias node <suggestedalias> is duplicated")]
+  DuplicateSuggestedAlias,
+  #[error("summary node <summary> is malformed at index {0}")]
+  ReadSummary(usize),
+  #[error("summary node <summary> is missing")]
+  MissingSummary,
+  #[error("summary node <summary> is duplicated")]
+  DuplicateSummary,
+  #[error("local alias node <localalias> is malformed at index {0}")]
+  ReadLocalAlias(usize),
+  #[error("local alias node <localalias> is duplicated")]
+  DuplicateLocalAlias,
+  #[error("validate package node <validatepackage> is malformed at index {0}")]
+  ReadValidatePackage(usize),
+  #[error("validate package node <validatepackage> is duplicated")]
+  DuplicateValidatePackage,
+  #[error("failed to read servers at index {1}")]
+  ReadServers(#[source] ChannelServersFromXmlNodeError, usize),
+  #[error("servers node <servers> is missing")]
+  MissingServers,
+  #[error("servers node <servers> is duplicated")]
+  DuplicateServers,
+}
+
+impl ChannelInfo<CompactString> {
+  pub fn from_xml_node(node: &Node) -> Result<Self, ChannelInfoFromXmlNodeError> {
+    use ChannelInfoFromXmlNodeError as E;
+
+    let mut channel_name: Option<CompactString> = None;
+    let mut suggested_alias: Option<CompactString> = None;
+    let mut summary: Option<CompactString> = None;
+    let mut local_alias: Option<CompactString> = None;
+    let mut validate_package: Option<ValidatePackage<CompactString>> = None;
+    let mut servers: Option<(ChannelServer<CompactString>, Vec<ChannelMirror<CompactString>>)> = None;
+
+    for (i, handle) in node.children.borrow().iter().enumerate() {
+      let node: &Node = handle;
+      match &node.data {
+        NodeData::Element { name, attrs, .. } => {
+          if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("name") {
+            let new = get_text(node).map_err(|_| E::ReadName(i))?;
+            let old = channel_name.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateName);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("suggestedalias") {
+            if channel_name.is_none() {
+              return Err(E::MissingName);
+            }
+            let new = get_text(node).map_err(|_| E::ReadSuggestedAlias(i))?;
+            let old = suggested_alias.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateSuggestedAlias);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("summary") {
+            if channel_name.is_none() {
+              // we still check `name` since `suggestedalias` is optional
+              return Err(E::MissingName);
+            }
+            let new = get_text(node).map_err(|_| E::ReadSummary(i))?;
+            let old = summary.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateSummary);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("localalias") {
+            if summary.is_none() {
+              return Err(E::MissingSummary);
+            }
+            let new = get_text(node).map_err(|_| E::ReadLocalAlias(i))?;
+            let old = local_alias.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateLocalAlias);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("validatepackage") {
+            if summary.is_none() {
+              return Err(E::MissingSummary);
+            }
+            let attrs = &*attrs.borrow();
+            let new_name = get_text(node).map_err(|_| E::ReadValidatePackage(i))?;
+            let new_version = get_plain_attr(attrs, "version").map_err(|_| E::ReadValidatePackage(i))?;
+            let new = ValidatePackage {
+              name: new_name,
+              version: new_version.map(|a| CompactString::new(a.value.as_ref())),
+            };
+            let old = validate_package.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateValidatePackage);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("servers") {
+            if summary.is_none() {
+              return Err(E::MissingSummary);
+            }
+            let new = read_servers(node).map_err(|e| E::ReadServers(e, i))?;
+            let old = servers.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateServers);
+            }
+          } else {
+            return Err(E::ChildType(i));
+          }
+        }
+        NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+        _ => return Err(E::ChildType(i)),
+      }
+    }
+
+    let (primary, mirrors) = servers.ok_or(E::MissingServers)?;
+    Ok(Self {
+      name: channel_name.ok_or(E::MissingName)?,
+      suggested_alias,
+      summary: summary.ok_or(E::MissingSummary)?,
+      local_alias,
+      validate_package,
+      primary,
+      mirrors,
+    })
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum ChannelServersFromXmlNodeError {
+  #[error("unexpected child node type at index {0}")]
+  ChildType(usize),
+  #[error("failed to read primary server at index {1}")]
+  ReadPrimary(#[source] ChannelServerFromXmlNodeError, usize),
+  #[error("primary server node <primary> is missing")]
+  MissingPrimary,
+  #[error("primary server node <primary> is duplicated")]
+  DuplicatePrimary,
+  #[error("failed to read mirror at index {1}")]
+  ReadMirror(#[source] ChannelServerFromXmlNodeError, usize),
+  #[error("mirror node <mirror> has a malformed `host` attribute at index {0}")]
+  ReadMirrorHost(usize),
+  #[error("mirror node <mirror> is missing the `host` attribute at index {0}")]
+  MissingMirrorHost(usize),
+}
+
+fn read_servers(
+  node: &Node,
+) -> Result<(ChannelServer<CompactString>, Vec<ChannelMirror<CompactString>>), ChannelServersFromXmlNodeError> {
+  use ChannelServersFromXmlNodeError as E;
+
+  let mut primary: Option<ChannelServer<CompactString>> = None;
+  let mut mirrors: Vec<ChannelMirror<CompactString>> = Vec::new();
+
+  for (i, handle) in node.children.borrow().iter().enumerate() {
+    let node: &Node = handle;
+    match &node.data {
+      NodeData::Element { name, attrs, .. } => {
+        let attrs = &*attrs.borrow();
+        if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("primary") {
+          let new = ChannelServer::from_xml_node(node, attrs).map_err(|e| E::ReadPrimary(e, i))?;
+          let old = primary.replace(new);
+          if old.is_some() {
+            return Err(E::DuplicatePrimary);
+          }
+        } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("mirror") {
+          if primary.is_none() {
+            return Err(E::MissingPrimary);
+          }
+          let host = get_plain_attr(attrs, "host")
+            .map_err(|_| E::ReadMirrorHost(i))?
+            .ok_or(E::MissingMirrorHost(i))?;
+          let server = ChannelServer::from_xml_node(node, attrs).map_err(|e| E::ReadMirror(e, i))?;
+          mirrors.push(ChannelMirror {
+            host: CompactString::new(host.value.as_ref()),
+            server,
+          });
+        } else {
+          return Err(E::ChildType(i));
+        }
+      }
+      NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+      _ => return Err(E::ChildType(i)),
+    }
+  }
+
+  Ok((primary.ok_or(E::MissingPrimary)?, mirrors))
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum ChannelServerFromXmlNodeError {
+  #[error("unexpected child node type at index {0}")]
+  ChildType(usize),
+  #[error("attribute `port` is malformed")]
+  ReadPort,
+  #[error("attribute `ssl` is malformed")]
+  ReadSsl,
+  #[error("rest node <rest> is duplicated")]
+  DuplicateRest,
+  #[error("base url node <baseurl> is malformed at index {0}")]
+  ReadBaseUrl(usize),
+  #[error("base url node <baseurl> is missing the `type` attribute at index {0}")]
+  MissingBaseUrlType(usize),
+  #[error("unexpected node in <rest> at index {0}")]
+  RestChildType(usize),
+}
+
+impl ChannelServer<CompactString> {
+  /// Read a `<primary>` or `<mirror>` server node
+  pub fn from_xml_node(node: &Node, attrs: &[Attribute]) -> Result<Self, ChannelServerFromXmlNodeError> {
+    use ChannelServerFromXmlNodeError as E;
+
+    let port = match get_plain_attr(attrs, "port").map_err(|_| E::ReadPort)? {
+      None => None,
+      Some(port) => Some(port.value.parse::<u16>().map_err(|_| E::ReadPort)?),
+    };
+    let ssl = match get_plain_attr(attrs, "ssl").map_err(|_| E::ReadSsl)? {
+      None => false,
+      Some(ssl) => match ssl.value.as_ref() {
+        "yes" => true,
+        "no" => false,
+        _ => return Err(E::ReadSsl),
+      },
+    };
+    let mut rest: Option<Vec<RestBaseUrl<CompactString>>> = None;
+
+    for (i, handle) in node.children.borrow().iter().enumerate() {
+      let node: &Node = handle;
+      match &node.data {
+        NodeData::Element { name, .. } => {
+          if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("rest") {
+            let new = read_rest(node)?;
+            let old = rest.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateRest);
+            }
+          } else if name.prefix.is_none()
+            && (name.local.eq_str_ignore_ascii_case("xmlrpc") || name.local.eq_str_ignore_ascii_case("soap"))
+          {
+            // legacy protocols, not supported by this client
+            continue;
+          } else {
+            return Err(E::ChildType(i));
+          }
+        }
+        NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+        _ => return Err(E::ChildType(i)),
+      }
+    }
+
+    Ok(Self {
+      port,
+      ssl,
+      rest: rest.unwrap_or_default(),
+    })
+  }
+}
+
+fn read_rest(node: &Node) -> Result<Vec<RestBaseUrl<CompactString>>, ChannelServerFromXmlNodeError> {
+  use ChannelServerFromXmlNodeError as E;
+
+  let mut items: Vec<RestBaseUrl<CompactString>> = Vec::new();
+  for (i, handle) in node.children.borrow().iter().enumerate() {
+    let node: &Node = handle;
+    match &node.data {
+      NodeData::Element { name, attrs, .. }
+        if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("baseurl") =>
+      {
+        let attrs = &*attrs.borrow();
+        let protocol = get_plain_attr(attrs, "type")
+          .map_err(|_| E::ReadBaseUrl(i))?
+          .ok_or(E::MissingBaseUrlType(i))?;
+        let url = get_text(node).map_err(|_| E::ReadBaseUrl(i))?;
+        items.push(RestBaseUrl {
+          protocol: CompactString::new(protocol.value.as_ref()),
+          url,
+        });
+      }
+      NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+      _ => return Err(E::RestChildType(i)),
+    }
+  }
+  Ok(items)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  pub fn test_channel_info_from_xml() {
+    let input = include_bytes!("../../test-resources/get_channel/pecl/input.xml");
+    let actual = ChannelInfo::from_xml(input);
+    assert_eq!(actual.name.as_str(), "pecl.php.net");
+    assert_eq!(actual.suggested_alias.as_deref(), Some("pecl"));
+    assert_eq!(actual.primary.rest.len(), 3);
+    assert_eq!(
+      actual.rest_base_url("REST1.3").map(|u| u.as_str()),
+      Some("https://pecl.php.net/rest/")
+    );
+    assert_eq!(
+      actual.pear_url().map(|u| u.0.to_string()).as_deref(),
+      Some("https://pecl.php.net/")
+    );
+  }
+
+  #[test]
+  pub fn test_channel_info_to_xml() {
+    let input = include_bytes!("../../test-resources/get_channel/pecl/input.xml");
+    let channel = ChannelInfo::from_xml(input);
+    assert_eq!(ChannelInfo::from_xml(channel.to_xml().as_bytes()), channel);
+
+    let mirrored = ChannelInfo {
+      local_alias: Some(CompactString::new("p")),
+      validate_package: Some(ValidatePackage {
+        name: CompactString::new("PEAR_Validate"),
+        version: None,
+      }),
+      mirrors: vec![ChannelMirror {
+        host: CompactString::new("mirror.example.com"),
+        server: ChannelServer {
+          port: Some(8443),
+          ssl: true,
+          rest: channel.primary.rest.clone(),
+        },
+      }],
+      ..channel
+    };
+    let actual = mirrored.to_xml();
+    assert!(actual.contains("\n  <mirror host=\"mirror.example.com\" port=\"8443\" ssl=\"yes\">\n   <rest>\n"));
+    assert_eq!(ChannelInfo::from_xml(actual.as_bytes()), mirrored);
+  }
+}
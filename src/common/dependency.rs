@@ -0,0 +1,843 @@
+use crate::common::package_xml::{Elements, PackageXmlFromXmlNodeError};
+use crate::common::requirement::{VersionComparator, VersionOp, VersionRequirement};
+use crate::common::version::{InvalidPearVersion, PearVersion};
+use crate::php_serialize::{serialize, unserialize, PhpKey, PhpUnserializeError, PhpValue};
+use compact_str::CompactString;
+use markup5ever_rcdom::Node;
+
+/// Dependencies of a release, from the `<dependencies>` node of `package.xml` v2
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct PackageDependencies<Str = CompactString> {
+  pub required: RequiredDependencies<Str>,
+  pub optional: OptionalDependencies<Str>,
+  /// Named groups of optional dependencies, installed together
+  pub groups: Vec<DependencyGroup<Str>>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct RequiredDependencies<Str = CompactString> {
+  /// PHP version constraint
+  pub php: VersionConstraint<Str>,
+  /// PEAR installer version constraint
+  pub pear_installer: VersionConstraint<Str>,
+  pub packages: Vec<PackageDependency<Str>>,
+  pub subpackages: Vec<PackageDependency<Str>>,
+  pub extensions: Vec<ExtensionDependency<Str>>,
+  pub os: Vec<OsDependency<Str>>,
+  pub arch: Vec<ArchDependency<Str>>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct OptionalDependencies<Str = CompactString> {
+  pub packages: Vec<PackageDependency<Str>>,
+  pub subpackages: Vec<PackageDependency<Str>>,
+  pub extensions: Vec<ExtensionDependency<Str>>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DependencyGroup<Str = CompactString> {
+  pub name: Str,
+  pub hint: Str,
+  pub packages: Vec<PackageDependency<Str>>,
+  pub subpackages: Vec<PackageDependency<Str>>,
+  pub extensions: Vec<ExtensionDependency<Str>>,
+}
+
+/// Dependencies of a release, from `deps.{version}.txt`
+///
+/// This is the same data as the `<dependencies>` node of `package.xml`.
+pub type ReleaseDependencies<Str = CompactString> = PackageDependencies<Str>;
+
+/// Version bounds, as used by PEAR dependencies
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VersionConstraint<Str = CompactString> {
+  /// Minimum version (inclusive)
+  pub min: Option<Str>,
+  /// Maximum version (inclusive)
+  pub max: Option<Str>,
+  /// Recommended version, the installer only accepts this version unless forced
+  pub recommended: Option<Str>,
+  /// Excluded versions
+  pub exclude: Vec<Str>,
+}
+
+impl<Str: AsRef<str>> VersionConstraint<Str> {
+  /// Convert to a [`VersionRequirement`]
+  ///
+  /// The `recommended` version is an exact requirement, as for the PEAR installer without `--force`.
+  pub fn to_requirement(&self) -> Result<VersionRequirement, InvalidPearVersion> {
+    let mut comparators = Vec::new();
+    if let Some(min) = &self.min {
+      comparators.push(VersionComparator::new(VersionOp::Ge, PearVersion::new(min.as_ref())?));
+    }
+    if let Some(max) = &self.max {
+      comparators.push(VersionComparator::new(VersionOp::Le, PearVersion::new(max.as_ref())?));
+    }
+    if let Some(recommended) = &self.recommended {
+      comparators.push(VersionComparator::new(
+        VersionOp::Eq,
+        PearVersion::new(recommended.as_ref())?,
+      ));
+    }
+    for exclude in self.exclude.iter() {
+      comparators.push(VersionComparator::new(
+        VersionOp::Ne,
+        PearVersion::new(exclude.as_ref())?,
+      ));
+    }
+    Ok(VersionRequirement {
+      alternatives: vec![comparators],
+    })
+  }
+}
+
+impl<Str> Default for VersionConstraint<Str> {
+  fn default() -> Self {
+    Self {
+      min: None,
+      max: None,
+      recommended: None,
+      exclude: Vec::new(),
+    }
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PackageDependency<Str = CompactString> {
+  pub name: Str,
+  /// Channel of the dependency, `None` for URI-based dependencies
+  pub channel: Option<Str>,
+  /// Static URI of the dependency, for packages outside of a channel
+  pub uri: Option<Str>,
+  pub version: VersionConstraint<Str>,
+  /// The dependency must _not_ be installed
+  pub conflicts: bool,
+  /// Extension name provided by the dependency
+  pub provides_extension: Option<Str>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExtensionDependency<Str = CompactString> {
+  pub name: Str,
+  pub version: VersionConstraint<Str>,
+  /// The extension must _not_ be loaded
+  pub conflicts: bool,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OsDependency<Str = CompactString> {
+  pub name: Str,
+  /// The release must _not_ be installed on this OS
+  pub conflicts: bool,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ArchDependency<Str = CompactString> {
+  /// Architecture pattern, as matched by `OS_Guess`
+  pub pattern: Str,
+  /// The release must _not_ be installed on this architecture
+  pub conflicts: bool,
+}
+
+impl PackageDependencies<CompactString> {
+  pub fn from_xml_node(node: &Node) -> Result<Self, PackageXmlFromXmlNodeError> {
+    let children = Elements::new(node, "dependencies")?;
+    children.check_names(&["required", "optional", "group"])?;
+    let required = children.required("required")?;
+    let optional = children.optional("optional")?;
+    let mut groups = Vec::new();
+    for group in children.all("group") {
+      groups.push(DependencyGroup {
+        name: group.required_attr("name")?,
+        hint: group.required_attr("hint")?,
+        ..DependencyGroup::from_lists(&group.elements()?)?
+      });
+    }
+    Ok(Self {
+      required: RequiredDependencies::from_xml_node(&required.handle)?,
+      optional: match optional {
+        Some(optional) => {
+          let lists = DependencyGroup::from_lists(&optional.elements()?)?;
+          OptionalDependencies {
+            packages: lists.packages,
+            subpackages: lists.subpackages,
+            extensions: lists.extensions,
+          }
+        }
+        None => OptionalDependencies::default(),
+      },
+      groups,
+    })
+  }
+}
+
+impl RequiredDependencies<CompactString> {
+  fn from_xml_node(node: &Node) -> Result<Self, PackageXmlFromXmlNodeError> {
+    let children = Elements::new(node, "required")?;
+    children.check_names(&[
+      "php",
+      "pearinstaller",
+      "package",
+      "subpackage",
+      "extension",
+      "arch",
+      "os",
+    ])?;
+    let php = VersionConstraint::from_xml_node(&children.required("php")?.elements()?)?;
+    let pear_installer = VersionConstraint::from_xml_node(&children.required("pearinstaller")?.elements()?)?;
+    let lists = DependencyGroup::from_lists(&children)?;
+    let mut os = Vec::new();
+    for child in children.all("os") {
+      let child = child.elements()?;
+      child.check_names(&["name", "conflicts"])?;
+      os.push(OsDependency {
+        name: child.required_text("name")?,
+        conflicts: child.optional("conflicts")?.is_some(),
+      });
+    }
+    let mut arch = Vec::new();
+    for child in children.all("arch") {
+      let child = child.elements()?;
+      child.check_names(&["pattern", "conflicts"])?;
+      arch.push(ArchDependency {
+        pattern: child.required_text("pattern")?,
+        conflicts: child.optional("conflicts")?.is_some(),
+      });
+    }
+    Ok(Self {
+      php,
+      pear_installer,
+      packages: lists.packages,
+      subpackages: lists.subpackages,
+      extensions: lists.extensions,
+      os,
+      arch,
+    })
+  }
+}
+
+impl DependencyGroup<CompactString> {
+  /// Read the `<package>`, `<subpackage>` and `<extension>` lists of a dependency node
+  fn from_lists(children: &Elements) -> Result<Self, PackageXmlFromXmlNodeError> {
+    let mut packages = Vec::new();
+    for child in children.all("package") {
+      packages.push(PackageDependency::from_xml_node(&child.elements()?)?);
+    }
+    let mut subpackages = Vec::new();
+    for child in children.all("subpackage") {
+      subpackages.push(PackageDependency::from_xml_node(&child.elements()?)?);
+    }
+    let mut extensions = Vec::new();
+    for child in children.all("extension") {
+      let child = child.elements()?;
+      child.check_names(&["name", "min", "max", "recommended", "exclude", "conflicts"])?;
+      extensions.push(ExtensionDependency {
+        name: child.required_text("name")?,
+        version: VersionConstraint::from_xml_node(&child)?,
+        conflicts: child.optional("conflicts")?.is_some(),
+      });
+    }
+    Ok(Self {
+      name: CompactString::default(),
+      hint: CompactString::default(),
+      packages,
+      subpackages,
+      extensions,
+    })
+  }
+}
+
+impl PackageDependency<CompactString> {
+  fn from_xml_node(children: &Elements) -> Result<Self, PackageXmlFromXmlNodeError> {
+    children.check_names(&[
+      "name",
+      "channel",
+      "uri",
+      "min",
+      "max",
+      "recommended",
+      "exclude",
+      "nodefault",
+      "conflicts",
+      "providesextension",
+    ])?;
+    Ok(Self {
+      name: children.required_text("name")?,
+      channel: children.optional_text("channel")?,
+      uri: children.optional_text("uri")?,
+      version: VersionConstraint::from_xml_node(children)?,
+      conflicts: children.optional("conflicts")?.is_some(),
+      provides_extension: children.optional_text("providesextension")?,
+    })
+  }
+}
+
+impl VersionConstraint<CompactString> {
+  fn from_xml_node(children: &Elements) -> Result<Self, PackageXmlFromXmlNodeError> {
+    let mut exclude = Vec::new();
+    for child in children.all("exclude") {
+      exclude.push(child.text()?);
+    }
+    Ok(Self {
+      min: children.optional_text("min")?,
+      max: children.optional_text("max")?,
+      recommended: children.optional_text("recommended")?,
+      exclude,
+    })
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum ReleaseDependenciesFromPhpError {
+  #[error("failed to unserialize PHP value")]
+  Unserialize(#[from] PhpUnserializeError),
+  #[error("unexpected value at `{0}`")]
+  Value(CompactString),
+  #[error("missing value at `{0}`")]
+  Missing(CompactString),
+}
+
+impl PackageDependencies<CompactString> {
+  /// Read dependencies from a PHP serialized value, as found in `deps.{version}.txt`.
+  ///
+  /// Both the `package.xml` v2 array format and the legacy `package.xml` v1 list format are supported. Releases
+  /// without dependencies are serialized as `false`.
+  pub fn from_php_serialized(input: &[u8]) -> Result<Self, ReleaseDependenciesFromPhpError> {
+    // ignore trailing whitespace, such as a final newline
+    let end = input
+      .iter()
+      .rposition(|b| !b.is_ascii_whitespace())
+      .map_or(0, |i| i + 1);
+    let value = unserialize(&input[..end])?;
+    Self::from_php_value(&value)
+  }
+
+  pub fn from_php_value(value: &PhpValue) -> Result<Self, ReleaseDependenciesFromPhpError> {
+    use ReleaseDependenciesFromPhpError as E;
+
+    match value {
+      PhpValue::Bool(false) | PhpValue::Null => return Ok(Self::default()),
+      PhpValue::Array(_) => {}
+      _ => return Err(E::Value("".into())),
+    }
+    // v1 lists are numbered from 1, and each of their items has a `type`
+    let entries = value.as_array().unwrap_or_default();
+    if entries
+      .iter()
+      .all(|(k, v)| matches!(k, PhpKey::Int(_)) && v.get("type").is_some())
+    {
+      return Self::from_php_value_v1(entries);
+    }
+
+    let mut deps = Self::default();
+    if let Some(required) = value.get("required") {
+      let lists = DependencyGroup::from_php_lists(required, "required")?;
+      let mut os = Vec::new();
+      for (i, item) in php_list(required.get("os")).into_iter().enumerate() {
+        os.push(OsDependency {
+          name: php_str(item.get("name"), || format!("required.os.{i}.name"))?,
+          conflicts: item.get("conflicts").is_some(),
+        });
+      }
+      let mut arch = Vec::new();
+      for (i, item) in php_list(required.get("arch")).into_iter().enumerate() {
+        arch.push(ArchDependency {
+          pattern: php_str(item.get("pattern"), || format!("required.arch.{i}.pattern"))?,
+          conflicts: item.get("conflicts").is_some(),
+        });
+      }
+      deps.required = RequiredDependencies {
+        php: VersionConstraint::from_php_value(required.get("php"), "required.php")?,
+        pear_installer: VersionConstraint::from_php_value(required.get("pearinstaller"), "required.pearinstaller")?,
+        packages: lists.packages,
+        subpackages: lists.subpackages,
+        extensions: lists.extensions,
+        os,
+        arch,
+      };
+    }
+    if let Some(optional) = value.get("optional") {
+      let lists = DependencyGroup::from_php_lists(optional, "optional")?;
+      deps.optional = OptionalDependencies {
+        packages: lists.packages,
+        subpackages: lists.subpackages,
+        extensions: lists.extensions,
+      };
+    }
+    for (i, group) in php_list(value.get("group")).into_iter().enumerate() {
+      let path = format!("group.{i}");
+      let attribs = group
+        .get("attribs")
+        .ok_or_else(|| E::Missing(format!("{path}.attribs").into()))?;
+      deps.groups.push(DependencyGroup {
+        name: php_str(attribs.get("name"), || format!("{path}.attribs.name"))?,
+        hint: php_str(attribs.get("hint"), || format!("{path}.attribs.hint"))?,
+        ..DependencyGroup::from_php_lists(group, &path)?
+      });
+    }
+    Ok(deps)
+  }
+
+  /// Convert the `package.xml` v1 dependency list (with `type`, `rel`, `version`, `name` and `optional` keys)
+  fn from_php_value_v1(entries: &[(PhpKey, PhpValue)]) -> Result<Self, ReleaseDependenciesFromPhpError> {
+    let mut deps = Self::default();
+    for (key, item) in entries {
+      let path = match key {
+        PhpKey::Int(i) => format!("{i}"),
+        PhpKey::String(s) => String::from_utf8_lossy(s).into_owned(),
+      };
+      let dep_type = php_str(item.get("type"), || format!("{path}.type"))?;
+      let rel = match item.get("rel") {
+        Some(rel) => php_str(Some(rel), || format!("{path}.rel"))?,
+        None => CompactString::new("has"),
+      };
+      let version = match item.get("version") {
+        Some(version) => Some(php_str(Some(version), || format!("{path}.version"))?),
+        None => None,
+      };
+      let optional = matches!(
+        item.get("optional").and_then(PhpValue::as_compact_str).as_deref(),
+        Some("yes")
+      );
+      if dep_type == "php" {
+        // packages usually declare both a minimum and a maximum PHP version, as two items
+        deps.required.php.apply_v1_rel(&rel, version);
+        continue;
+      }
+      let mut constraint = VersionConstraint::default();
+      let conflicts = constraint.apply_v1_rel(&rel, version);
+      match dep_type.as_str() {
+        "pkg" => {
+          let dep = PackageDependency {
+            name: php_str(item.get("name"), || format!("{path}.name"))?,
+            channel: Some(CompactString::new("pear.php.net")),
+            uri: None,
+            version: constraint,
+            conflicts,
+            provides_extension: None,
+          };
+          if optional {
+            deps.optional.packages.push(dep);
+          } else {
+            deps.required.packages.push(dep);
+          }
+        }
+        "ext" => {
+          let dep = ExtensionDependency {
+            name: php_str(item.get("name"), || format!("{path}.name"))?,
+            version: constraint,
+            conflicts,
+          };
+          if optional {
+            deps.optional.extensions.push(dep);
+          } else {
+            deps.required.extensions.push(dep);
+          }
+        }
+        "os" => deps.required.os.push(OsDependency {
+          name: php_str(item.get("name"), || format!("{path}.name"))?,
+          conflicts,
+        }),
+        // other v1 dependency types (`prog`, `ldlib`, `sapi`...) are not checked by the installer anymore
+        _ => {}
+      }
+    }
+    Ok(deps)
+  }
+}
+
+impl DependencyGroup<CompactString> {
+  fn from_php_lists(value: &PhpValue, path: &str) -> Result<Self, ReleaseDependenciesFromPhpError> {
+    let mut packages = Vec::new();
+    for (i, item) in php_list(value.get("package")).into_iter().enumerate() {
+      packages.push(PackageDependency::from_php_value(item, &format!("{path}.package.{i}"))?);
+    }
+    let mut subpackages = Vec::new();
+    for (i, item) in php_list(value.get("subpackage")).into_iter().enumerate() {
+      subpackages.push(PackageDependency::from_php_value(
+        item,
+        &format!("{path}.subpackage.{i}"),
+      )?);
+    }
+    let mut extensions = Vec::new();
+    for (i, item) in php_list(value.get("extension")).into_iter().enumerate() {
+      let path = format!("{path}.extension.{i}");
+      extensions.push(ExtensionDependency {
+        name: php_str(item.get("name"), || format!("{path}.name"))?,
+        version: VersionConstraint::from_php_value(Some(item), &path)?,
+        conflicts: item.get("conflicts").is_some(),
+      });
+    }
+    Ok(Self {
+      name: CompactString::default(),
+      hint: CompactString::default(),
+      packages,
+      subpackages,
+      extensions,
+    })
+  }
+}
+
+impl PackageDependency<CompactString> {
+  fn from_php_value(value: &PhpValue, path: &str) -> Result<Self, ReleaseDependenciesFromPhpError> {
+    Ok(Self {
+      name: php_str(value.get("name"), || format!("{path}.name"))?,
+      channel: php_opt_str(value.get("channel"), || format!("{path}.channel"))?,
+      uri: php_opt_str(value.get("uri"), || format!("{path}.uri"))?,
+      version: VersionConstraint::from_php_value(Some(value), path)?,
+      conflicts: value.get("conflicts").is_some(),
+      provides_extension: php_opt_str(value.get("providesextension"), || format!("{path}.providesextension"))?,
+    })
+  }
+}
+
+impl VersionConstraint<CompactString> {
+  fn from_php_value(value: Option<&PhpValue>, path: &str) -> Result<Self, ReleaseDependenciesFromPhpError> {
+    let value = match value {
+      Some(value) => value,
+      None => return Ok(Self::default()),
+    };
+    let mut exclude = Vec::new();
+    for (i, item) in php_list(value.get("exclude")).into_iter().enumerate() {
+      exclude.push(php_str(Some(item), || format!("{path}.exclude.{i}"))?);
+    }
+    Ok(Self {
+      min: php_opt_str(value.get("min"), || format!("{path}.min"))?,
+      max: php_opt_str(value.get("max"), || format!("{path}.max"))?,
+      recommended: php_opt_str(value.get("recommended"), || format!("{path}.recommended"))?,
+      exclude,
+    })
+  }
+
+  /// Apply a `package.xml` v1 relation, returns `true` if the relation is a conflict (`not`)
+  fn apply_v1_rel(&mut self, rel: &str, version: Option<CompactString>) -> bool {
+    let version = match (rel, version) {
+      ("not", _) => return true,
+      (_, None) => return false,
+      (_, Some(version)) => version,
+    };
+    match rel {
+      "ge" => self.min = Some(version),
+      "le" => self.max = Some(version),
+      "gt" => {
+        self.min = Some(version.clone());
+        self.exclude.push(version);
+      }
+      "lt" => {
+        self.max = Some(version.clone());
+        self.exclude.push(version);
+      }
+      "eq" => {
+        self.min = Some(version.clone());
+        self.max = Some(version);
+      }
+      "ne" => self.exclude.push(version),
+      _ => {}
+    }
+    false
+  }
+}
+
+impl<Str: AsRef<str>> PackageDependencies<Str> {
+  /// Write dependencies in the PHP serialized format of `deps.{version}.txt`
+  pub fn to_php_serialized(&self) -> Vec<u8> {
+    serialize(&self.to_php_value())
+  }
+
+  /// Convert to the `package.xml` v2 array format, releases without dependencies are converted to `false`
+  pub fn to_php_value(&self) -> PhpValue {
+    let required = &self.required;
+    let optional = &self.optional;
+    let is_empty = required.php.is_empty()
+      && required.pear_installer.is_empty()
+      && required.packages.is_empty()
+      && required.subpackages.is_empty()
+      && required.extensions.is_empty()
+      && required.os.is_empty()
+      && required.arch.is_empty()
+      && optional.packages.is_empty()
+      && optional.subpackages.is_empty()
+      && optional.extensions.is_empty()
+      && self.groups.is_empty();
+    if is_empty {
+      return PhpValue::Bool(false);
+    }
+
+    let mut value = Vec::new();
+    let mut entries = vec![
+      ("php", php_map(required.php.to_php_entries())),
+      ("pearinstaller", php_map(required.pear_installer.to_php_entries())),
+    ];
+    push_php_lists(
+      &mut entries,
+      &required.packages,
+      &required.subpackages,
+      &required.extensions,
+    );
+    push_php_items(
+      &mut entries,
+      "arch",
+      required.arch.iter().map(|dep| {
+        let mut entries = vec![("pattern", php_string(&dep.pattern))];
+        push_php_conflicts(&mut entries, dep.conflicts);
+        php_map(entries)
+      }),
+    );
+    push_php_items(
+      &mut entries,
+      "os",
+      required.os.iter().map(|dep| {
+        let mut entries = vec![("name", php_string(&dep.name))];
+        push_php_conflicts(&mut entries, dep.conflicts);
+        php_map(entries)
+      }),
+    );
+    value.push(("required", php_map(entries)));
+
+    let mut entries = Vec::new();
+    push_php_lists(
+      &mut entries,
+      &optional.packages,
+      &optional.subpackages,
+      &optional.extensions,
+    );
+    if !entries.is_empty() {
+      value.push(("optional", php_map(entries)));
+    }
+
+    push_php_items(
+      &mut value,
+      "group",
+      self.groups.iter().map(|group| {
+        let attribs = php_map(vec![
+          ("hint", php_string(&group.hint)),
+          ("name", php_string(&group.name)),
+        ]);
+        let mut entries = vec![("attribs", attribs)];
+        push_php_lists(&mut entries, &group.packages, &group.subpackages, &group.extensions);
+        php_map(entries)
+      }),
+    );
+    php_map(value)
+  }
+}
+
+impl<Str: AsRef<str>> PackageDependency<Str> {
+  fn to_php_value(&self) -> PhpValue {
+    let mut entries = vec![("name", php_string(&self.name))];
+    if let Some(channel) = &self.channel {
+      entries.push(("channel", php_string(channel)));
+    }
+    if let Some(uri) = &self.uri {
+      entries.push(("uri", php_string(uri)));
+    }
+    entries.extend(self.version.to_php_entries());
+    push_php_conflicts(&mut entries, self.conflicts);
+    if let Some(provides_extension) = &self.provides_extension {
+      entries.push(("providesextension", php_string(provides_extension)));
+    }
+    php_map(entries)
+  }
+}
+
+impl<Str: AsRef<str>> VersionConstraint<Str> {
+  fn is_empty(&self) -> bool {
+    self.min.is_none() && self.max.is_none() && self.recommended.is_none() && self.exclude.is_empty()
+  }
+
+  fn to_php_entries(&self) -> Vec<(&'static str, PhpValue)> {
+    let mut entries = Vec::new();
+    if let Some(min) = &self.min {
+      entries.push(("min", php_string(min)));
+    }
+    if let Some(max) = &self.max {
+      entries.push(("max", php_string(max)));
+    }
+    if let Some(recommended) = &self.recommended {
+      entries.push(("recommended", php_string(recommended)));
+    }
+    push_php_items(&mut entries, "exclude", self.exclude.iter().map(php_string));
+    entries
+  }
+}
+
+/// Push the `package`, `subpackage` and `extension` lists of a dependency node
+fn push_php_lists<Str: AsRef<str>>(
+  entries: &mut Vec<(&'static str, PhpValue)>,
+  packages: &[PackageDependency<Str>],
+  subpackages: &[PackageDependency<Str>],
+  extensions: &[ExtensionDependency<Str>],
+) {
+  push_php_items(entries, "package", packages.iter().map(PackageDependency::to_php_value));
+  push_php_items(
+    entries,
+    "subpackage",
+    subpackages.iter().map(PackageDependency::to_php_value),
+  );
+  push_php_items(
+    entries,
+    "extension",
+    extensions.iter().map(|dep| {
+      let mut entries = vec![("name", php_string(&dep.name))];
+      entries.extend(dep.version.to_php_entries());
+      push_php_conflicts(&mut entries, dep.conflicts);
+      php_map(entries)
+    }),
+  );
+}
+
+/// Push the items of a PEAR array value, the inverse of [`php_list`]: nothing is pushed for an empty list
+fn push_php_items(
+  entries: &mut Vec<(&'static str, PhpValue)>,
+  key: &'static str,
+  items: impl Iterator<Item = PhpValue>,
+) {
+  let mut items: Vec<PhpValue> = items.collect();
+  match items.len() {
+    0 => {}
+    1 => entries.push((key, items.remove(0))),
+    _ => entries.push((
+      key,
+      PhpValue::Array(
+        items
+          .into_iter()
+          .enumerate()
+          .map(|(i, item)| (PhpKey::Int(i as i64), item))
+          .collect(),
+      ),
+    )),
+  }
+}
+
+/// The `<conflicts/>` element is read as an empty string
+fn push_php_conflicts(entries: &mut Vec<(&'static str, PhpValue)>, conflicts: bool) {
+  if conflicts {
+    entries.push(("conflicts", PhpValue::String(Vec::new())));
+  }
+}
+
+fn php_map(entries: Vec<(&'static str, PhpValue)>) -> PhpValue {
+  PhpValue::Array(
+    entries
+      .into_iter()
+      .map(|(k, v)| (PhpKey::String(k.as_bytes().to_vec()), v))
+      .collect(),
+  )
+}
+
+fn php_string(value: impl AsRef<str>) -> PhpValue {
+  PhpValue::String(value.as_ref().as_bytes().to_vec())
+}
+
+/// Get the items of a PEAR array value: a single item is stored directly, multiple items are stored as a list.
+fn php_list(value: Option<&PhpValue>) -> Vec<&PhpValue> {
+  match value {
+    None => Vec::new(),
+    Some(value) if value.is_list() => value.as_array().unwrap_or_default().iter().map(|(_, v)| v).collect(),
+    Some(value) => vec![value],
+  }
+}
+
+fn php_opt_str(
+  value: Option<&PhpValue>,
+  path: impl FnOnce() -> String,
+) -> Result<Option<CompactString>, ReleaseDependenciesFromPhpError> {
+  match value {
+    None => Ok(None),
+    Some(value) => value
+      .as_compact_str()
+      .map(Some)
+      .ok_or_else(|| ReleaseDependenciesFromPhpError::Value(path().into())),
+  }
+}
+
+fn php_str(
+  value: Option<&PhpValue>,
+  path: impl FnOnce() -> String,
+) -> Result<CompactString, ReleaseDependenciesFromPhpError> {
+  match value {
+    None => Err(ReleaseDependenciesFromPhpError::Missing(path().into())),
+    Some(value) => value
+      .as_compact_str()
+      .ok_or_else(|| ReleaseDependenciesFromPhpError::Value(path().into())),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  pub fn test_release_dependencies_from_php_serialized() {
+    let input = include_bytes!("../../test-resources/get_release_dependencies/pecl_pecl_http_4.2.4/input.txt");
+    let actual = ReleaseDependencies::from_php_serialized(input).unwrap();
+    assert_eq!(actual.required.php.min.as_deref(), Some("8.0.0"));
+    assert_eq!(actual.required.packages.len(), 1);
+    assert_eq!(actual.required.packages[0].provides_extension.as_deref(), Some("raphf"));
+    assert_eq!(actual.required.extensions.len(), 2);
+    assert!(actual.optional.extensions.is_empty());
+  }
+
+  #[test]
+  pub fn test_release_dependencies_from_php_serialized_v1() {
+    let input = include_bytes!("../../test-resources/get_release_dependencies/pecl_apc_3.0.0/input.txt");
+    let actual = ReleaseDependencies::from_php_serialized(input).unwrap();
+    assert_eq!(actual.required.php.min.as_deref(), Some("4.3.0"));
+    assert_eq!(actual.required.os.len(), 1);
+    assert!(actual.required.os[0].conflicts);
+    assert_ne!(actual, ReleaseDependencies::default());
+  }
+
+  #[test]
+  pub fn test_release_dependencies_from_php_serialized_v1_php_range() {
+    let input = br#"a:3:{i:1;a:3:{s:4:"type";s:3:"php";s:3:"rel";s:2:"ge";s:7:"version";s:5:"4.3.0";}i:2;a:3:{s:4:"type";s:3:"php";s:3:"rel";s:2:"le";s:7:"version";s:5:"5.9.9";}i:3;a:3:{s:4:"type";s:3:"ext";s:3:"rel";s:3:"has";s:4:"name";s:4:"zlib";}}"#;
+    let actual = ReleaseDependencies::from_php_serialized(input).unwrap();
+    assert_eq!(actual.required.php.min.as_deref(), Some("4.3.0"));
+    assert_eq!(actual.required.php.max.as_deref(), Some("5.9.9"));
+    assert_eq!(actual.required.extensions.len(), 1);
+    assert_eq!(actual.required.extensions[0].name, "zlib");
+  }
+
+  #[test]
+  pub fn test_release_dependencies_to_php_serialized() {
+    let input = include_bytes!("../../test-resources/get_release_dependencies/pecl_pecl_http_4.2.4/input.txt");
+    let deps = ReleaseDependencies::from_php_serialized(input).unwrap();
+    assert_eq!(deps.to_php_serialized(), input);
+    assert_eq!(
+      ReleaseDependencies::<CompactString>::default().to_php_serialized(),
+      b"b:0;"
+    );
+
+    let input = include_bytes!("../../test-resources/get_release_dependencies/pecl_apc_3.0.0/input.txt");
+    let deps = ReleaseDependencies::from_php_serialized(input).unwrap();
+    assert_eq!(
+      ReleaseDependencies::from_php_serialized(&deps.to_php_serialized()).unwrap(),
+      deps
+    );
+  }
+
+  #[test]
+  pub fn test_version_constraint_to_requirement() {
+    let constraint = VersionConstraint {
+      min: Some("1.0.0"),
+      max: Some("2.0.0"),
+      recommended: None,
+      exclude: vec!["1.5.0"],
+    };
+    let requirement = constraint.to_requirement().unwrap();
+    assert_eq!(requirement.to_string(), ">=1.0.0, <=2.0.0, !=1.5.0");
+    assert!(requirement.matches(&PearVersion::new("1.4.2").unwrap()));
+    assert!(!requirement.matches(&PearVersion::new("1.5.0").unwrap()));
+    assert!(!requirement.matches(&PearVersion::new("2.0.1").unwrap()));
+  }
+}
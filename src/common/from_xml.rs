@@ -0,0 +1,53 @@
+use crate::common::category::{
+  CategoryInfoFromRcDomError, CategoryListingFromRcDomError, CategoryPackageListingFromRcDomError,
+  CategoryPackagesInfoFromRcDomError,
+};
+use crate::common::channel::ChannelInfoFromRcDomError;
+use crate::common::maintainer::{
+  MaintainerInfoFromRcDomError, MaintainerListingFromRcDomError, PackageMaintainersFromRcDomError,
+};
+use crate::common::package::{PackageInfoFromRcDomError, PackageListingFromRcDomError};
+use crate::common::package_xml::{PackageXml1FromRcDomError, PackageXmlFromRcDomError};
+use crate::common::release::{
+  ReleaseFromRcDomError, ReleaseListing2FromRcDomError, ReleaseListingFromRcDomError, ReleaseV2FromRcDomError,
+};
+
+/// Error when reading a model from an XML document
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum FromXmlError {
+  #[error("failed to read XML document: {0}")]
+  Xml(String),
+  #[error("invalid category listing document")]
+  CategoryListing(#[from] CategoryListingFromRcDomError),
+  #[error("invalid category info document")]
+  CategoryInfo(#[from] CategoryInfoFromRcDomError),
+  #[error("invalid category package listing document")]
+  CategoryPackageListing(#[from] CategoryPackageListingFromRcDomError),
+  #[error("invalid category packages info document")]
+  CategoryPackagesInfo(#[from] CategoryPackagesInfoFromRcDomError),
+  #[error("invalid channel document")]
+  ChannelInfo(#[from] ChannelInfoFromRcDomError),
+  #[error("invalid maintainer listing document")]
+  MaintainerListing(#[from] MaintainerListingFromRcDomError),
+  #[error("invalid maintainer info document")]
+  MaintainerInfo(#[from] MaintainerInfoFromRcDomError),
+  #[error("invalid package maintainers document")]
+  PackageMaintainers(#[from] PackageMaintainersFromRcDomError),
+  #[error("invalid package listing document")]
+  PackageListing(#[from] PackageListingFromRcDomError),
+  #[error("invalid package info document")]
+  PackageInfo(#[from] PackageInfoFromRcDomError),
+  #[error("invalid package.xml document")]
+  PackageXml(#[from] PackageXmlFromRcDomError),
+  #[error("invalid package.xml (v1) document")]
+  PackageXml1(#[from] PackageXml1FromRcDomError),
+  #[error("invalid release listing document")]
+  ReleaseListing(#[from] ReleaseListingFromRcDomError),
+  #[error("invalid release listing (v2) document")]
+  ReleaseListing2(#[from] ReleaseListing2FromRcDomError),
+  #[error("invalid release document")]
+  Release(#[from] ReleaseFromRcDomError),
+  #[error("invalid release (v2) document")]
+  ReleaseV2(#[from] ReleaseV2FromRcDomError),
+}
@@ -0,0 +1,559 @@
+use crate::common::from_xml::FromXmlError;
+use crate::common::package_xml::MaintainerRole;
+use crate::xml_util::{find_root, get_link_attr, get_text, parse_rc_dom, RestXmlWriter};
+use compact_str::CompactString;
+use markup5ever_rcdom::{Node, NodeData, RcDom};
+
+/// List of all maintainers, from `/rest/m/allmaintainers.xml`
+///
+/// <https://pear.php.net/dtd/rest.allmaintainers.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MaintainerListing<Str = CompactString> {
+  pub items: Vec<Maintainer<Str>>,
+}
+
+impl<Str: AsRef<str>> MaintainerListing<Str> {
+  /// Write as an `allmaintainers.xml` document
+  pub fn to_xml(&self) -> String {
+    let mut writer = RestXmlWriter::new(
+      "m",
+      "http://pear.php.net/dtd/rest.allmaintainers",
+      "http://pear.php.net/dtd/rest.allmaintainers http://pear.php.net/dtd/rest.allmaintainers.xsd",
+    );
+    for item in &self.items {
+      writer.link_text("h", item.link.as_ref(), item.handle.as_ref());
+    }
+    writer.finish()
+  }
+}
+
+/// Maintainer handle, with the link to its `info.xml`
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Maintainer<Str = CompactString> {
+  pub handle: Str,
+  pub link: Str,
+}
+
+impl MaintainerListing<CompactString> {
+  /// Read from an XML document, panics if the input is invalid.
+  ///
+  /// See [`Self::try_from_xml`] for the fallible version.
+  pub fn from_xml(input: &[u8]) -> Self {
+    Self::try_from_xml(input).unwrap()
+  }
+
+  pub fn try_from_xml(input: &[u8]) -> Result<Self, FromXmlError> {
+    let dom = parse_rc_dom(input)?;
+    Ok(Self::from_rc_dom(dom)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum MaintainerListingFromRcDomError {
+  #[error("failed to find root node")]
+  RootNotFound,
+  #[error("failed to read listing from XML Node")]
+  Read(#[from] MaintainerListingFromXmlNodeError),
+}
+
+impl MaintainerListing<CompactString> {
+  pub fn from_rc_dom(dom: RcDom) -> Result<Self, MaintainerListingFromRcDomError> {
+    let doc = dom.document;
+    let root = find_root(&doc, "m").map_err(|_| MaintainerListingFromRcDomError::RootNotFound)?;
+    Ok(Self::from_xml_node(&root)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum MaintainerListingFromXmlNodeError {
+  #[error("unexpected child node type at index {0}")]
+  ChildType(usize),
+  #[error("handle node <h> is malformed at index {0}")]
+  ReadHandle(usize),
+  #[error("handle node <h> is missing attribute `xlink:href` at index {0}")]
+  MissingHandleLink(usize),
+}
+
+impl MaintainerListing<CompactString> {
+  pub fn from_xml_node(node: &Node) -> Result<Self, MaintainerListingFromXmlNodeError> {
+    use MaintainerListingFromXmlNodeError as E;
+
+    let mut items: Vec<Maintainer<CompactString>> = Vec::new();
+
+    for (i, handle) in node.children.borrow().iter().enumerate() {
+      let node: &Node = handle;
+      match &node.data {
+        NodeData::Element { name, attrs, .. } if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("h") => {
+          let attrs = &*attrs.borrow();
+          let new_handle = get_text(node).map_err(|_| E::ReadHandle(i))?;
+          let new_link = get_link_attr(attrs)
+            .map_err(|_| E::ReadHandle(i))?
+            .ok_or(E::MissingHandleLink(i))?;
+          items.push(Maintainer {
+            handle: new_handle,
+            link: CompactString::new(new_link.value.as_ref()),
+          });
+        }
+        NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+        _ => return Err(E::ChildType(i)),
+      }
+    }
+
+    Ok(Self { items })
+  }
+}
+
+/// Maintainer information, from `/rest/m/{handle}/info.xml`
+///
+/// <https://pear.php.net/dtd/rest.maintainer.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MaintainerInfo<Str = CompactString> {
+  pub handle: Str,
+  /// Full name
+  pub name: Str,
+  /// Home page
+  pub url: Option<Str>,
+}
+
+impl<Str: AsRef<str>> MaintainerInfo<Str> {
+  /// Write as a `/rest/m/{handle}/info.xml` document
+  pub fn to_xml(&self) -> String {
+    let mut writer = RestXmlWriter::new(
+      "m",
+      "http://pear.php.net/dtd/rest.maintainer",
+      "http://pear.php.net/dtd/rest.maintainer http://pear.php.net/dtd/rest.maintainer.xsd",
+    );
+    writer.text("h", self.handle.as_ref()).text("n", self.name.as_ref());
+    if let Some(url) = &self.url {
+      writer.text("u", url.as_ref());
+    }
+    writer.finish()
+  }
+}
+
+impl MaintainerInfo<CompactString> {
+  /// Read from an XML document, panics if the input is invalid.
+  ///
+  /// See [`Self::try_from_xml`] for the fallible version.
+  pub fn from_xml(input: &[u8]) -> Self {
+    Self::try_from_xml(input).unwrap()
+  }
+
+  pub fn try_from_xml(input: &[u8]) -> Result<Self, FromXmlError> {
+    let dom = parse_rc_dom(input)?;
+    Ok(Self::from_rc_dom(dom)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum MaintainerInfoFromRcDomError {
+  #[error("failed to find root node")]
+  RootNotFound,
+  #[error("failed to read maintainer from XML Node")]
+  Read(#[from] MaintainerInfoFromXmlNodeError),
+}
+
+impl MaintainerInfo<CompactString> {
+  pub fn from_rc_dom(dom: RcDom) -> Result<Self, MaintainerInfoFromRcDomError> {
+    let doc = dom.document;
+    let root = find_root(&doc, "m").map_err(|_| MaintainerInfoFromRcDomError::RootNotFound)?;
+    Ok(Self::from_xml_node(&root)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum MaintainerInfoFromXmlNodeError {
+  #[error("unexpected child node type at index {0}")]
+  ChildType(usize),
+  #[error("handle node <h> is malformed at index {0}")]
+  ReadHandle(usize),
+  #[error("handle node <h> is missing")]
+  MissingHandle,
+  #[error("handle node <h> is duplicated")]
+  DuplicateHandle,
+  #[error("name node <n> is malformed at index {0}")]
+  ReadName(usize),
+  #[error("name node <n> is missing")]
+  MissingName,
+  #[error("name node <n> is duplicated")]
+  DuplicateName,
+  #[error("url node <u> is malformed at index {0}")]
+  ReadUrl(usize),
+  #[error("url node <u> is duplicated")]
+  DuplicateUrl,
+}
+
+impl MaintainerInfo<CompactString> {
+  pub fn from_xml_node(node: &Node) -> Result<Self, MaintainerInfoFromXmlNodeError> {
+    use MaintainerInfoFromXmlNodeError as E;
+
+    let mut handle: Option<CompactString> = None;
+    let mut full_name: Option<CompactString> = None;
+    let mut url: Option<CompactString> = None;
+
+    for (i, child) in node.children.borrow().iter().enumerate() {
+      let node: &Node = child;
+      match &node.data {
+        NodeData::Element { name, .. } => {
+          if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("h") {
+            let new = get_text(node).map_err(|_| E::ReadHandle(i))?;
+            let old = handle.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateHandle);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("n") {
+            if handle.is_none() {
+              return Err(E::MissingHandle);
+            }
+            let new = get_text(node).map_err(|_| E::ReadName(i))?;
+            let old = full_name.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateName);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("u") {
+            if full_name.is_none() {
+              return Err(E::MissingName);
+            }
+            let new = get_text(node).map_err(|_| E::ReadUrl(i))?;
+            let old = url.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateUrl);
+            }
+          } else {
+            return Err(E::ChildType(i));
+          }
+        }
+        NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+        _ => return Err(E::ChildType(i)),
+      }
+    }
+
+    Ok(Self {
+      handle: handle.ok_or(E::MissingHandle)?,
+      name: full_name.ok_or(E::MissingName)?,
+      url,
+    })
+  }
+}
+
+/// Maintainers of a package, from `/rest/p/{package}/maintainers.xml` or `/rest/p/{package}/maintainers2.xml`
+///
+/// The `role` of each maintainer is only available with `maintainers2.xml`.
+///
+/// <https://pear.php.net/dtd/rest.packagemaintainers.xsd>
+/// <https://pear.php.net/dtd/rest.packagemaintainers2.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PackageMaintainers<Str = CompactString> {
+  pub package: Str,
+  pub channel: Str,
+  pub items: Vec<PackageMaintainer<Str>>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PackageMaintainer<Str = CompactString> {
+  pub handle: Str,
+  pub active: bool,
+  /// Role of the maintainer, `None` for `maintainers.xml`
+  pub role: Option<MaintainerRole>,
+}
+
+impl<Str: AsRef<str>> PackageMaintainers<Str> {
+  /// Write as a `maintainers2.xml` document, or as a `maintainers.xml` document if no maintainer has a role
+  pub fn to_xml(&self) -> String {
+    let with_roles = self.items.iter().any(|item| item.role.is_some());
+    let mut writer = if with_roles {
+      RestXmlWriter::new(
+        "m",
+        "http://pear.php.net/dtd/rest.packagemaintainers2",
+        "http://pear.php.net/dtd/rest.packagemaintainers2 http://pear.php.net/dtd/rest.packagemaintainers2.xsd",
+      )
+    } else {
+      RestXmlWriter::new(
+        "m",
+        "http://pear.php.net/dtd/rest.packagemaintainers",
+        "http://pear.php.net/dtd/rest.packagemaintainers http://pear.php.net/dtd/rest.packagemaintainers.xsd",
+      )
+    };
+    writer.text("p", self.package.as_ref()).text("c", self.channel.as_ref());
+    for item in &self.items {
+      let active = if item.active { "1" } else { "0" };
+      match item.role {
+        Some(role) => writer.group("m", &[("h", item.handle.as_ref()), ("a", active), ("r", role.as_str())]),
+        None => writer.group("m", &[("h", item.handle.as_ref()), ("a", active)]),
+      };
+    }
+    writer.finish()
+  }
+}
+
+impl PackageMaintainers<CompactString> {
+  /// Read from an XML document, panics if the input is invalid.
+  ///
+  /// See [`Self::try_from_xml`] for the fallible version.
+  pub fn from_xml(input: &[u8]) -> Self {
+    Self::try_from_xml(input).unwrap()
+  }
+
+  pub fn try_from_xml(input: &[u8]) -> Result<Self, FromXmlError> {
+    let dom = parse_rc_dom(input)?;
+    Ok(Self::from_rc_dom(dom)?)
+  }
+
+  /// Maintainers with the `active` flag
+  pub fn active(&self) -> impl Iterator<Item = &PackageMaintainer<CompactString>> {
+    self.items.iter().filter(|m| m.active)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum PackageMaintainersFromRcDomError {
+  #[error("failed to find root node")]
+  RootNotFound,
+  #[error("failed to read package maintainers from XML Node")]
+  Read(#[from] PackageMaintainersFromXmlNodeError),
+}
+
+impl PackageMaintainers<CompactString> {
+  pub fn from_rc_dom(dom: RcDom) -> Result<Self, PackageMaintainersFromRcDomError> {
+    let doc = dom.document;
+    let root = find_root(&doc, "m").map_err(|_| PackageMaintainersFromRcDomError::RootNotFound)?;
+    Ok(Self::from_xml_node(&root)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum PackageMaintainersFromXmlNodeError {
+  #[error("unexpected child node type at index {0}")]
+  ChildType(usize),
+  #[error("package node <p> is malformed at index {0}")]
+  ReadPackage(usize),
+  #[error("package node <p> is missing")]
+  MissingPackage,
+  #[error("package node <p> is duplicated")]
+  DuplicatePackage,
+  #[error("channel node <c> is malformed at index {0}")]
+  ReadChannel(usize),
+  #[error("channel node <c> is missing")]
+  MissingChannel,
+  #[error("channel node <c> is duplicated")]
+  DuplicateChannel,
+  #[error("failed to read maintainer at index {1}")]
+  ReadMaintainer(#[source] PackageMaintainerFromXmlNodeError, usize),
+}
+
+impl PackageMaintainers<CompactString> {
+  pub fn from_xml_node(node: &Node) -> Result<Self, PackageMaintainersFromXmlNodeError> {
+    use PackageMaintainersFromXmlNodeError as E;
+
+    let mut package: Option<CompactString> = None;
+    let mut channel: Option<CompactString> = None;
+    let mut items: Vec<PackageMaintainer<CompactString>> = Vec::new();
+
+    for (i, handle) in node.children.borrow().iter().enumerate() {
+      let node: &Node = handle;
+      match &node.data {
+        NodeData::Element { name, .. } => {
+          if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("p") {
+            let new = get_text(node).map_err(|_| E::ReadPackage(i))?;
+            let old = package.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicatePackage);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("c") {
+            if package.is_none() {
+              return Err(E::MissingPackage);
+            }
+            let new = get_text(node).map_err(|_| E::ReadChannel(i))?;
+            let old = channel.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateChannel);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("m") {
+            if channel.is_none() {
+              return Err(E::MissingChannel);
+            }
+            let m = PackageMaintainer::from_xml_node(node).map_err(|e| E::ReadMaintainer(e, i))?;
+            items.push(m);
+          } else {
+            return Err(E::ChildType(i));
+          }
+        }
+        NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+        _ => return Err(E::ChildType(i)),
+      }
+    }
+
+    Ok(Self {
+      package: package.ok_or(E::MissingPackage)?,
+      channel: channel.ok_or(E::MissingChannel)?,
+      items,
+    })
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum PackageMaintainerFromXmlNodeError {
+  #[error("unexpected child node type at index {0}")]
+  ChildType(usize),
+  #[error("handle node <h> is malformed at index {0}")]
+  ReadHandle(usize),
+  #[error("handle node <h> is missing")]
+  MissingHandle,
+  #[error("handle node <h> is duplicated")]
+  DuplicateHandle,
+  #[error("active node <a> is malformed at index {0}")]
+  ReadActive(usize),
+  #[error("active node <a> is missing")]
+  MissingActive,
+  #[error("active node <a> is duplicated")]
+  DuplicateActive,
+  #[error("role node <r> is malformed at index {0}")]
+  ReadRole(usize),
+  #[error("role node <r> is duplicated")]
+  DuplicateRole,
+}
+
+impl PackageMaintainer<CompactString> {
+  pub fn from_xml_node(node: &Node) -> Result<Self, PackageMaintainerFromXmlNodeError> {
+    use PackageMaintainerFromXmlNodeError as E;
+
+    let mut handle: Option<CompactString> = None;
+    let mut active: Option<bool> = None;
+    let mut role: Option<MaintainerRole> = None;
+
+    for (i, child) in node.children.borrow().iter().enumerate() {
+      let node: &Node = child;
+      match &node.data {
+        NodeData::Element { name, .. } => {
+          if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("h") {
+            let new = get_text(node).map_err(|_| E::ReadHandle(i))?;
+            let old = handle.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateHandle);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("a") {
+            if handle.is_none() {
+              return Err(E::MissingHandle);
+            }
+            let new = get_text(node).map_err(|_| E::ReadActive(i))?;
+            let new = match new.trim() {
+              "1" => true,
+              "0" => false,
+              _ => return Err(E::ReadActive(i)),
+            };
+            let old = active.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateActive);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("r") {
+            if active.is_none() {
+              return Err(E::MissingActive);
+            }
+            let new = get_text(node).map_err(|_| E::ReadRole(i))?;
+            let new: MaintainerRole = new.trim().parse().map_err(|_| E::ReadRole(i))?;
+            let old = role.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateRole);
+            }
+          } else {
+            return Err(E::ChildType(i));
+          }
+        }
+        NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+        _ => return Err(E::ChildType(i)),
+      }
+    }
+
+    Ok(Self {
+      handle: handle.ok_or(E::MissingHandle)?,
+      active: active.ok_or(E::MissingActive)?,
+      role,
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  pub fn test_maintainer_listing_from_xml() {
+    let input = include_bytes!("../../test-resources/get_maintainer_list/pecl/input.xml");
+    let actual = MaintainerListing::from_xml(input);
+    let expected = Maintainer {
+      handle: CompactString::new("remi"),
+      link: CompactString::new("/rest/m/remi"),
+    };
+    assert!(actual.items.contains(&expected));
+  }
+
+  #[test]
+  pub fn test_maintainer_info_from_xml() {
+    let input = include_bytes!("../../test-resources/get_maintainer_info/pecl_remi/input.xml");
+    let actual = MaintainerInfo::from_xml(input);
+    let expected = MaintainerInfo {
+      handle: CompactString::new("remi"),
+      name: CompactString::new("Remi Collet"),
+      url: Some(CompactString::new("https://blog.remirepo.net/")),
+    };
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  pub fn test_package_maintainers_from_xml() {
+    let input = include_bytes!("../../test-resources/get_package_maintainers/pecl_protobuf/input.xml");
+    let actual = PackageMaintainers::from_xml(input);
+    assert_eq!(actual.package.as_str(), "protobuf");
+    assert_eq!(actual.items.len(), 3);
+    assert_eq!(actual.active().count(), 2);
+    assert!(actual.items.iter().all(|m| m.role.is_none()));
+  }
+
+  #[test]
+  pub fn test_package_maintainers2_from_xml() {
+    let input = include_bytes!("../../test-resources/get_package_maintainers2/pecl_protobuf/input.xml");
+    let actual = PackageMaintainers::from_xml(input);
+    let expected = PackageMaintainer {
+      handle: CompactString::new("bshaffer"),
+      active: true,
+      role: Some(MaintainerRole::Lead),
+    };
+    assert_eq!(actual.items.first(), Some(&expected));
+    assert_eq!(actual.items[2].role, Some(MaintainerRole::Developer));
+  }
+
+  #[test]
+  pub fn test_maintainers_to_xml() {
+    let input = include_bytes!("../../test-resources/get_maintainer_list/pecl/input.xml");
+    let listing = MaintainerListing::from_xml(input);
+    assert_eq!(MaintainerListing::from_xml(listing.to_xml().as_bytes()), listing);
+
+    let input = include_bytes!("../../test-resources/get_maintainer_info/pecl_remi/input.xml");
+    let info = MaintainerInfo::from_xml(input);
+    assert_eq!(MaintainerInfo::from_xml(info.to_xml().as_bytes()), info);
+
+    let input = include_bytes!("../../test-resources/get_package_maintainers/pecl_protobuf/input.xml");
+    let maintainers = PackageMaintainers::from_xml(input);
+    let actual = maintainers.to_xml();
+    assert!(actual.contains("rest.packagemaintainers.xsd"));
+    assert_eq!(PackageMaintainers::from_xml(actual.as_bytes()), maintainers);
+
+    let input = include_bytes!("../../test-resources/get_package_maintainers2/pecl_protobuf/input.xml");
+    let maintainers = PackageMaintainers::from_xml(input);
+    let actual = maintainers.to_xml();
+    assert!(actual.contains(" <m><h>haberman</h><a>1</a><r>developer</r></m>\n"));
+    assert_eq!(PackageMaintainers::from_xml(actual.as_bytes()), maintainers);
+  }
+}
@@ -1,2 +1,28 @@
+//! Models of the PEAR REST documents
+//!
+//! # Serde representation
+//!
+//! With the `serde` feature, all the models implement `Serialize` and `Deserialize`. The representation is part of
+//! the public API: changes are breaking changes and are listed in the changelog.
+//!
+//! - Structs are maps using the Rust field names (`snake_case`), optional fields are `null` when missing.
+//! - [`PearVersion`](version::PearVersion) is the version string, as published by the channel (`"4.27.0RC3"`).
+//! - [`Stability`](stability::Stability) is the lowercase stability name (`"stable"`), unknown values are kept as-is.
+//! - [`VersionRequirement`](requirement::VersionRequirement) is its string form (`">=3.24, <4"`).
+//! - Dates without timezone, such as [`Release::time`](release::Release::time), use the ISO 8601 format
+//!   (`"2024-05-23T14:46:50"`).
+//! - URLs, such as [`ReleaseArchive::link`](release::ReleaseArchive::link), are absolute URL strings.
+//! - Enums without data are lowercase strings (`"lead"`). Enums with data are externally tagged, using lowercase or
+//!   `snake_case` names (`{"ext_src": {...}}`).
+
+pub mod category;
+pub mod channel;
+pub mod dependency;
+pub mod from_xml;
+pub mod maintainer;
 pub mod package;
+pub mod package_xml;
 pub mod release;
+pub mod requirement;
+pub mod stability;
+pub mod version;
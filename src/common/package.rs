@@ -1,9 +1,10 @@
+use crate::common::from_xml::FromXmlError;
+use crate::url_util::php_urlencode;
+use crate::xml_util::{find_root, get_link_attr, get_text, parse_rc_dom, RestXmlWriter};
 use compact_str::CompactString;
 use markup5ever_rcdom::{Node, NodeData, RcDom};
-use xml5ever::tendril::{TendrilSink};
-use xml5ever::driver::{parse_document, XmlParseOpts};
-use crate::xml_util::{find_root, get_text};
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct PackageListing<Str = CompactString> {
   pub category: Str,
@@ -11,14 +12,20 @@ pub struct PackageListing<Str = CompactString> {
 }
 
 impl PackageListing<CompactString> {
-  pub fn from_xml(mut input: &[u8]) -> Self {
-    let input = &mut input;
-    let sink = RcDom::default();
-    let dom: RcDom = parse_document(sink, XmlParseOpts::default()).from_utf8().read_from(input).unwrap();
-    Self::from_rc_dom(dom).unwrap()
+  /// Read from an XML document, panics if the input is invalid.
+  ///
+  /// See [`Self::try_from_xml`] for the fallible version.
+  pub fn from_xml(input: &[u8]) -> Self {
+    Self::try_from_xml(input).unwrap()
+  }
+
+  pub fn try_from_xml(input: &[u8]) -> Result<Self, FromXmlError> {
+    let dom = parse_rc_dom(input)?;
+    Ok(Self::from_rc_dom(dom)?)
   }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
 pub enum PackageListingFromRcDomError {
   #[error("failed to find root node")]
@@ -35,6 +42,7 @@ impl PackageListing<CompactString> {
   }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
 pub enum PackageListingFromXmlNodeError {
   #[error("unexpected child node type at index {0}")]
@@ -74,8 +82,8 @@ impl PackageListing<CompactString> {
           } else {
             return Err(PackageListingFromXmlNodeError::ChildType(i));
           }
-        },
-        NodeData::Text { .. } | NodeData::Comment { .. } => { continue },
+        }
+        NodeData::Text { .. } | NodeData::Comment { .. } => continue,
         _ => return Err(PackageListingFromXmlNodeError::ChildType(i)),
       }
     }
@@ -87,6 +95,23 @@ impl PackageListing<CompactString> {
   }
 }
 
+impl<Str: AsRef<str>> PackageListing<Str> {
+  /// Write as a `packages.xml` document
+  pub fn to_xml(&self) -> String {
+    let mut writer = RestXmlWriter::new(
+      "a",
+      "http://pear.php.net/dtd/rest.allpackages",
+      "http://pear.php.net/dtd/rest.allpackages     http://pear.php.net/dtd/rest.allpackages.xsd",
+    );
+    writer.unindented_text("c", self.category.as_ref());
+    for item in &self.items {
+      writer.text("p", item.as_ref());
+    }
+    writer.finish()
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct PackageInfo<Str = CompactString> {
   pub name: Str,
@@ -96,29 +121,83 @@ pub struct PackageInfo<Str = CompactString> {
   pub license_uri: Option<Str>,
   pub summary: Str,
   pub description: Str,
+  /// Link to the release listing, from the `xlink:href` attribute of `<r>`
   pub release_uri: Str,
   pub parent_package: Option<Str>,
   /// If this package is deprecated, deprecation info
-  pub deprecation: Option<DeprecationInfo<Str>>
+  pub deprecation: Option<DeprecationInfo<Str>>,
 }
 
+impl<Str: AsRef<str>> PackageInfo<Str> {
+  /// Write as an `info.xml` document
+  ///
+  /// The category link is not stored, it is derived from the category name and the REST prefix of the release link
+  /// (e.g. `/rest/c/Tools+and+Utilities` for `/rest/r/protobuf`).
+  pub fn to_xml(&self) -> String {
+    let mut writer = RestXmlWriter::new(
+      "p",
+      "http://pear.php.net/dtd/rest.package",
+      "http://pear.php.net/dtd/rest.package    http://pear.php.net/dtd/rest.package.xsd",
+    );
+    self.write_xml(&mut writer);
+    writer.finish()
+  }
+
+  /// Write the children of the package node, also used by `packagesinfo.xml`
+  pub(crate) fn write_xml(&self, writer: &mut RestXmlWriter) {
+    let category = self.category.as_ref();
+    let rest_prefix = self
+      .release_uri
+      .as_ref()
+      .rsplit_once("r/")
+      .map_or("/rest/", |(prefix, _)| prefix);
+    writer
+      .text("n", self.name.as_ref())
+      .text("c", self.channel.as_ref())
+      .link_text("ca", &format!("{rest_prefix}c/{}", php_urlencode(category)), category)
+      .text("l", self.license.as_ref());
+    if let Some(license_uri) = &self.license_uri {
+      writer.text("lu", license_uri.as_ref());
+    }
+    writer
+      .text("s", self.summary.as_ref())
+      .text("d", self.description.as_ref())
+      .link("r", self.release_uri.as_ref());
+    if let Some(parent) = &self.parent_package {
+      writer.text("pa", parent.as_ref());
+    }
+    if let Some(deprecation) = &self.deprecation {
+      writer
+        .text("dc", deprecation.recommended_channel.as_ref())
+        .text("dp", deprecation.recommended_package.as_ref());
+    }
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DeprecationInfo<Str = CompactString> {
   /// Channel of the recommended replacement
-  recommended_channel: Str,
+  pub recommended_channel: Str,
   /// Name of the recommended replacement
-  recommended_package: Str,
+  pub recommended_package: Str,
 }
 
 impl PackageInfo<CompactString> {
-  pub fn from_xml(mut input: &[u8]) -> Self {
-    let input = &mut input;
-    let sink = RcDom::default();
-    let dom: RcDom = parse_document(sink, XmlParseOpts::default()).from_utf8().read_from(input).unwrap();
-    Self::from_rc_dom(dom).unwrap()
+  /// Read from an XML document, panics if the input is invalid.
+  ///
+  /// See [`Self::try_from_xml`] for the fallible version.
+  pub fn from_xml(input: &[u8]) -> Self {
+    Self::try_from_xml(input).unwrap()
+  }
+
+  pub fn try_from_xml(input: &[u8]) -> Result<Self, FromXmlError> {
+    let dom = parse_rc_dom(input)?;
+    Ok(Self::from_rc_dom(dom)?)
   }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
 pub enum PackageInfoFromRcDomError {
   #[error("failed to find root node")]
@@ -135,6 +214,7 @@ impl PackageInfo<CompactString> {
   }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
 pub enum PackageInfoFromXmlNodeError {
   #[error("unexpected child node type at index {0}")]
@@ -225,7 +305,7 @@ impl PackageInfo<CompactString> {
     for (i, handle) in node.children.borrow().iter().enumerate() {
       let node: &Node = handle;
       match &node.data {
-        NodeData::Element { name, .. } => {
+        NodeData::Element { name, attrs, .. } => {
           if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("n") {
             let new = get_text(node).map_err(|_| E::ReadName(i))?;
             let old = package_name.replace(new);
@@ -292,8 +372,11 @@ impl PackageInfo<CompactString> {
             if description.is_none() {
               return Err(E::MissingDescription);
             }
-            let new = get_text(node).map_err(|_| E::ReadRelease(i))?;
-            let old = release.replace(new);
+            let attrs = &*attrs.borrow();
+            let new = get_link_attr(attrs)
+              .map_err(|_| E::ReadRelease(i))?
+              .ok_or(E::ReadRelease(i))?;
+            let old = release.replace(CompactString::new(new.value.as_ref()));
             if old.is_some() {
               return Err(E::DuplicateRelease);
             }
@@ -329,8 +412,8 @@ impl PackageInfo<CompactString> {
           } else {
             return Err(E::ChildType(i));
           }
-        },
-        NodeData::Text { .. } | NodeData::Comment { .. } => { continue },
+        }
+        NodeData::Text { .. } | NodeData::Comment { .. } => continue,
         _ => return Err(E::ChildType(i)),
       }
     }
@@ -346,7 +429,10 @@ impl PackageInfo<CompactString> {
       release_uri: release.ok_or(E::MissingRelease)?,
       parent_package: parent,
       deprecation: match (deprecation_channel, deprecation_package) {
-        (Some(dc), Some(dp)) => Some(DeprecationInfo { recommended_channel: dc, recommended_package: dp }),
+        (Some(dc), Some(dp)) => Some(DeprecationInfo {
+          recommended_channel: dc,
+          recommended_package: dp,
+        }),
         (Some(_), None) => return Err(E::MissingDeprecationPackage),
         (None, Some(_)) => return Err(E::MissingDeprecationChannel),
         (None, None) => None,
@@ -367,6 +453,25 @@ mod test {
     assert_eq!(actual.items.len(), 434);
   }
 
+  #[test]
+  pub fn test_package_listing_try_from_html() {
+    let input = b"<html><body><h1>502 Bad Gateway</h1></body></html>";
+    let actual = PackageListing::try_from_xml(input);
+    assert_eq!(
+      actual,
+      Err(FromXmlError::PackageListing(PackageListingFromRcDomError::RootNotFound))
+    );
+  }
+
+  #[test]
+  pub fn test_package_listing_to_xml() {
+    let input = include_bytes!("../../test-resources/get_package_list/pecl/input.xml");
+    let listing = PackageListing::from_xml(input);
+    let actual = listing.to_xml();
+    assert_eq!(actual.as_bytes(), input);
+    assert_eq!(PackageListing::from_xml(actual.as_bytes()), listing);
+  }
+
   #[test]
   pub fn test_package_info_from_xml() {
     let input = include_bytes!("../../test-resources/get_package_info/pecl_protobuf/input.xml");
@@ -374,4 +479,60 @@ mod test {
     assert_eq!(actual.name.as_str(), "protobuf");
     assert_eq!(actual.category.as_str(), "Tools and Utilities");
   }
+
+  #[test]
+  pub fn test_package_info_to_xml() {
+    let input = include_bytes!("../../test-resources/get_package_info/pecl_protobuf/input.xml");
+    let info = PackageInfo::from_xml(input);
+    let actual = info.to_xml();
+    assert_eq!(actual.as_bytes(), input);
+
+    let deprecated = PackageInfo {
+      license_uri: Some(CompactString::new("https://opensource.org/license/bsd-3-clause")),
+      description: CompactString::new("Fast & <small>"),
+      parent_package: Some(CompactString::new("protobuf_base")),
+      deprecation: Some(DeprecationInfo {
+        recommended_channel: CompactString::new("pecl.php.net"),
+        recommended_package: CompactString::new("protobuf2"),
+      }),
+      ..info
+    };
+    assert_eq!(PackageInfo::from_xml(deprecated.to_xml().as_bytes()), deprecated);
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  pub fn test_package_listing_serde() {
+    let input = include_bytes!("../../test-resources/get_package_list/pecl/input.xml");
+    let listing = PackageListing::from_xml(input);
+    let json = serde_json::to_value(&listing).unwrap();
+    assert_eq!(json["category"], "pecl.php.net");
+    assert_eq!(json["items"][0], "ahocorasick");
+    assert_eq!(serde_json::from_value::<PackageListing>(json).unwrap(), listing);
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  pub fn test_package_info_serde() {
+    let input = include_bytes!("../../test-resources/get_package_info/pecl_protobuf/input.xml");
+    let info = PackageInfo::from_xml(input);
+    let json = serde_json::to_value(&info).unwrap();
+    assert_eq!(json["release_uri"], "/rest/r/protobuf");
+    assert_eq!(json["deprecation"], serde_json::Value::Null);
+    assert_eq!(serde_json::from_value::<PackageInfo>(json).unwrap(), info);
+
+    let deprecated = PackageInfo {
+      deprecation: Some(DeprecationInfo {
+        recommended_channel: CompactString::new("pecl.php.net"),
+        recommended_package: CompactString::new("protobuf2"),
+      }),
+      ..info
+    };
+    let json = serde_json::to_value(&deprecated).unwrap();
+    assert_eq!(
+      json["deprecation"],
+      serde_json::json!({"recommended_channel": "pecl.php.net", "recommended_package": "protobuf2"})
+    );
+    assert_eq!(serde_json::from_value::<PackageInfo>(json).unwrap(), deprecated);
+  }
 }
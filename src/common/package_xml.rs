@@ -0,0 +1,777 @@
+//! `package.xml` description files
+//!
+//! Unlike the REST documents, `package.xml` has dozens of element kinds, nested several levels deep and shared between
+//! models (maintainers, licenses, version constraints...). Instead of one error variant per field and model, as for
+//! [`ReleaseFromXmlNodeError`](crate::common::release::ReleaseFromXmlNodeError), the models of this file and of
+//! [`dependency`](crate::common::dependency) are read with the `Elements` helper and share
+//! [`PackageXmlFromXmlNodeError`], whose variants carry the element names from the XSD schema.
+
+use crate::common::dependency::PackageDependencies;
+use crate::common::from_xml::FromXmlError;
+use crate::xml_util::{find_root, get_plain_attr, get_text, parse_rc_dom};
+use compact_str::CompactString;
+use core::str::FromStr;
+use markup5ever_rcdom::{Handle, Node, NodeData, RcDom};
+
+/// Package description file, for `package.xml` version 2.0 and 2.1
+///
+/// <https://pear.php.net/dtd/package-2.0.xsd>
+/// <https://pear.php.net/dtd/package-2.1.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PackageXml<Str = CompactString> {
+  /// Version of the `package.xml` format, `2.0` or `2.1`
+  pub format_version: Str,
+  /// Version of the PEAR installer which generated this file
+  pub packager_version: Option<Str>,
+  pub name: Str,
+  /// Channel of the package, `None` for URI-based packages
+  pub channel: Option<Str>,
+  /// Static URI of the package, for packages outside of a channel
+  pub uri: Option<Str>,
+  pub extends: Option<Str>,
+  pub summary: Str,
+  pub description: Str,
+  pub maintainers: Vec<PackageXmlMaintainer<Str>>,
+  /// Release date, `YYYY-MM-DD`
+  pub date: Str,
+  /// Release time, `HH:MM:SS`
+  pub time: Option<Str>,
+  pub version: ReleaseApi<Str>,
+  pub stability: ReleaseApi<Str>,
+  pub license: PackageXmlLicense<Str>,
+  pub notes: Str,
+  /// Root directory of the package contents
+  pub contents: PackageDir<Str>,
+  pub dependencies: PackageDependencies<Str>,
+  /// Name of the extension provided by this package
+  pub provides_extension: Option<Str>,
+  /// For binary releases, name of the source package
+  pub src_package: Option<Str>,
+  pub src_channel: Option<Str>,
+  pub src_uri: Option<Str>,
+  pub release: PackageXmlRelease<Str>,
+  pub changelog: Vec<ChangelogEntry<Str>>,
+}
+
+/// Pair of release and API values, used for both versions and stabilities
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ReleaseApi<Str = CompactString> {
+  pub release: Str,
+  pub api: Str,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum MaintainerRole {
+  Lead,
+  Developer,
+  Contributor,
+  Helper,
+}
+
+impl MaintainerRole {
+  pub const fn as_str(self) -> &'static str {
+    match self {
+      Self::Lead => "lead",
+      Self::Developer => "developer",
+      Self::Contributor => "contributor",
+      Self::Helper => "helper",
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+#[error("unknown maintainer role")]
+pub struct UnknownMaintainerRole;
+
+impl FromStr for MaintainerRole {
+  type Err = UnknownMaintainerRole;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "lead" => Ok(Self::Lead),
+      "developer" => Ok(Self::Developer),
+      "contributor" => Ok(Self::Contributor),
+      "helper" => Ok(Self::Helper),
+      _ => Err(UnknownMaintainerRole),
+    }
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PackageXmlMaintainer<Str = CompactString> {
+  pub role: MaintainerRole,
+  /// Full name
+  pub name: Str,
+  /// Handle on the channel
+  pub user: Str,
+  pub email: Option<Str>,
+  pub active: Option<bool>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PackageXmlLicense<Str = CompactString> {
+  pub name: Str,
+  pub uri: Option<Str>,
+  /// Path of the license file inside the package
+  pub file_source: Option<Str>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PackageDir<Str = CompactString> {
+  pub name: Str,
+  pub base_install_dir: Option<Str>,
+  pub entries: Vec<PackageDirEntry<Str>>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum PackageDirEntry<Str = CompactString> {
+  Dir(PackageDir<Str>),
+  File(PackageFile<Str>),
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PackageFile<Str = CompactString> {
+  /// File name, relative to the parent directory
+  pub name: Str,
+  /// Installation role, e.g. `src`, `php`, `test` or `doc`
+  pub role: Str,
+  /// Hex-encoded MD5 checksum of the file
+  pub md5sum: Option<Str>,
+  pub base_install_dir: Option<Str>,
+}
+
+impl<Str: AsRef<str>> PackageDir<Str> {
+  /// List all the files in this directory tree, with their path relative to this directory
+  pub fn files(&self) -> Vec<(String, &PackageFile<Str>)> {
+    let mut out = Vec::new();
+    self.collect_files("", &mut out);
+    out
+  }
+
+  fn collect_files<'a>(&'a self, prefix: &str, out: &mut Vec<(String, &'a PackageFile<Str>)>) {
+    for entry in &self.entries {
+      match entry {
+        PackageDirEntry::Dir(dir) => {
+          let name = dir.name.as_ref().trim_matches('/');
+          let prefix = if name.is_empty() {
+            prefix.to_string()
+          } else {
+            format!("{prefix}{name}/")
+          };
+          dir.collect_files(&prefix, out);
+        }
+        PackageDirEntry::File(file) => {
+          out.push((format!("{prefix}{}", file.name.as_ref().trim_start_matches('/')), file));
+        }
+      }
+    }
+  }
+}
+
+/// Kind of release, with the kind-specific data
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PackageXmlRelease<Str = CompactString> {
+  /// Package of PHP files
+  Php,
+  /// Extension source release
+  ExtSrc(ExtSrcRelease<Str>),
+  /// Extension binary release
+  ExtBin,
+  /// Zend extension source release
+  ZendExtSrc(ExtSrcRelease<Str>),
+  /// Zend extension binary release
+  ZendExtBin,
+  /// Bundle of other packages
+  Bundle,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExtSrcRelease<Str = CompactString> {
+  /// Options passed to `configure`, the installer prompts for their value
+  pub configure_options: Vec<ConfigureOption<Str>>,
+  /// Names of the binary packages built from this source
+  pub binary_packages: Vec<Str>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ConfigureOption<Str = CompactString> {
+  pub name: Str,
+  pub prompt: Str,
+  pub default: Option<Str>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChangelogEntry<Str = CompactString> {
+  pub version: ReleaseApi<Str>,
+  pub stability: ReleaseApi<Str>,
+  pub date: Str,
+  pub license: Option<PackageXmlLicense<Str>>,
+  pub notes: Str,
+}
+
+/// Legacy package description file, for `package.xml` version 1.0
+///
+/// Only the fields required to identify the release and check its files are read.
+///
+/// <https://pear.php.net/dtd/package-1.0>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PackageXml1<Str = CompactString> {
+  pub name: Str,
+  pub summary: Str,
+  /// Release version
+  pub version: Str,
+  /// Release stability
+  pub state: Option<Str>,
+  /// Root directory of the release files, from `<release><filelist>`
+  pub contents: PackageDir<Str>,
+}
+
+impl PackageXml<CompactString> {
+  /// Read from an XML document, panics if the input is invalid.
+  ///
+  /// See [`Self::try_from_xml`] for the fallible version.
+  pub fn from_xml(input: &[u8]) -> Self {
+    Self::try_from_xml(input).unwrap()
+  }
+
+  pub fn try_from_xml(input: &[u8]) -> Result<Self, FromXmlError> {
+    let dom = parse_rc_dom(input)?;
+    Ok(Self::from_rc_dom(dom)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum PackageXmlFromRcDomError {
+  #[error("failed to find root node")]
+  RootNotFound,
+  #[error("unsupported package.xml format version {0:?}, expected 2.0 or 2.1")]
+  UnsupportedFormat(Option<CompactString>),
+  #[error("failed to read package.xml from XML Node")]
+  Read(#[from] PackageXmlFromXmlNodeError),
+}
+
+impl PackageXml<CompactString> {
+  pub fn from_rc_dom(dom: RcDom) -> Result<Self, PackageXmlFromRcDomError> {
+    let doc = dom.document;
+    let root = find_root(&doc, "package").map_err(|_| PackageXmlFromRcDomError::RootNotFound)?;
+    let format_version = match &root.data {
+      NodeData::Element { attrs, .. } => get_plain_attr(&attrs.borrow(), "version")
+        .ok()
+        .flatten()
+        .map(|a| CompactString::new(a.value.as_ref())),
+      _ => None,
+    };
+    match format_version.as_deref() {
+      Some("2.0") | Some("2.1") => {}
+      _ => return Err(PackageXmlFromRcDomError::UnsupportedFormat(format_version)),
+    }
+    Ok(Self::from_xml_node(&root)?)
+  }
+}
+
+/// Error when reading a `package.xml` model from an XML node
+///
+/// Element and attribute names are the ones of the XSD schema, e.g. `Missing("notes", "package")` for a `<package>`
+/// without `<notes>`. Child indexes count all the child nodes of the parent, including text nodes.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum PackageXmlFromXmlNodeError {
+  #[error("unexpected child node in <{0}> at index {1}")]
+  ChildType(CompactString, usize),
+  #[error("node <{0}> is malformed at index {1}")]
+  Read(CompactString, usize),
+  #[error("node <{0}> is missing in <{1}>")]
+  Missing(CompactString, CompactString),
+  #[error("node <{0}> is duplicated in <{1}>")]
+  Duplicate(CompactString, CompactString),
+  #[error("attribute `{0}` of <{1}> is malformed")]
+  ReadAttribute(CompactString, CompactString),
+  #[error("attribute `{0}` of <{1}> is missing")]
+  MissingAttribute(CompactString, CompactString),
+  #[error("node <{0}> has an invalid value")]
+  InvalidValue(CompactString),
+}
+
+impl PackageXml<CompactString> {
+  pub fn from_xml_node(node: &Node) -> Result<Self, PackageXmlFromXmlNodeError> {
+    use PackageXmlFromXmlNodeError as E;
+
+    let attrs = match &node.data {
+      NodeData::Element { attrs, .. } => attrs.borrow().clone(),
+      _ => Vec::new(),
+    };
+    let format_version = get_plain_attr(&attrs, "version")
+      .map_err(|_| E::ReadAttribute("version".into(), "package".into()))?
+      .ok_or_else(|| E::MissingAttribute("version".into(), "package".into()))?;
+    let packager_version = get_plain_attr(&attrs, "packagerversion")
+      .map_err(|_| E::ReadAttribute("packagerversion".into(), "package".into()))?;
+
+    let children = Elements::new(node, "package")?;
+    children.check_names(&[
+      "name",
+      "channel",
+      "uri",
+      "extends",
+      "summary",
+      "description",
+      "lead",
+      "developer",
+      "contributor",
+      "helper",
+      "date",
+      "time",
+      "version",
+      "stability",
+      "license",
+      "notes",
+      "contents",
+      "compatible",
+      "dependencies",
+      "usesrole",
+      "usestask",
+      "providesextension",
+      "srcpackage",
+      "srcchannel",
+      "srcuri",
+      "phprelease",
+      "extsrcrelease",
+      "extbinrelease",
+      "zendextsrcrelease",
+      "zendextbinrelease",
+      "bundle",
+      "changelog",
+    ])?;
+
+    let mut maintainers = Vec::new();
+    for child in children.items.iter() {
+      let role: MaintainerRole = match child.name.parse() {
+        Ok(role) => role,
+        Err(_) => continue,
+      };
+      let fields = child.elements()?;
+      fields.check_names(&["name", "user", "email", "active"])?;
+      let active = match fields.optional_text("active")? {
+        None => None,
+        Some(active) => match active.trim() {
+          "yes" => Some(true),
+          "no" => Some(false),
+          _ => return Err(E::InvalidValue("active".into())),
+        },
+      };
+      maintainers.push(PackageXmlMaintainer {
+        role,
+        name: fields.required_text("name")?,
+        user: fields.required_text("user")?,
+        email: fields.optional_text("email")?,
+        active,
+      });
+    }
+
+    let contents = children.required("contents")?.elements()?;
+    contents.check_names(&["dir", "bundledpackage"])?;
+    let contents = match contents.optional("dir")? {
+      Some(dir) => PackageDir::from_xml_node(dir, None)?,
+      None => PackageDir {
+        name: CompactString::new("/"),
+        base_install_dir: None,
+        entries: Vec::new(),
+      },
+    };
+
+    let dependencies = PackageDependencies::from_xml_node(&children.required("dependencies")?.handle)?;
+
+    // `phprelease` may be repeated for different install conditions
+    let release = if children.all("phprelease").next().is_some() {
+      PackageXmlRelease::Php
+    } else if let Some(ext) = children.optional("extsrcrelease")? {
+      PackageXmlRelease::ExtSrc(ExtSrcRelease::from_xml_node(ext)?)
+    } else if children.optional("extbinrelease")?.is_some() {
+      PackageXmlRelease::ExtBin
+    } else if let Some(ext) = children.optional("zendextsrcrelease")? {
+      PackageXmlRelease::ZendExtSrc(ExtSrcRelease::from_xml_node(ext)?)
+    } else if children.optional("zendextbinrelease")?.is_some() {
+      PackageXmlRelease::ZendExtBin
+    } else if children.optional("bundle")?.is_some() {
+      PackageXmlRelease::Bundle
+    } else {
+      return Err(E::Missing("phprelease".into(), "package".into()));
+    };
+
+    let mut changelog = Vec::new();
+    if let Some(log) = children.optional("changelog")? {
+      let log = log.elements()?;
+      log.check_names(&["release"])?;
+      for entry in log.all("release") {
+        changelog.push(ChangelogEntry::from_xml_node(entry)?);
+      }
+    }
+
+    Ok(Self {
+      format_version: CompactString::new(format_version.value.as_ref()),
+      packager_version: packager_version.map(|a| CompactString::new(a.value.as_ref())),
+      name: children.required_text("name")?,
+      channel: children.optional_text("channel")?,
+      uri: children.optional_text("uri")?,
+      extends: children.optional_text("extends")?,
+      summary: children.required_text("summary")?,
+      description: children.required_text("description")?,
+      maintainers,
+      date: children.required_text("date")?,
+      time: children.optional_text("time")?,
+      version: ReleaseApi::from_xml_node(children.required("version")?)?,
+      stability: ReleaseApi::from_xml_node(children.required("stability")?)?,
+      license: PackageXmlLicense::from_xml_node(children.required("license")?)?,
+      notes: children.required_text("notes")?,
+      contents,
+      dependencies,
+      provides_extension: children.optional_text("providesextension")?,
+      src_package: children.optional_text("srcpackage")?,
+      src_channel: children.optional_text("srcchannel")?,
+      src_uri: children.optional_text("srcuri")?,
+      release,
+      changelog,
+    })
+  }
+}
+
+impl PackageXml1<CompactString> {
+  /// Read from an XML document, panics if the input is invalid.
+  ///
+  /// See [`Self::try_from_xml`] for the fallible version.
+  pub fn from_xml(input: &[u8]) -> Self {
+    Self::try_from_xml(input).unwrap()
+  }
+
+  pub fn try_from_xml(input: &[u8]) -> Result<Self, FromXmlError> {
+    let dom = parse_rc_dom(input)?;
+    Ok(Self::from_rc_dom(dom)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum PackageXml1FromRcDomError {
+  #[error("failed to find root node")]
+  RootNotFound,
+  #[error("unsupported package.xml format version {0:?}, expected 1.0")]
+  UnsupportedFormat(Option<CompactString>),
+  #[error("failed to read package.xml from XML Node")]
+  Read(#[from] PackageXmlFromXmlNodeError),
+}
+
+impl PackageXml1<CompactString> {
+  pub fn from_rc_dom(dom: RcDom) -> Result<Self, PackageXml1FromRcDomError> {
+    let doc = dom.document;
+    let root = find_root(&doc, "package").map_err(|_| PackageXml1FromRcDomError::RootNotFound)?;
+    let format_version = match &root.data {
+      NodeData::Element { attrs, .. } => get_plain_attr(&attrs.borrow(), "version")
+        .ok()
+        .flatten()
+        .map(|a| CompactString::new(a.value.as_ref())),
+      _ => None,
+    };
+    if format_version.as_deref() != Some("1.0") {
+      return Err(PackageXml1FromRcDomError::UnsupportedFormat(format_version));
+    }
+    Ok(Self::from_xml_node(&root)?)
+  }
+
+  pub fn from_xml_node(node: &Node) -> Result<Self, PackageXmlFromXmlNodeError> {
+    // version 1.0 has many more optional nodes, only check the ones that are read
+    let children = Elements::new(node, "package")?;
+    let release = children.required("release")?.elements()?;
+    let mut entries = Vec::new();
+    if let Some(filelist) = release.optional("filelist")? {
+      let filelist = filelist.elements()?;
+      filelist.check_names(&["dir", "file"])?;
+      for child in filelist.items.iter() {
+        // the role defaults to `php` in version 1.0
+        entries.push(PackageDirEntry::from_xml_node(child, Some("php"))?);
+      }
+    }
+    Ok(Self {
+      name: children.required_text("name")?,
+      summary: children.required_text("summary")?,
+      version: release.required_text("version")?,
+      state: release.optional_text("state")?,
+      contents: PackageDir {
+        name: CompactString::new("/"),
+        base_install_dir: None,
+        entries,
+      },
+    })
+  }
+}
+
+impl ReleaseApi<CompactString> {
+  fn from_xml_node(element: &Element) -> Result<Self, PackageXmlFromXmlNodeError> {
+    let fields = element.elements()?;
+    fields.check_names(&["release", "api"])?;
+    Ok(Self {
+      release: fields.required_text("release")?,
+      api: fields.required_text("api")?,
+    })
+  }
+}
+
+impl PackageXmlLicense<CompactString> {
+  fn from_xml_node(element: &Element) -> Result<Self, PackageXmlFromXmlNodeError> {
+    Ok(Self {
+      name: element.text()?,
+      uri: element.attr("uri")?,
+      file_source: element.attr("filesource")?,
+    })
+  }
+}
+
+impl PackageDir<CompactString> {
+  fn from_xml_node(element: &Element, default_role: Option<&str>) -> Result<Self, PackageXmlFromXmlNodeError> {
+    let children = element.elements()?;
+    children.check_names(&["dir", "file"])?;
+    let mut entries = Vec::new();
+    for child in children.items.iter() {
+      entries.push(PackageDirEntry::from_xml_node(child, default_role)?);
+    }
+    Ok(Self {
+      name: element.required_attr("name")?,
+      base_install_dir: element.attr("baseinstalldir")?,
+      entries,
+    })
+  }
+}
+
+impl PackageDirEntry<CompactString> {
+  /// Read a `<dir>` or `<file>` element
+  fn from_xml_node(element: &Element, default_role: Option<&str>) -> Result<Self, PackageXmlFromXmlNodeError> {
+    Ok(match element.name.as_str() {
+      "dir" => PackageDirEntry::Dir(PackageDir::from_xml_node(element, default_role)?),
+      _ => PackageDirEntry::File(PackageFile {
+        name: element.required_attr("name")?,
+        role: match (element.attr("role")?, default_role) {
+          (Some(role), _) => role,
+          (None, Some(role)) => CompactString::new(role),
+          (None, None) => element.required_attr("role")?,
+        },
+        md5sum: element.attr("md5sum")?,
+        base_install_dir: element.attr("baseinstalldir")?,
+      }),
+    })
+  }
+}
+
+impl ExtSrcRelease<CompactString> {
+  fn from_xml_node(element: &Element) -> Result<Self, PackageXmlFromXmlNodeError> {
+    let children = element.elements()?;
+    children.check_names(&["installconditions", "configureoption", "binarypackage", "filelist"])?;
+    let mut configure_options = Vec::new();
+    for option in children.all("configureoption") {
+      configure_options.push(ConfigureOption {
+        name: option.required_attr("name")?,
+        prompt: option.required_attr("prompt")?,
+        default: option.attr("default")?,
+      });
+    }
+    let mut binary_packages = Vec::new();
+    for package in children.all("binarypackage") {
+      binary_packages.push(package.text()?);
+    }
+    Ok(Self {
+      configure_options,
+      binary_packages,
+    })
+  }
+}
+
+impl ChangelogEntry<CompactString> {
+  fn from_xml_node(element: &Element) -> Result<Self, PackageXmlFromXmlNodeError> {
+    let fields = element.elements()?;
+    fields.check_names(&["version", "stability", "date", "license", "notes"])?;
+    Ok(Self {
+      version: ReleaseApi::from_xml_node(fields.required("version")?)?,
+      stability: ReleaseApi::from_xml_node(fields.required("stability")?)?,
+      date: fields.required_text("date")?,
+      license: match fields.optional("license")? {
+        Some(license) => Some(PackageXmlLicense::from_xml_node(license)?),
+        None => None,
+      },
+      notes: fields.required_text("notes")?,
+    })
+  }
+}
+
+/// Element child, with its index among all the children of its parent
+pub(crate) struct Element {
+  pub index: usize,
+  pub name: CompactString,
+  pub handle: Handle,
+}
+
+impl Element {
+  pub fn text(&self) -> Result<CompactString, PackageXmlFromXmlNodeError> {
+    get_text(&self.handle).map_err(|_| PackageXmlFromXmlNodeError::Read(self.name.clone(), self.index))
+  }
+
+  pub fn elements(&self) -> Result<Elements, PackageXmlFromXmlNodeError> {
+    Elements::new(&self.handle, &self.name)
+  }
+
+  pub fn attr(&self, name: &str) -> Result<Option<CompactString>, PackageXmlFromXmlNodeError> {
+    match &self.handle.data {
+      NodeData::Element { attrs, .. } => {
+        let attrs = &*attrs.borrow();
+        let attr = get_plain_attr(attrs, name)
+          .map_err(|_| PackageXmlFromXmlNodeError::ReadAttribute(name.into(), self.name.clone()))?;
+        Ok(attr.map(|a| CompactString::new(a.value.as_ref())))
+      }
+      _ => Ok(None),
+    }
+  }
+
+  pub fn required_attr(&self, name: &str) -> Result<CompactString, PackageXmlFromXmlNodeError> {
+    self
+      .attr(name)?
+      .ok_or_else(|| PackageXmlFromXmlNodeError::MissingAttribute(name.into(), self.name.clone()))
+  }
+}
+
+/// Element children of a node
+pub(crate) struct Elements {
+  pub parent: CompactString,
+  pub items: Vec<Element>,
+}
+
+impl Elements {
+  pub fn new(node: &Node, parent: &str) -> Result<Self, PackageXmlFromXmlNodeError> {
+    let mut items = Vec::new();
+    for (i, handle) in node.children.borrow().iter().enumerate() {
+      match &handle.data {
+        NodeData::Element { name, .. } => {
+          let local: &str = &name.local;
+          let name = match &name.prefix {
+            None => CompactString::new(local),
+            Some(prefix) => {
+              let prefix: &str = prefix;
+              compact_str::format_compact!("{prefix}:{local}")
+            }
+          };
+          items.push(Element {
+            index: i,
+            name,
+            handle: handle.clone(),
+          });
+        }
+        NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+        _ => return Err(PackageXmlFromXmlNodeError::ChildType(parent.into(), i)),
+      }
+    }
+    Ok(Self {
+      parent: parent.into(),
+      items,
+    })
+  }
+
+  /// Check that all the children have one of the expected names
+  pub fn check_names(&self, expected: &[&str]) -> Result<(), PackageXmlFromXmlNodeError> {
+    for item in self.items.iter() {
+      if !expected.contains(&item.name.as_str()) {
+        return Err(PackageXmlFromXmlNodeError::ChildType(self.parent.clone(), item.index));
+      }
+    }
+    Ok(())
+  }
+
+  pub fn all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+    self.items.iter().filter(move |item| item.name == name)
+  }
+
+  pub fn optional(&self, name: &str) -> Result<Option<&Element>, PackageXmlFromXmlNodeError> {
+    let mut found = self.items.iter().filter(|item| item.name == name);
+    let first = found.next();
+    if found.next().is_some() {
+      return Err(PackageXmlFromXmlNodeError::Duplicate(name.into(), self.parent.clone()));
+    }
+    Ok(first)
+  }
+
+  pub fn required(&self, name: &str) -> Result<&Element, PackageXmlFromXmlNodeError> {
+    self
+      .optional(name)?
+      .ok_or_else(|| PackageXmlFromXmlNodeError::Missing(name.into(), self.parent.clone()))
+  }
+
+  pub fn optional_text(&self, name: &str) -> Result<Option<CompactString>, PackageXmlFromXmlNodeError> {
+    match self.optional(name)? {
+      Some(element) => Ok(Some(element.text()?)),
+      None => Ok(None),
+    }
+  }
+
+  pub fn required_text(&self, name: &str) -> Result<CompactString, PackageXmlFromXmlNodeError> {
+    self.required(name)?.text()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  pub fn test_package_xml_from_xml() {
+    let input = include_bytes!("../../test-resources/get_package_xml/pecl_protobuf_4.27.0/input.xml");
+    let actual = PackageXml::from_xml(input);
+    assert_eq!(actual.name.as_str(), "protobuf");
+    assert_eq!(actual.version.release.as_str(), "4.27.0");
+    assert_eq!(actual.maintainers[0].role, MaintainerRole::Lead);
+    assert_eq!(actual.dependencies.required.php.min.as_deref(), Some("8.0.0"));
+    assert_eq!(actual.provides_extension.as_deref(), Some("protobuf"));
+    assert!(matches!(actual.release, PackageXmlRelease::ExtSrc(_)));
+    let files = actual.contents.files();
+    assert_eq!(files[0].0, "config.m4");
+    assert!(files
+      .iter()
+      .any(|(path, _)| path == "third_party/utf8_range/utf8_range.h"));
+    assert_eq!(actual.changelog.len(), 2);
+  }
+
+  #[test]
+  pub fn test_package_xml_from_xml_errors() {
+    let input = include_str!("../../test-resources/get_package_xml/pecl_protobuf_4.27.0/input.xml");
+    let read = |input: &str| {
+      let dom = parse_rc_dom(input.as_bytes()).unwrap();
+      let root = find_root(&dom.document, "package").unwrap();
+      PackageXml::from_xml_node(&root)
+    };
+    let start = input.find("<notes>").unwrap();
+    let end = input[start..].find("</notes>").unwrap() + start + "</notes>".len();
+    let actual = read(&format!("{}{}", &input[..start], &input[end..]));
+    assert_eq!(
+      actual,
+      Err(PackageXmlFromXmlNodeError::Missing("notes".into(), "package".into()))
+    );
+    let actual = read(&input.replacen("<summary>", "<summary>a</summary><summary>", 1));
+    assert_eq!(
+      actual,
+      Err(PackageXmlFromXmlNodeError::Duplicate(
+        "summary".into(),
+        "package".into()
+      ))
+    );
+  }
+}
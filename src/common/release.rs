@@ -1,25 +1,38 @@
+use crate::common::from_xml::FromXmlError;
+use crate::common::requirement::VersionRequirement;
+use crate::common::stability::Stability;
+use crate::common::version::PearVersion;
+use crate::context::PearUrl;
+use crate::url_util::url_join_root;
+use crate::xml_util::{find_root, get_link_attr, get_text, parse_rc_dom, RestXmlWriter};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 use compact_str::CompactString;
 use markup5ever_rcdom::{Node, NodeData, RcDom};
-use xml5ever::tendril::{TendrilSink};
-use xml5ever::driver::{parse_document, XmlParseOpts};
-use crate::xml_util::{find_root, get_link_attr, get_text};
+use url::Url;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ReleaseListing<Str = CompactString> {
   pub package: Str,
   pub channel: Str,
-  pub items: Vec<ShortRelease<Str>>,
+  pub items: Vec<ShortRelease>,
 }
 
 impl ReleaseListing<CompactString> {
-  pub fn from_xml(mut input: &[u8]) -> Self {
-    let input = &mut input;
-    let sink = RcDom::default();
-    let dom: RcDom = parse_document(sink, XmlParseOpts::default()).from_utf8().read_from(input).unwrap();
-    Self::from_rc_dom(dom).unwrap()
+  /// Read from an XML document, panics if the input is invalid.
+  ///
+  /// See [`Self::try_from_xml`] for the fallible version.
+  pub fn from_xml(input: &[u8]) -> Self {
+    Self::try_from_xml(input).unwrap()
+  }
+
+  pub fn try_from_xml(input: &[u8]) -> Result<Self, FromXmlError> {
+    let dom = parse_rc_dom(input)?;
+    Ok(Self::from_rc_dom(dom)?)
   }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
 pub enum ReleaseListingFromRcDomError {
   #[error("failed to find root node")]
@@ -36,6 +49,7 @@ impl ReleaseListing<CompactString> {
   }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
 pub enum ReleaseListingFromXmlNodeError {
   #[error("unexpected child node type at index {0}")]
@@ -60,7 +74,7 @@ impl ReleaseListing<CompactString> {
   pub fn from_xml_node(node: &Node) -> Result<Self, ReleaseListingFromXmlNodeError> {
     let mut package: Option<CompactString> = None;
     let mut channel: Option<CompactString> = None;
-    let mut items: Vec<ShortRelease<CompactString>> = Vec::new();
+    let mut items: Vec<ShortRelease> = Vec::new();
 
     for (i, handle) in node.children.borrow().iter().enumerate() {
       let node: &Node = handle;
@@ -92,8 +106,8 @@ impl ReleaseListing<CompactString> {
           } else {
             return Err(ReleaseListingFromXmlNodeError::ChildType(i));
           }
-        },
-        NodeData::Text { .. } | NodeData::Comment { .. } => { continue },
+        }
+        NodeData::Text { .. } | NodeData::Comment { .. } => continue,
         _ => return Err(ReleaseListingFromXmlNodeError::ChildType(i)),
       }
     }
@@ -106,13 +120,63 @@ impl ReleaseListing<CompactString> {
   }
 }
 
+impl ReleaseListing<CompactString> {
+  /// Releases with a stability of at least `min_stability`, e.g. `beta` and `stable` releases for `Stability::Beta`
+  pub fn at_least(&self, min_stability: &Stability) -> impl Iterator<Item = &ShortRelease> {
+    let min_stability = min_stability.clone();
+    self
+      .items
+      .iter()
+      .filter(move |r| r.stability.is_at_least(&min_stability))
+  }
+
+  /// Newest release matching `requirement`, with a stability of at least `min_stability`
+  ///
+  /// Releases with an unknown stability are ignored.
+  pub fn select(&self, requirement: &VersionRequirement, min_stability: &Stability) -> Option<&ShortRelease> {
+    self
+      .at_least(min_stability)
+      .filter(|r| requirement.matches(&r.version))
+      .max_by(|a, b| a.version.cmp(&b.version))
+  }
+
+  /// Newest release with a stability of at least `min_stability`
+  pub fn latest(&self, min_stability: &Stability) -> Option<&ShortRelease> {
+    self.select(&VersionRequirement::any(), min_stability)
+  }
+}
+
+impl<Str: AsRef<str>> ReleaseListing<Str> {
+  /// Write as an `allreleases.xml` document
+  pub fn to_xml(&self) -> String {
+    let mut writer = RestXmlWriter::new(
+      "a",
+      "http://pear.php.net/dtd/rest.allreleases",
+      "http://pear.php.net/dtd/rest.allreleases     http://pear.php.net/dtd/rest.allreleases.xsd",
+    );
+    writer.text("p", self.package.as_ref()).text("c", self.channel.as_ref());
+    for item in &self.items {
+      item.write_xml(&mut writer);
+    }
+    writer.finish()
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct ShortRelease<Str = CompactString> {
-  pub version: Str,
-  pub stability: Str,
-  // pub php_version: ...
+pub struct ShortRelease {
+  pub version: PearVersion,
+  pub stability: Stability,
+}
+
+impl ShortRelease {
+  /// Write as a single line `<r>` node
+  pub(crate) fn write_xml(&self, writer: &mut RestXmlWriter) {
+    writer.group("r", &[("v", self.version.as_str()), ("s", self.stability.as_str())]);
+  }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
 pub enum ShortReleaseFromXmlNodeError {
   #[error("unexpected child node type at index {0}")]
@@ -131,16 +195,17 @@ pub enum ShortReleaseFromXmlNodeError {
   DuplicateStability,
 }
 
-impl ShortRelease<CompactString> {
+impl ShortRelease {
   pub fn from_xml_node(node: &Node) -> Result<Self, ShortReleaseFromXmlNodeError> {
-    let mut version: Option<CompactString> = None;
-    let mut stability: Option<CompactString> = None;
+    let mut version: Option<PearVersion> = None;
+    let mut stability: Option<Stability> = None;
     for (i, handle) in node.children.borrow().iter().enumerate() {
       let node: &Node = handle;
       match &node.data {
         NodeData::Element { name, .. } => {
           if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("v") {
             let new = get_text(node).map_err(|_| ShortReleaseFromXmlNodeError::ReadVersion(i))?;
+            let new = PearVersion::new(new.as_str()).map_err(|_| ShortReleaseFromXmlNodeError::ReadVersion(i))?;
             let old = version.replace(new);
             if old.is_some() {
               return Err(ShortReleaseFromXmlNodeError::DuplicateVersion);
@@ -151,13 +216,14 @@ impl ShortRelease<CompactString> {
               return Err(ShortReleaseFromXmlNodeError::MissingVersion);
             }
             let new = get_text(node).map_err(|_| ShortReleaseFromXmlNodeError::ReadStability(i))?;
+            let new = Stability::new(new.as_str());
             let old = stability.replace(new);
             if old.is_some() {
               return Err(ShortReleaseFromXmlNodeError::DuplicateStability);
             }
           }
-        },
-        NodeData::Text { .. } | NodeData::Comment { .. } => { continue },
+        }
+        NodeData::Text { .. } | NodeData::Comment { .. } => continue,
         _ => return Err(ShortReleaseFromXmlNodeError::ChildType(i)),
       }
     }
@@ -169,44 +235,404 @@ impl ShortRelease<CompactString> {
   }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ReleaseListing2<Str = CompactString> {
+  pub package: Str,
+  pub channel: Str,
+  pub items: Vec<ShortRelease2>,
+}
+
+impl ReleaseListing2<CompactString> {
+  /// Read from an XML document, panics if the input is invalid.
+  ///
+  /// See [`Self::try_from_xml`] for the fallible version.
+  pub fn from_xml(input: &[u8]) -> Self {
+    Self::try_from_xml(input).unwrap()
+  }
+
+  pub fn try_from_xml(input: &[u8]) -> Result<Self, FromXmlError> {
+    let dom = parse_rc_dom(input)?;
+    Ok(Self::from_rc_dom(dom)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum ReleaseListing2FromRcDomError {
+  #[error("failed to find root node")]
+  RootNotFound,
+  #[error("failed to read listing from XML Node")]
+  Read(#[from] ReleaseListing2FromXmlNodeError),
+}
+
+impl ReleaseListing2<CompactString> {
+  pub fn from_rc_dom(dom: RcDom) -> Result<Self, ReleaseListing2FromRcDomError> {
+    let doc = dom.document;
+    let root = find_root(&doc, "a").map_err(|_| ReleaseListing2FromRcDomError::RootNotFound)?;
+    Ok(Self::from_xml_node(&root)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum ReleaseListing2FromXmlNodeError {
+  #[error("unexpected child node type at index {0}")]
+  ChildType(usize),
+  #[error("failed to read package at index {0}")]
+  ReadPackage(usize),
+  #[error("package node is missing")]
+  MissingPackage,
+  #[error("package node is duplicated")]
+  DuplicatePackage,
+  #[error("failed to read channel at index {0}")]
+  ReadChannel(usize),
+  #[error("channel node is missing")]
+  MissingChannel,
+  #[error("channel node is duplicated")]
+  DuplicateChannel,
+  #[error("failed to read release at index {1}")]
+  ReadRelease(#[source] ShortRelease2FromXmlNodeError, usize),
+}
+
+impl ReleaseListing2<CompactString> {
+  pub fn from_xml_node(node: &Node) -> Result<Self, ReleaseListing2FromXmlNodeError> {
+    use ReleaseListing2FromXmlNodeError as E;
+
+    let mut package: Option<CompactString> = None;
+    let mut channel: Option<CompactString> = None;
+    let mut items: Vec<ShortRelease2> = Vec::new();
+
+    for (i, handle) in node.children.borrow().iter().enumerate() {
+      let node: &Node = handle;
+      match &node.data {
+        NodeData::Element { name, .. } => {
+          if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("p") {
+            let new = get_text(node).map_err(|_| E::ReadPackage(i))?;
+            let old = package.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicatePackage);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("c") {
+            if package.is_none() {
+              return Err(E::MissingPackage);
+            }
+            let new = get_text(node).map_err(|_| E::ReadChannel(i))?;
+            let old = channel.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateChannel);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("r") {
+            if channel.is_none() {
+              return Err(E::MissingChannel);
+            }
+            let r = ShortRelease2::from_xml_node(node).map_err(|e| E::ReadRelease(e, i))?;
+            items.push(r);
+          } else {
+            return Err(E::ChildType(i));
+          }
+        }
+        NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+        _ => return Err(E::ChildType(i)),
+      }
+    }
+
+    Ok(Self {
+      package: package.ok_or(E::MissingPackage)?,
+      channel: channel.ok_or(E::MissingChannel)?,
+      items,
+    })
+  }
+}
+
+impl<Str: AsRef<str>> ReleaseListing2<Str> {
+  /// Write as an `allreleases2.xml` document
+  pub fn to_xml(&self) -> String {
+    let mut writer = RestXmlWriter::new(
+      "a",
+      "http://pear.php.net/dtd/rest.allreleases2",
+      "http://pear.php.net/dtd/rest.allreleases2     http://pear.php.net/dtd/rest.allreleases2.xsd",
+    );
+    writer.text("p", self.package.as_ref()).text("c", self.channel.as_ref());
+    for item in &self.items {
+      writer.group(
+        "r",
+        &[
+          ("v", item.version.as_str()),
+          ("s", item.stability.as_str()),
+          ("m", item.php_version.as_str()),
+        ],
+      );
+    }
+    writer.finish()
+  }
+}
+
+/// Release summary from `allreleases2.xml`, including the minimum PHP version
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ShortRelease2 {
+  pub version: PearVersion,
+  pub stability: Stability,
+  /// Minimum PHP version required by this release
+  pub php_version: PearVersion,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum ShortRelease2FromXmlNodeError {
+  #[error("unexpected child node type at index {0}")]
+  ChildType(usize),
+  #[error("version node <v> is malformed at index {0}")]
+  ReadVersion(usize),
+  #[error("version node <v> is missing")]
+  MissingVersion,
+  #[error("version node <v> is duplicated")]
+  DuplicateVersion,
+  #[error("stability node <s> is malformed at index {0}")]
+  ReadStability(usize),
+  #[error("stability node <s> is missing")]
+  MissingStability,
+  #[error("stability node <s> is duplicated")]
+  DuplicateStability,
+  #[error("minimum PHP version node <m> is malformed at index {0}")]
+  ReadPhpVersion(usize),
+  #[error("minimum PHP version node <m> is missing")]
+  MissingPhpVersion,
+  #[error("minimum PHP version node <m> is duplicated")]
+  DuplicatePhpVersion,
+}
+
+impl ShortRelease2 {
+  pub fn from_xml_node(node: &Node) -> Result<Self, ShortRelease2FromXmlNodeError> {
+    use ShortRelease2FromXmlNodeError as E;
+
+    let mut version: Option<PearVersion> = None;
+    let mut stability: Option<Stability> = None;
+    let mut php_version: Option<PearVersion> = None;
+    for (i, handle) in node.children.borrow().iter().enumerate() {
+      let node: &Node = handle;
+      match &node.data {
+        NodeData::Element { name, .. } => {
+          if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("v") {
+            let new = get_text(node).map_err(|_| E::ReadVersion(i))?;
+            let new = PearVersion::new(new.as_str()).map_err(|_| E::ReadVersion(i))?;
+            let old = version.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateVersion);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("s") {
+            if version.is_none() {
+              return Err(E::MissingVersion);
+            }
+            let new = get_text(node).map_err(|_| E::ReadStability(i))?;
+            let new = Stability::new(new.as_str());
+            let old = stability.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicateStability);
+            }
+          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("m") {
+            if stability.is_none() {
+              return Err(E::MissingStability);
+            }
+            let new = get_text(node).map_err(|_| E::ReadPhpVersion(i))?;
+            let new = PearVersion::new(new.as_str()).map_err(|_| E::ReadPhpVersion(i))?;
+            let old = php_version.replace(new);
+            if old.is_some() {
+              return Err(E::DuplicatePhpVersion);
+            }
+          }
+          // other nodes (such as compatibility info `<co>`) are ignored
+        }
+        NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+        _ => return Err(E::ChildType(i)),
+      }
+    }
+
+    Ok(Self {
+      version: version.ok_or(E::MissingVersion)?,
+      stability: stability.ok_or(E::MissingStability)?,
+      php_version: php_version.ok_or(E::MissingPhpVersion)?,
+    })
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Release<Str = CompactString> {
   pub package: ReleasePackage<Str>,
   pub channel: Str,
-  pub version: Str,
-  pub status: Str,
+  pub version: PearVersion,
+  pub status: Stability,
   pub license: Str,
   pub maintainer: Str,
   pub summary: Str,
   pub description: Str,
-  pub time: Str,
+  /// Release time, from `<da>`
+  ///
+  /// The REST API does not specify the timezone: channels write the local time of their server, without offset. Use
+  /// [`Release::time_utc`] for the usual UTC convention, or [`Release::time_in`] to pick the channel timezone.
+  pub time: NaiveDateTime,
   pub release_notes: Str,
-  pub archive: ReleaseArchive<Str>,
+  pub archive: ReleaseArchive,
   /// Link to extracted release info
   pub extracted_link: Str,
 }
 
+/// Release information from the REST 1.3 `v2.{version}.xml` file
+///
+/// <https://pear.php.net/dtd/rest.release2.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ReleaseV2<Str = CompactString> {
+  /// Fields shared with [`Release`]
+  pub release: Release<Str>,
+  /// API version
+  pub api_version: PearVersion,
+  /// Minimum PHP version
+  pub php_version: PearVersion,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ReleasePackage<Str = CompactString> {
   pub name: Str,
   pub link: Str,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct ReleaseArchive<Str = CompactString> {
+pub struct ReleaseArchive {
+  /// Size in bytes
   pub size: u64,
-  pub link: Str,
+  /// Download URL
+  ///
+  /// Relative links are resolved against the channel URL when it is known (see
+  /// [`Release::try_from_xml_with_base`]), else against `https://{channel}/`.
+  pub link: Url,
+}
+
+impl<Str> Release<Str> {
+  /// Release time, assuming that the channel uses UTC
+  pub fn time_utc(&self) -> DateTime<Utc> {
+    self.time.and_utc()
+  }
+
+  /// Release time, assuming that the channel uses the timezone `tz`
+  ///
+  /// Returns `None` if the local time is ambiguous or does not exist in `tz` (e.g. during a DST transition).
+  pub fn time_in<Tz: TimeZone>(&self, tz: &Tz) -> Option<DateTime<Tz>> {
+    tz.from_local_datetime(&self.time).single()
+  }
+}
+
+impl<Str: AsRef<str>> Release<Str> {
+  /// Write as a `{version}.xml` document
+  ///
+  /// The archive link is always written as an absolute URL.
+  pub fn to_xml(&self) -> String {
+    let mut writer = RestXmlWriter::new(
+      "r",
+      "http://pear.php.net/dtd/rest.release",
+      "http://pear.php.net/dtd/rest.release     http://pear.php.net/dtd/rest.release.xsd",
+    );
+    self.write_xml(&mut writer, None);
+    writer.finish()
+  }
+
+  /// Write the children of the release node, with the API and PHP versions of `v2.{version}.xml`
+  fn write_xml(&self, writer: &mut RestXmlWriter, v2: Option<(&PearVersion, &PearVersion)>) {
+    writer
+      .link_text("p", self.package.link.as_ref(), self.package.name.as_ref())
+      .text("c", self.channel.as_ref())
+      .text("v", self.version.as_str());
+    if let Some((api_version, php_version)) = v2 {
+      writer.text("a", api_version.as_str()).text("mp", php_version.as_str());
+    }
+    writer
+      .text("st", self.status.as_str())
+      .text("l", self.license.as_ref())
+      .text("m", self.maintainer.as_ref())
+      .text("s", self.summary.as_ref())
+      .text("d", self.description.as_ref())
+      .text("da", &self.time.format("%Y-%m-%d %H:%M:%S").to_string())
+      .text("n", self.release_notes.as_ref())
+      .text("f", &self.archive.size.to_string())
+      .text("g", self.archive.link.as_str())
+      .link("x", self.extracted_link.as_ref());
+  }
+}
+
+impl<Str: AsRef<str>> ReleaseV2<Str> {
+  /// Write as a `v2.{version}.xml` document
+  pub fn to_xml(&self) -> String {
+    let mut writer = RestXmlWriter::new(
+      "r",
+      "http://pear.php.net/dtd/rest.release2",
+      "http://pear.php.net/dtd/rest.release2     http://pear.php.net/dtd/rest.release2.xsd",
+    );
+    self
+      .release
+      .write_xml(&mut writer, Some((&self.api_version, &self.php_version)));
+    writer.finish()
+  }
+}
+
+/// Parse a release date such as `2024-05-23 14:46:50`, a date without time is read as midnight
+pub(crate) fn parse_release_date(raw: &str) -> Option<NaiveDateTime> {
+  let raw = raw.trim();
+  NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S")
+    .ok()
+    .or_else(|| {
+      NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .ok()
+        .map(|d| d.and_time(NaiveTime::MIN))
+    })
+}
+
+/// Parse a link found in a channel document
+///
+/// Relative links are resolved against `base` if provided, else against `https://{channel}/`. The channel name may
+/// include a port or a path, such as `localhost:8080` or `example.com/pear`.
+pub(crate) fn parse_channel_link(channel: &str, link: &str, base: Option<&Url>) -> Option<Url> {
+  let link = link.trim();
+  match Url::parse(link) {
+    Ok(url) => Some(url),
+    Err(url::ParseError::RelativeUrlWithoutBase) => match base {
+      Some(base) => base.join(link).ok(),
+      None => Url::parse(&format!("https://{}/", channel.trim_matches('/')))
+        .ok()?
+        .join(link)
+        .ok(),
+    },
+    Err(_) => None,
+  }
 }
 
 impl Release<CompactString> {
-  pub fn from_xml(mut input: &[u8]) -> Self {
-    let input = &mut input;
-    let sink = RcDom::default();
-    let dom: RcDom = parse_document(sink, XmlParseOpts::default()).from_utf8().read_from(input).unwrap();
-    Self::from_rc_dom(dom).unwrap()
+  /// Read from an XML document, panics if the input is invalid.
+  ///
+  /// See [`Self::try_from_xml`] for the fallible version.
+  pub fn from_xml(input: &[u8]) -> Self {
+    Self::try_from_xml(input).unwrap()
+  }
+
+  pub fn try_from_xml(input: &[u8]) -> Result<Self, FromXmlError> {
+    let dom = parse_rc_dom(input)?;
+    Ok(Self::from_rc_dom(dom)?)
+  }
+
+  /// Read from an XML document, resolving a relative archive link against the channel at `pear_url`
+  pub fn try_from_xml_with_base(input: &[u8], pear_url: &PearUrl) -> Result<Self, FromXmlError> {
+    let dom = parse_rc_dom(input)?;
+    let root = find_root(&dom.document, "r").map_err(|_| ReleaseFromRcDomError::RootNotFound)?;
+    let base = url_join_root(pear_url, [""]);
+    let node = read_release(&root, false, Some(&base)).map_err(ReleaseFromRcDomError::Read)?;
+    Ok(node.release)
   }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
 pub enum ReleaseFromRcDomError {
   #[error("failed to find root node")]
@@ -223,6 +649,7 @@ impl Release<CompactString> {
   }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
 pub enum ReleaseFromXmlNodeError {
   #[error("unexpected child node type at index {0}")]
@@ -249,6 +676,18 @@ pub enum ReleaseFromXmlNodeError {
   MissingVersion,
   #[error("version node <v> is duplicated")]
   DuplicateVersion,
+  #[error("api version node <a> is malformed at index {0}")]
+  ReadApiVersion(usize),
+  #[error("api version node <a> is missing")]
+  MissingApiVersion,
+  #[error("api version node <a> is duplicated")]
+  DuplicateApiVersion,
+  #[error("php version node <mp> is malformed at index {0}")]
+  ReadPhpVersion(usize),
+  #[error("php version node <mp> is missing")]
+  MissingPhpVersion,
+  #[error("php version node <mp> is duplicated")]
+  DuplicatePhpVersion,
   #[error("status node <st> is malformed at index {0}")]
   ReadStatus(usize),
   #[error("status node <st> is missing")]
@@ -285,6 +724,8 @@ pub enum ReleaseFromXmlNodeError {
   MissingDate,
   #[error("date node <da> is duplicated")]
   DuplicateDate,
+  #[error("date node <da> contains invalid value")]
+  InvalidDate,
   #[error("release notes node <n> is malformed at index {0}")]
   ReadReleaseNotes(usize),
   #[error("release notes node <n> is missing")]
@@ -305,6 +746,8 @@ pub enum ReleaseFromXmlNodeError {
   MissingArchiveLink,
   #[error("archive link node <g> is duplicated")]
   DuplicateArchiveLink,
+  #[error("archive link node <g> contains invalid value")]
+  InvalidArchiveLink,
   #[error("extracted link node <x> is malformed at index {0}")]
   ReadExtracted(usize),
   #[error("extracted link node <x> is missing")]
@@ -317,172 +760,284 @@ pub enum ReleaseFromXmlNodeError {
 
 impl Release<CompactString> {
   pub fn from_xml_node(node: &Node) -> Result<Self, ReleaseFromXmlNodeError> {
-    use ReleaseFromXmlNodeError as E;
+    read_release(node, false, None).map(|node| node.release)
+  }
+}
 
-    let mut package: Option<ReleasePackage<CompactString>> = None;
-    let mut channel: Option<CompactString> = None;
-    let mut version: Option<CompactString> = None;
-    let mut status: Option<CompactString> = None;
-    let mut license: Option<CompactString> = None;
-    let mut maintainer: Option<CompactString> = None;
-    let mut summary: Option<CompactString> = None;
-    let mut description: Option<CompactString> = None;
-    let mut date: Option<CompactString> = None;
-    let mut release_notes: Option<CompactString> = None;
-    let mut archive_size: Option<CompactString> = None;
-    let mut archive_link: Option<CompactString> = None;
-    let mut extracted: Option<CompactString> = None;
+/// Release node contents, shared by `{version}.xml` and `v2.{version}.xml`
+struct ReleaseNode {
+  release: Release<CompactString>,
+  api_version: Option<PearVersion>,
+  php_version: Option<PearVersion>,
+}
 
-    for (i, handle) in node.children.borrow().iter().enumerate() {
-      let node: &Node = handle;
-      match &node.data {
-        NodeData::Element { name, attrs, .. } => {
-          if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("p") {
-            let attrs = &*attrs.borrow();
-            let new_name = get_text(node).map_err(|_| E::ReadPackage(i))?;
-            let new_link = get_link_attr(attrs.as_slice()).map_err(|_| E::DuplicatePackageLink(i) )?.ok_or(E::MissingPackageLink(i))?;
-            let new_package = ReleasePackage {
-              name: new_name,
-              link: CompactString::new(new_link.value.as_ref()),
-            };
-            let old = package.replace(new_package);
-            if old.is_some() {
-              return Err(E::DuplicatePackage);
-            }
-          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("c") {
-            if package.is_none() {
-              return Err(E::MissingPackage);
-            }
-            let new = get_text(node).map_err(|_| E::ReadChannel(i))?;
-            let old = channel.replace(new);
-            if old.is_some() {
-              return Err(E::DuplicateChannel);
-            }
-          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("v") {
-            if channel.is_none() {
-              return Err(E::MissingChannel);
-            }
-            let new = get_text(node).map_err(|_| E::ReadVersion(i))?;
-            let old = version.replace(new);
-            if old.is_some() {
-              return Err(E::DuplicateVersion);
-            }
-          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("st") {
-            if version.is_none() {
-              return Err(E::MissingVersion);
-            }
-            let new = get_text(node).map_err(|_| E::ReadStatus(i))?;
-            let old = status.replace(new);
-            if old.is_some() {
-              return Err(E::DuplicateStatus);
-            }
-          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("l") {
-            if status.is_none() {
-              return Err(E::MissingStatus);
-            }
-            let new = get_text(node).map_err(|_| E::ReadLicense(i))?;
-            let old = license.replace(new);
-            if old.is_some() {
-              return Err(E::DuplicateLicense);
-            }
-          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("m") {
-            if license.is_none() {
-              return Err(E::MissingLicense);
-            }
-            let new = get_text(node).map_err(|_| E::ReadMaintainer(i))?;
-            let old = maintainer.replace(new);
-            if old.is_some() {
-              return Err(E::DuplicateMaintainer);
-            }
-          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("s") {
-            if maintainer.is_none() {
-              return Err(E::MissingMaintainer);
-            }
-            let new = get_text(node).map_err(|_| E::ReadSummary(i))?;
-            let old = summary.replace(new);
-            if old.is_some() {
-              return Err(E::DuplicateSummary);
-            }
-          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("d") {
-            if summary.is_none() {
-              return Err(E::MissingSummary);
-            }
-            let new = get_text(node).map_err(|_| E::ReadDescription(i))?;
-            let old = description.replace(new);
-            if old.is_some() {
-              return Err(E::DuplicateDescription);
-            }
-          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("da") {
-            if description.is_none() {
-              return Err(E::MissingDescription);
-            }
-            let new = get_text(node).map_err(|_| E::ReadDate(i))?;
-            let old = date.replace(new);
-            if old.is_some() {
-              return Err(E::DuplicateDate);
-            }
-          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("n") {
-            if date.is_none() {
-              return Err(E::MissingDate);
-            }
-            let new = get_text(node).map_err(|_| E::ReadReleaseNotes(i))?;
-            let old = release_notes.replace(new);
-            if old.is_some() {
-              return Err(E::DuplicateReleaseNotes);
-            }
-          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("f") {
-            if release_notes.is_none() {
-              return Err(E::MissingReleaseNotes);
-            }
-            let new = get_text(node).map_err(|_| E::ReadArchiveSize(i))?;
-            let old = archive_size.replace(new);
-            if old.is_some() {
-              return Err(E::DuplicateArchiveSize);
-            }
-          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("g") {
-            if archive_size.is_none() {
-              return Err(E::MissingArchiveSize);
-            }
-            let new = get_text(node).map_err(|_| E::ReadArchiveLink(i))?;
-            let old = archive_link.replace(new);
-            if old.is_some() {
-              return Err(E::DuplicateArchiveLink);
-            }
-          } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("x") {
-            let attrs = &*attrs.borrow();
-            if archive_link.is_none() {
-              return Err(E::MissingArchiveLink);
-            }
-            let new = get_link_attr(attrs).map_err(|_| E::ReadExtracted(i))?.ok_or(E::InvalidExtracted)?;
-            let old = extracted.replace(CompactString::new(new.value.as_ref()));
-            if old.is_some() {
-              return Err(E::DuplicateExtracted);
-            }
-          } else {
-            return Err(E::ChildType(i));
+/// Read the children of a `<r>` release node.
+///
+/// When `v2` is set, the `<a>` (API version) and `<mp>` (minimum PHP version) nodes of `v2.{version}.xml` are required
+/// between `<v>` and `<st>`. A relative archive link is resolved against `base`, or against the channel name.
+fn read_release(node: &Node, v2: bool, base: Option<&Url>) -> Result<ReleaseNode, ReleaseFromXmlNodeError> {
+  use ReleaseFromXmlNodeError as E;
+
+  let mut package: Option<ReleasePackage<CompactString>> = None;
+  let mut channel: Option<CompactString> = None;
+  let mut version: Option<PearVersion> = None;
+  let mut api_version: Option<PearVersion> = None;
+  let mut php_version: Option<PearVersion> = None;
+  let mut status: Option<Stability> = None;
+  let mut license: Option<CompactString> = None;
+  let mut maintainer: Option<CompactString> = None;
+  let mut summary: Option<CompactString> = None;
+  let mut description: Option<CompactString> = None;
+  let mut date: Option<CompactString> = None;
+  let mut release_notes: Option<CompactString> = None;
+  let mut archive_size: Option<CompactString> = None;
+  let mut archive_link: Option<CompactString> = None;
+  let mut extracted: Option<CompactString> = None;
+
+  for (i, handle) in node.children.borrow().iter().enumerate() {
+    let node: &Node = handle;
+    match &node.data {
+      NodeData::Element { name, attrs, .. } => {
+        if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("p") {
+          let attrs = &*attrs.borrow();
+          let new_name = get_text(node).map_err(|_| E::ReadPackage(i))?;
+          let new_link = get_link_attr(attrs.as_slice())
+            .map_err(|_| E::DuplicatePackageLink(i))?
+            .ok_or(E::MissingPackageLink(i))?;
+          let new_package = ReleasePackage {
+            name: new_name,
+            link: CompactString::new(new_link.value.as_ref()),
+          };
+          let old = package.replace(new_package);
+          if old.is_some() {
+            return Err(E::DuplicatePackage);
           }
-        },
-        NodeData::Text { .. } | NodeData::Comment { .. } => { continue },
-        _ => return Err(E::ChildType(i)),
+        } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("c") {
+          if package.is_none() {
+            return Err(E::MissingPackage);
+          }
+          let new = get_text(node).map_err(|_| E::ReadChannel(i))?;
+          let old = channel.replace(new);
+          if old.is_some() {
+            return Err(E::DuplicateChannel);
+          }
+        } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("v") {
+          if channel.is_none() {
+            return Err(E::MissingChannel);
+          }
+          let new = get_text(node).map_err(|_| E::ReadVersion(i))?;
+          let new = PearVersion::new(new.as_str()).map_err(|_| E::ReadVersion(i))?;
+          let old = version.replace(new);
+          if old.is_some() {
+            return Err(E::DuplicateVersion);
+          }
+        } else if v2 && name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("a") {
+          if version.is_none() {
+            return Err(E::MissingVersion);
+          }
+          let new = get_text(node).map_err(|_| E::ReadApiVersion(i))?;
+          let new = PearVersion::new(new.as_str()).map_err(|_| E::ReadApiVersion(i))?;
+          let old = api_version.replace(new);
+          if old.is_some() {
+            return Err(E::DuplicateApiVersion);
+          }
+        } else if v2 && name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("mp") {
+          if api_version.is_none() {
+            return Err(E::MissingApiVersion);
+          }
+          let new = get_text(node).map_err(|_| E::ReadPhpVersion(i))?;
+          let new = PearVersion::new(new.as_str()).map_err(|_| E::ReadPhpVersion(i))?;
+          let old = php_version.replace(new);
+          if old.is_some() {
+            return Err(E::DuplicatePhpVersion);
+          }
+        } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("st") {
+          if version.is_none() {
+            return Err(E::MissingVersion);
+          }
+          if v2 && php_version.is_none() {
+            return Err(E::MissingPhpVersion);
+          }
+          let new = get_text(node).map_err(|_| E::ReadStatus(i))?;
+          let new = Stability::new(new.as_str());
+          let old = status.replace(new);
+          if old.is_some() {
+            return Err(E::DuplicateStatus);
+          }
+        } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("l") {
+          if status.is_none() {
+            return Err(E::MissingStatus);
+          }
+          let new = get_text(node).map_err(|_| E::ReadLicense(i))?;
+          let old = license.replace(new);
+          if old.is_some() {
+            return Err(E::DuplicateLicense);
+          }
+        } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("m") {
+          if license.is_none() {
+            return Err(E::MissingLicense);
+          }
+          let new = get_text(node).map_err(|_| E::ReadMaintainer(i))?;
+          let old = maintainer.replace(new);
+          if old.is_some() {
+            return Err(E::DuplicateMaintainer);
+          }
+        } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("s") {
+          if maintainer.is_none() {
+            return Err(E::MissingMaintainer);
+          }
+          let new = get_text(node).map_err(|_| E::ReadSummary(i))?;
+          let old = summary.replace(new);
+          if old.is_some() {
+            return Err(E::DuplicateSummary);
+          }
+        } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("d") {
+          if summary.is_none() {
+            return Err(E::MissingSummary);
+          }
+          let new = get_text(node).map_err(|_| E::ReadDescription(i))?;
+          let old = description.replace(new);
+          if old.is_some() {
+            return Err(E::DuplicateDescription);
+          }
+        } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("da") {
+          if description.is_none() {
+            return Err(E::MissingDescription);
+          }
+          let new = get_text(node).map_err(|_| E::ReadDate(i))?;
+          let old = date.replace(new);
+          if old.is_some() {
+            return Err(E::DuplicateDate);
+          }
+        } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("n") {
+          if date.is_none() {
+            return Err(E::MissingDate);
+          }
+          let new = get_text(node).map_err(|_| E::ReadReleaseNotes(i))?;
+          let old = release_notes.replace(new);
+          if old.is_some() {
+            return Err(E::DuplicateReleaseNotes);
+          }
+        } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("f") {
+          if release_notes.is_none() {
+            return Err(E::MissingReleaseNotes);
+          }
+          let new = get_text(node).map_err(|_| E::ReadArchiveSize(i))?;
+          let old = archive_size.replace(new);
+          if old.is_some() {
+            return Err(E::DuplicateArchiveSize);
+          }
+        } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("g") {
+          if archive_size.is_none() {
+            return Err(E::MissingArchiveSize);
+          }
+          let new = get_text(node).map_err(|_| E::ReadArchiveLink(i))?;
+          let old = archive_link.replace(new);
+          if old.is_some() {
+            return Err(E::DuplicateArchiveLink);
+          }
+        } else if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case("x") {
+          let attrs = &*attrs.borrow();
+          if archive_link.is_none() {
+            return Err(E::MissingArchiveLink);
+          }
+          let new = get_link_attr(attrs)
+            .map_err(|_| E::ReadExtracted(i))?
+            .ok_or(E::InvalidExtracted)?;
+          let old = extracted.replace(CompactString::new(new.value.as_ref()));
+          if old.is_some() {
+            return Err(E::DuplicateExtracted);
+          }
+        } else {
+          return Err(E::ChildType(i));
+        }
       }
+      NodeData::Text { .. } | NodeData::Comment { .. } => continue,
+      _ => return Err(E::ChildType(i)),
     }
+  }
+
+  let channel = channel.ok_or(E::MissingChannel)?;
+  let release = Release {
+    package: package.ok_or(E::MissingPackage)?,
+    version: version.ok_or(E::MissingVersion)?,
+    status: status.ok_or(E::MissingStatus)?,
+    license: license.ok_or(E::MissingLicense)?,
+    maintainer: maintainer.ok_or(E::MissingMaintainer)?,
+    summary: summary.ok_or(E::MissingSummary)?,
+    description: description.ok_or(E::MissingDescription)?,
+    time: parse_release_date(date.ok_or(E::MissingDate)?.as_str()).ok_or(E::InvalidDate)?,
+    release_notes: release_notes.ok_or(E::MissingReleaseNotes)?,
+    archive: ReleaseArchive {
+      size: archive_size
+        .ok_or(E::MissingArchiveSize)?
+        .parse::<u64>()
+        .map_err(|_| E::InvalidArchiveSize)?,
+      link: parse_channel_link(
+        channel.as_str(),
+        archive_link.ok_or(E::MissingArchiveLink)?.as_str(),
+        base,
+      )
+      .ok_or(E::InvalidArchiveLink)?,
+    },
+    extracted_link: extracted.ok_or(E::MissingExtracted)?,
+    channel,
+  };
+  Ok(ReleaseNode {
+    release,
+    api_version,
+    php_version,
+  })
+}
 
+impl ReleaseV2<CompactString> {
+  /// Read from an XML document, panics if the input is invalid.
+  ///
+  /// See [`Self::try_from_xml`] for the fallible version.
+  pub fn from_xml(input: &[u8]) -> Self {
+    Self::try_from_xml(input).unwrap()
+  }
+
+  pub fn try_from_xml(input: &[u8]) -> Result<Self, FromXmlError> {
+    let dom = parse_rc_dom(input)?;
+    Ok(Self::from_rc_dom(dom)?)
+  }
+
+  /// Read from an XML document, resolving a relative archive link against the channel at `pear_url`
+  pub fn try_from_xml_with_base(input: &[u8], pear_url: &PearUrl) -> Result<Self, FromXmlError> {
+    let dom = parse_rc_dom(input)?;
+    let root = find_root(&dom.document, "r").map_err(|_| ReleaseV2FromRcDomError::RootNotFound)?;
+    let base = url_join_root(pear_url, [""]);
+    let node = read_release(&root, true, Some(&base)).map_err(ReleaseV2FromRcDomError::Read)?;
+    Ok(Self::from_release_node(node).map_err(ReleaseV2FromRcDomError::Read)?)
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum ReleaseV2FromRcDomError {
+  #[error("failed to find root node")]
+  RootNotFound,
+  #[error("failed to read release from XML Node")]
+  Read(#[from] ReleaseFromXmlNodeError),
+}
+
+impl ReleaseV2<CompactString> {
+  pub fn from_rc_dom(dom: RcDom) -> Result<Self, ReleaseV2FromRcDomError> {
+    let doc = dom.document;
+    let root = find_root(&doc, "r").map_err(|_| ReleaseV2FromRcDomError::RootNotFound)?;
+    Ok(Self::from_xml_node(&root)?)
+  }
+
+  pub fn from_xml_node(node: &Node) -> Result<Self, ReleaseFromXmlNodeError> {
+    Self::from_release_node(read_release(node, true, None)?)
+  }
+
+  fn from_release_node(node: ReleaseNode) -> Result<Self, ReleaseFromXmlNodeError> {
     Ok(Self {
-      package: package.ok_or(E::MissingPackage)?,
-      channel: channel.ok_or(E::MissingChannel)?,
-      version: version.ok_or(E::MissingVersion)?,
-      status: status.ok_or(E::MissingStatus)?,
-      license: license.ok_or(E::MissingLicense)?,
-      maintainer: maintainer.ok_or(E::MissingMaintainer)?,
-      summary: summary.ok_or(E::MissingSummary)?,
-      description: description.ok_or(E::MissingDescription)?,
-      time: date.ok_or(E::MissingDate)?,
-      release_notes: release_notes.ok_or(E::MissingReleaseNotes)?,
-      archive: ReleaseArchive {
-        size: archive_size.ok_or(E::MissingArchiveSize)?.parse::<u64>().map_err(|_| E::InvalidArchiveSize)?,
-        link: archive_link.ok_or(E::MissingArchiveLink)?,
-      },
-      extracted_link: extracted.ok_or(E::MissingExtracted)?,
+      release: node.release,
+      api_version: node.api_version.ok_or(ReleaseFromXmlNodeError::MissingApiVersion)?,
+      php_version: node.php_version.ok_or(ReleaseFromXmlNodeError::MissingPhpVersion)?,
     })
   }
 }
@@ -499,10 +1054,217 @@ mod test {
     assert_eq!(actual.items.len(), 141);
   }
 
+  #[test]
+  pub fn test_release_listing_to_xml() {
+    let input = include_bytes!("../../test-resources/get_release_list/pecl_protobuf/input.xml");
+    let listing = ReleaseListing::from_xml(input);
+    let actual = listing.to_xml();
+    assert_eq!(actual.as_bytes(), input);
+    assert_eq!(ReleaseListing::from_xml(actual.as_bytes()), listing);
+  }
+
+  #[test]
+  pub fn test_release_listing_select() {
+    let input = include_bytes!("../../test-resources/get_release_list/pecl_protobuf/input.xml");
+    let actual = ReleaseListing::from_xml(input);
+    let select = |requirement: &str, min_stability: Stability| {
+      actual
+        .select(&requirement.parse().unwrap(), &min_stability)
+        .map(|r| r.version.as_str())
+    };
+    assert_eq!(
+      actual.latest(&Stability::Stable).map(|r| r.version.as_str()),
+      Some("4.27.0")
+    );
+    assert_eq!(select("^3.0", Stability::Stable), Some("3.25.3"));
+    assert_eq!(select(">=3.24,<4", Stability::Stable), Some("3.25.3"));
+    assert_eq!(select("~4.27.0", Stability::Beta), Some("4.27.0"));
+    assert_eq!(select("<4.27.0", Stability::Beta), Some("4.27.0RC3"));
+    assert_eq!(select("^5.0", Stability::Devel), None);
+  }
+
+  #[test]
+  pub fn test_release_listing_at_least() {
+    let input = include_bytes!("../../test-resources/get_release_list/pecl_protobuf/input.xml");
+    let actual = ReleaseListing::from_xml(input);
+    assert_eq!(actual.items[1].stability, Stability::Beta);
+    assert!(actual
+      .at_least(&Stability::Beta)
+      .any(|r| r.stability == Stability::Beta));
+    assert!(actual
+      .at_least(&Stability::Stable)
+      .all(|r| r.stability == Stability::Stable));
+    assert_eq!(actual.at_least(&Stability::Snapshot).count(), actual.items.len());
+  }
+
+  #[test]
+  pub fn test_release_listing2_from_xml() {
+    let input = include_bytes!("../../test-resources/get_release_list2/pecl_protobuf/input.xml");
+    let actual = ReleaseListing2::from_xml(input);
+    assert_eq!(actual.channel.as_str(), "pecl.php.net");
+    assert_eq!(actual.items.len(), 141);
+    assert_eq!(actual.items[0].version.as_str(), "4.27.0");
+    assert_eq!(actual.items[0].php_version.as_str(), "8.0.0");
+  }
+
   #[test]
   pub fn test_release_from_xml() {
     let input = include_bytes!("../../test-resources/get_release/pecl_protobuf_4.27.0/input.xml");
     let actual = Release::from_xml(input);
     assert_eq!(actual.channel.as_str(), "pecl.php.net");
+    assert_eq!(actual.time_utc().to_rfc3339(), "2024-05-23T14:46:50+00:00");
+    let paris = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+    assert_eq!(
+      actual.time_in(&paris).unwrap().to_rfc3339(),
+      "2024-05-23T14:46:50+02:00"
+    );
+    assert_eq!(actual.archive.link.as_str(), "https://pecl.php.net/get/protobuf-4.27.0");
+  }
+
+  #[test]
+  pub fn test_release_to_xml() {
+    let input = include_bytes!("../../test-resources/get_release/pecl_protobuf_4.27.0/input.xml");
+    let release = Release::from_xml(input);
+    let actual = release.to_xml();
+    assert_eq!(actual.as_bytes(), input);
+
+    let release = Release {
+      release_notes: CompactString::new("- fixed <a> & <b>"),
+      ..release
+    };
+    assert_eq!(Release::from_xml(release.to_xml().as_bytes()), release);
+  }
+
+  #[test]
+  pub fn test_parse_release_date() {
+    assert_eq!(
+      parse_release_date("2024-05-23 14:46:50").unwrap().to_string(),
+      "2024-05-23 14:46:50"
+    );
+    assert_eq!(
+      parse_release_date("2004-01-02").unwrap().to_string(),
+      "2004-01-02 00:00:00"
+    );
+    assert_eq!(parse_release_date("yesterday"), None);
+  }
+
+  #[test]
+  pub fn test_parse_channel_link() {
+    assert_eq!(
+      parse_channel_link("pecl.php.net", "/get/apc-3.0.0.tgz", None)
+        .unwrap()
+        .as_str(),
+      "https://pecl.php.net/get/apc-3.0.0.tgz"
+    );
+    assert_eq!(
+      parse_channel_link("pecl.php.net", "http://example.com/apc.tgz", None)
+        .unwrap()
+        .as_str(),
+      "http://example.com/apc.tgz"
+    );
+    assert_eq!(
+      parse_channel_link("localhost:8080", "get/apc-3.0.0", None)
+        .unwrap()
+        .as_str(),
+      "https://localhost:8080/get/apc-3.0.0"
+    );
+    assert_eq!(
+      parse_channel_link("example.com/pear", "get/apc-3.0.0", None)
+        .unwrap()
+        .as_str(),
+      "https://example.com/pear/get/apc-3.0.0"
+    );
+    let base = Url::parse("http://localhost:8080/pear/").unwrap();
+    assert_eq!(
+      parse_channel_link("localhost:8080/pear", "get/apc-3.0.0", Some(&base))
+        .unwrap()
+        .as_str(),
+      "http://localhost:8080/pear/get/apc-3.0.0"
+    );
+  }
+
+  #[test]
+  pub fn test_release_from_xml_with_base() {
+    let input = include_bytes!("../../test-resources/get_release/pecl_protobuf_4.27.0/input.xml");
+    let input = String::from_utf8(input.to_vec()).unwrap();
+    let input = input
+      .replace("<c>pecl.php.net</c>", "<c>localhost:8080/pear</c>")
+      .replace("https://pecl.php.net/get/protobuf-4.27.0", "get/protobuf-4.27.0");
+    let actual = Release::try_from_xml(input.as_bytes()).unwrap();
+    assert_eq!(
+      actual.archive.link.as_str(),
+      "https://localhost:8080/pear/get/protobuf-4.27.0"
+    );
+    let pear_url = PearUrl(Url::parse("http://localhost:8080/pear/").unwrap());
+    let actual = Release::try_from_xml_with_base(input.as_bytes(), &pear_url).unwrap();
+    assert_eq!(
+      actual.archive.link.as_str(),
+      "http://localhost:8080/pear/get/protobuf-4.27.0"
+    );
+  }
+
+  #[test]
+  pub fn test_release_v2_from_xml() {
+    let input = include_bytes!("../../test-resources/get_release_v2/pecl_protobuf_4.27.0/input.xml");
+    let actual = ReleaseV2::from_xml(input);
+    assert_eq!(actual.release.version.as_str(), "4.27.0");
+    assert_eq!(actual.api_version.as_str(), "4.27.0");
+    assert_eq!(actual.php_version.as_str(), "8.0.0");
+    assert_eq!(actual.release.archive.size, 243961);
+  }
+
+  #[test]
+  pub fn test_release_listing2_to_xml() {
+    let input = include_bytes!("../../test-resources/get_release_list2/pecl_protobuf/input.xml");
+    let listing = ReleaseListing2::from_xml(input);
+    let actual = listing.to_xml();
+    assert_eq!(actual.as_bytes(), input);
+  }
+
+  #[test]
+  pub fn test_release_v2_to_xml() {
+    let input = include_bytes!("../../test-resources/get_release_v2/pecl_protobuf_4.27.0/input.xml");
+    let release = ReleaseV2::from_xml(input);
+    let actual = release.to_xml();
+    assert_eq!(actual.as_bytes(), input);
+  }
+
+  #[test]
+  pub fn test_release_from_xml_rejects_v2_nodes() {
+    let input = include_bytes!("../../test-resources/get_release_v2/pecl_protobuf_4.27.0/input.xml");
+    assert!(Release::try_from_xml(input).is_err());
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  pub fn test_release_listing_serde() {
+    let input = include_bytes!("../../test-resources/get_release_list/pecl_protobuf/input.xml");
+    let listing = ReleaseListing::from_xml(input);
+    let json = serde_json::to_string(&listing).unwrap();
+    assert_eq!(serde_json::from_str::<ReleaseListing>(&json).unwrap(), listing);
+    assert_eq!(
+      serde_json::to_value(&listing.items[0]).unwrap(),
+      serde_json::json!({"version": "4.27.0", "stability": "stable"})
+    );
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  pub fn test_release_serde() {
+    let input = include_bytes!("../../test-resources/get_release/pecl_protobuf_4.27.0/input.xml");
+    let release = Release::from_xml(input);
+    let json = serde_json::to_value(&release).unwrap();
+    assert_eq!(serde_json::from_value::<Release>(json.clone()).unwrap(), release);
+    assert_eq!(
+      json["package"],
+      serde_json::json!({"name": "protobuf", "link": "/rest/p/protobuf"})
+    );
+    assert_eq!(json["version"], "4.27.0");
+    assert_eq!(json["status"], "stable");
+    assert_eq!(json["time"], "2024-05-23T14:46:50");
+    assert_eq!(
+      json["archive"],
+      serde_json::json!({"size": 243961, "link": "https://pecl.php.net/get/protobuf-4.27.0"})
+    );
   }
 }
@@ -0,0 +1,327 @@
+use crate::common::version::PearVersion;
+use compact_str::CompactString;
+use core::fmt;
+use core::str::FromStr;
+
+/// Version requirement, using the Composer constraint syntax
+///
+/// A requirement is a list of alternatives separated by `||`, each alternative is a list of comparators separated by
+/// commas or spaces, which must all match. Supported comparators:
+/// - `1.2.3` or `=1.2.3`, `!=1.2.3`, `<1.2.3`, `<=1.2.3`, `>1.2.3`, `>=1.2.3`
+/// - `^1.2.3`: same leading non-zero number, e.g. `^4.26` is `>=4.26` with major version `4`
+/// - `~1.2.3`: same leading numbers except the last one, e.g. `~3.25.0` is `>=3.25.0` with version `3.25.*`
+/// - `1.2.*`: same leading numbers
+/// - `*`: any version
+///
+/// Versions are compared with [`PearVersion`] semantics, so `<4.0` still matches `4.0RC1`. Use `<4` or a minimum
+/// stability to exclude pre-releases of the next version.
+///
+/// PEAR dependency constraints can be converted with [`VersionConstraint::to_requirement`].
+///
+/// With `serde`, the requirement is represented by its string form, e.g. `">=3.24, <4"`.
+///
+/// [`VersionConstraint::to_requirement`]: crate::common::dependency::VersionConstraint::to_requirement
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VersionRequirement {
+  /// The requirement matches if any alternative matches
+  pub alternatives: Vec<Vec<VersionComparator>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VersionComparator {
+  pub op: VersionOp,
+  pub version: PearVersion,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum VersionOp {
+  Eq,
+  Ne,
+  Lt,
+  Le,
+  Gt,
+  Ge,
+  Caret,
+  Tilde,
+  /// Numeric prefix match, e.g. `4.27.*`
+  Wildcard,
+}
+
+impl VersionOp {
+  /// Operator prefix, empty for [`Self::Wildcard`] which uses a `.*` suffix
+  pub const fn as_str(self) -> &'static str {
+    match self {
+      Self::Eq => "=",
+      Self::Ne => "!=",
+      Self::Lt => "<",
+      Self::Le => "<=",
+      Self::Gt => ">",
+      Self::Ge => ">=",
+      Self::Caret => "^",
+      Self::Tilde => "~",
+      Self::Wildcard => "",
+    }
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum VersionRequirementFromStrError {
+  #[error("version requirement alternative is empty")]
+  Empty,
+  #[error("version requirement comparator is missing its version: {0:?}")]
+  MissingVersion(CompactString),
+  #[error("version requirement comparator has an invalid version: {0:?}")]
+  Version(CompactString),
+}
+
+impl VersionRequirement {
+  /// Requirement matching any version
+  pub fn any() -> Self {
+    Self {
+      alternatives: vec![Vec::new()],
+    }
+  }
+
+  pub fn matches(&self, version: &PearVersion) -> bool {
+    self
+      .alternatives
+      .iter()
+      .any(|comparators| comparators.iter().all(|c| c.matches(version)))
+  }
+}
+
+impl VersionComparator {
+  pub fn new(op: VersionOp, version: PearVersion) -> Self {
+    Self { op, version }
+  }
+
+  pub fn matches(&self, version: &PearVersion) -> bool {
+    match self.op {
+      VersionOp::Eq => *version == self.version,
+      VersionOp::Ne => *version != self.version,
+      VersionOp::Lt => *version < self.version,
+      VersionOp::Le => *version <= self.version,
+      VersionOp::Gt => *version > self.version,
+      VersionOp::Ge => *version >= self.version,
+      VersionOp::Caret => {
+        let numbers: Vec<u64> = self.version.numbers().collect();
+        let len = match numbers.iter().position(|n| *n != 0) {
+          Some(i) => i + 1,
+          None => numbers.len(),
+        };
+        *version >= self.version && has_numeric_prefix(version, &numbers[..len])
+      }
+      VersionOp::Tilde => {
+        let numbers: Vec<u64> = self.version.numbers().collect();
+        let len = numbers.len().saturating_sub(1).max(1).min(numbers.len());
+        *version >= self.version && has_numeric_prefix(version, &numbers[..len])
+      }
+      VersionOp::Wildcard => {
+        let numbers: Vec<u64> = self.version.numbers().collect();
+        has_numeric_prefix(version, &numbers)
+      }
+    }
+  }
+}
+
+/// Check that the leading numbers of `version` start with `prefix`, missing numbers are `0`
+fn has_numeric_prefix(version: &PearVersion, prefix: &[u64]) -> bool {
+  let mut numbers = version.numbers();
+  prefix.iter().all(|expected| numbers.next().unwrap_or(0) == *expected)
+}
+
+impl FromStr for VersionRequirement {
+  type Err = VersionRequirementFromStrError;
+
+  fn from_str(input: &str) -> Result<Self, Self::Err> {
+    let mut alternatives = Vec::new();
+    for alternative in input.split("||") {
+      alternatives.push(parse_alternative(alternative)?);
+    }
+    Ok(Self { alternatives })
+  }
+}
+
+fn parse_alternative(input: &str) -> Result<Vec<VersionComparator>, VersionRequirementFromStrError> {
+  let tokens = input
+    .split(|c: char| c == ',' || c.is_whitespace())
+    .filter(|t| !t.is_empty());
+  let mut comparators = Vec::new();
+  let mut any = false;
+  // operator separated from its version by a space, e.g. `>= 1.0`
+  let mut pending_op: Option<(VersionOp, &str)> = None;
+  for token in tokens {
+    if token == "*" && pending_op.is_none() {
+      any = true;
+      continue;
+    }
+    let (op, version) = match pending_op.take() {
+      Some((op, _)) => (op, token),
+      None => split_op(token),
+    };
+    if version.is_empty() {
+      pending_op = Some((op, token));
+      continue;
+    }
+    comparators.push(parse_comparator(op, version, token)?);
+  }
+  if let Some((_, token)) = pending_op {
+    return Err(VersionRequirementFromStrError::MissingVersion(CompactString::new(
+      token,
+    )));
+  }
+  if comparators.is_empty() && !any {
+    return Err(VersionRequirementFromStrError::Empty);
+  }
+  Ok(comparators)
+}
+
+fn split_op(token: &str) -> (VersionOp, &str) {
+  const OPS: [(&str, VersionOp); 10] = [
+    (">=", VersionOp::Ge),
+    ("<=", VersionOp::Le),
+    ("!=", VersionOp::Ne),
+    ("<>", VersionOp::Ne),
+    ("==", VersionOp::Eq),
+    (">", VersionOp::Gt),
+    ("<", VersionOp::Lt),
+    ("=", VersionOp::Eq),
+    ("^", VersionOp::Caret),
+    ("~", VersionOp::Tilde),
+  ];
+  OPS
+    .iter()
+    .find_map(|(prefix, op)| token.strip_prefix(prefix).map(|rest| (*op, rest)))
+    .unwrap_or((VersionOp::Eq, token))
+}
+
+fn parse_comparator(
+  op: VersionOp,
+  version: &str,
+  token: &str,
+) -> Result<VersionComparator, VersionRequirementFromStrError> {
+  let invalid = || VersionRequirementFromStrError::Version(CompactString::new(token));
+  let (op, version) = match version.strip_suffix(".*").or_else(|| version.strip_suffix(".x")) {
+    Some(prefix) if op == VersionOp::Eq => (VersionOp::Wildcard, prefix),
+    Some(_) => return Err(invalid()),
+    None => (op, version),
+  };
+  let version = PearVersion::new(version).map_err(|_| invalid())?;
+  Ok(VersionComparator { op, version })
+}
+
+impl fmt::Display for VersionRequirement {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (i, comparators) in self.alternatives.iter().enumerate() {
+      if i > 0 {
+        f.write_str(" || ")?;
+      }
+      if comparators.is_empty() {
+        f.write_str("*")?;
+      }
+      for (j, comparator) in comparators.iter().enumerate() {
+        if j > 0 {
+          f.write_str(", ")?;
+        }
+        fmt::Display::fmt(comparator, f)?;
+      }
+    }
+    Ok(())
+  }
+}
+
+impl fmt::Display for VersionComparator {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.op {
+      VersionOp::Wildcard => write!(f, "{}.*", self.version),
+      op => write!(f, "{}{}", op.as_str(), self.version),
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for VersionRequirement {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for VersionRequirement {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let raw = CompactString::deserialize(deserializer)?;
+    raw.parse().map_err(serde::de::Error::custom)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn req(input: &str) -> VersionRequirement {
+    input.parse().unwrap()
+  }
+
+  fn v(raw: &str) -> PearVersion {
+    PearVersion::new(raw).unwrap()
+  }
+
+  #[test]
+  pub fn test_caret() {
+    let r = req("^4.26");
+    assert!(r.matches(&v("4.26.0")));
+    assert!(r.matches(&v("4.27.0")));
+    assert!(!r.matches(&v("4.25.9")));
+    assert!(!r.matches(&v("5.0.0")));
+    let r = req("^0.3.1");
+    assert!(r.matches(&v("0.3.5")));
+    assert!(!r.matches(&v("0.4.0")));
+  }
+
+  #[test]
+  pub fn test_tilde() {
+    let r = req("~3.25.0");
+    assert!(r.matches(&v("3.25.4")));
+    assert!(!r.matches(&v("3.26.0")));
+    let r = req("~3.25");
+    assert!(r.matches(&v("3.26.0")));
+    assert!(!r.matches(&v("4.0.0")));
+  }
+
+  #[test]
+  pub fn test_range() {
+    let r = req(">=3.24,<4");
+    assert!(r.matches(&v("3.24.0")));
+    assert!(r.matches(&v("3.25.3")));
+    assert!(!r.matches(&v("4.0.0RC1")));
+    assert!(!r.matches(&v("3.23.4")));
+    assert_eq!(req(">= 3.24 < 4"), r);
+  }
+
+  #[test]
+  pub fn test_alternatives() {
+    let r = req("3.21.* || ^4.0");
+    assert!(r.matches(&v("3.21.12")));
+    assert!(!r.matches(&v("3.22.0")));
+    assert!(r.matches(&v("4.27.0")));
+    assert_eq!(r.to_string(), "3.21.* || ^4.0");
+    assert!(req("*").matches(&v("0.0.1")));
+  }
+
+  #[test]
+  pub fn test_invalid() {
+    assert_eq!(
+      "".parse::<VersionRequirement>(),
+      Err(VersionRequirementFromStrError::Empty)
+    );
+    assert_eq!(
+      ">=".parse::<VersionRequirement>(),
+      Err(VersionRequirementFromStrError::MissingVersion(CompactString::new(">=")))
+    );
+    assert_eq!(
+      "^...".parse::<VersionRequirement>(),
+      Err(VersionRequirementFromStrError::Version(CompactString::new("^...")))
+    );
+  }
+}
@@ -0,0 +1,143 @@
+use compact_str::CompactString;
+use core::convert::Infallible;
+use core::fmt;
+use core::str::FromStr;
+
+/// Release stability
+///
+/// Stabilities are ordered from the least to the most stable: unknown values (`Other`) < `snapshot` < `devel` <
+/// `alpha` < `beta` < `stable`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stability {
+  /// Unknown stability, with its raw value
+  Other(CompactString),
+  Snapshot,
+  Devel,
+  Alpha,
+  Beta,
+  Stable,
+}
+
+impl Stability {
+  pub fn new(raw: &str) -> Self {
+    match raw {
+      "snapshot" => Self::Snapshot,
+      "devel" => Self::Devel,
+      "alpha" => Self::Alpha,
+      "beta" => Self::Beta,
+      "stable" => Self::Stable,
+      other => Self::Other(CompactString::new(other)),
+    }
+  }
+
+  pub fn as_str(&self) -> &str {
+    match self {
+      Self::Other(raw) => raw.as_str(),
+      Self::Snapshot => "snapshot",
+      Self::Devel => "devel",
+      Self::Alpha => "alpha",
+      Self::Beta => "beta",
+      Self::Stable => "stable",
+    }
+  }
+
+  /// Check if this stability is at least as stable as `min`
+  ///
+  /// An unknown minimum is satisfied by every stability, and an unknown stability only satisfies an unknown minimum.
+  /// Unknown values are not compared with each other.
+  pub fn is_at_least(&self, min: &Stability) -> bool {
+    match (self, min) {
+      (_, Self::Other(_)) => true,
+      (Self::Other(_), _) => false,
+      _ => self >= min,
+    }
+  }
+}
+
+impl fmt::Display for Stability {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+impl From<&str> for Stability {
+  fn from(raw: &str) -> Self {
+    Self::new(raw)
+  }
+}
+
+impl FromStr for Stability {
+  type Err = Infallible;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Ok(Self::new(s))
+  }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Stability {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(self.as_str())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Stability {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let raw = CompactString::deserialize(deserializer)?;
+    Ok(Self::new(raw.as_str()))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  pub fn test_stability_order() {
+    let ordered = [
+      Stability::Other(CompactString::new("unknown")),
+      Stability::Snapshot,
+      Stability::Devel,
+      Stability::Alpha,
+      Stability::Beta,
+      Stability::Stable,
+    ];
+    for pair in ordered.windows(2) {
+      assert!(pair[0] < pair[1]);
+    }
+  }
+
+  #[test]
+  pub fn test_stability_is_at_least() {
+    let other = |raw: &str| Stability::Other(CompactString::new(raw));
+    assert!(Stability::Stable.is_at_least(&Stability::Beta));
+    assert!(!Stability::Beta.is_at_least(&Stability::Stable));
+    assert!(Stability::Stable.is_at_least(&other("x")));
+    assert!(Stability::Snapshot.is_at_least(&other("x")));
+    assert!(!other("x").is_at_least(&Stability::Snapshot));
+    assert!(other("a").is_at_least(&other("b")));
+    assert!(other("b").is_at_least(&other("a")));
+  }
+
+  #[test]
+  pub fn test_stability_round_trip() {
+    for raw in ["snapshot", "devel", "alpha", "beta", "stable", "experimental"] {
+      assert_eq!(Stability::new(raw).as_str(), raw);
+    }
+    assert_eq!(
+      Stability::new("experimental"),
+      Stability::Other(CompactString::new("experimental"))
+    );
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  pub fn test_stability_serde() {
+    assert_eq!(serde_json::to_string(&Stability::Beta).unwrap(), "\"beta\"");
+    assert_eq!(
+      serde_json::from_str::<Stability>("\"rc\"").unwrap(),
+      Stability::Other(CompactString::new("rc"))
+    );
+  }
+}
@@ -0,0 +1,269 @@
+use compact_str::CompactString;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+
+/// Package version, compared with the semantics of the PHP `version_compare` function
+///
+/// The version is split into numeric and alphabetic parts: all the other characters (`.`, `-`, `_`, `+`, ...) are
+/// separators, and a part also ends when switching between digits and letters. Numeric parts are compared by value,
+/// alphabetic parts by their special form: `dev` < `alpha` = `a` < `beta` = `b` < `RC` = `rc` < number < `pl` = `p`.
+/// Unknown words are older than `dev`.
+///
+/// Two versions are equal if they compare equal, e.g. `1.0a1` and `1.0-alpha.1`. The original string is kept for
+/// display.
+///
+/// <https://www.php.net/manual/en/function.version-compare.php>
+#[derive(Debug, Clone)]
+pub struct PearVersion {
+  raw: CompactString,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+#[error("invalid PEAR version, it must contain at least one alphanumeric character")]
+pub struct InvalidPearVersion;
+
+impl PearVersion {
+  pub fn new(raw: &str) -> Result<Self, InvalidPearVersion> {
+    if VersionParts::new(raw).next().is_none() {
+      return Err(InvalidPearVersion);
+    }
+    Ok(Self {
+      raw: CompactString::new(raw),
+    })
+  }
+
+  pub fn as_str(&self) -> &str {
+    self.raw.as_str()
+  }
+
+  pub fn into_inner(self) -> CompactString {
+    self.raw
+  }
+
+  /// Leading numeric parts, e.g. `[4, 27, 0]` for `4.27.0RC1`
+  ///
+  /// Numbers too large for `u64` saturate.
+  pub fn numbers(&self) -> impl Iterator<Item = u64> + '_ {
+    self.parts().map_while(|part| match part {
+      VersionPart::Number(digits) => Some(digits.parse::<u64>().unwrap_or(u64::MAX)),
+      VersionPart::Special(_) => None,
+    })
+  }
+
+  fn parts(&self) -> VersionParts<'_> {
+    VersionParts::new(self.raw.as_str())
+  }
+}
+
+impl AsRef<str> for PearVersion {
+  fn as_ref(&self) -> &str {
+    self.as_str()
+  }
+}
+
+impl fmt::Display for PearVersion {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+impl FromStr for PearVersion {
+  type Err = InvalidPearVersion;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Self::new(s)
+  }
+}
+
+impl PartialEq for PearVersion {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other) == Ordering::Equal
+  }
+}
+
+impl Eq for PearVersion {}
+
+impl PartialOrd for PearVersion {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for PearVersion {
+  fn cmp(&self, other: &Self) -> Ordering {
+    let mut left = self.parts();
+    let mut right = other.parts();
+    loop {
+      let ord = match (left.next(), right.next()) {
+        (None, None) => return Ordering::Equal,
+        (Some(l), Some(r)) => l.compare(r),
+        // `1.0` < `1.0.1` but `1.0` > `1.0RC1`
+        (Some(l), None) => l.cmp_missing(),
+        (None, Some(r)) => r.cmp_missing().reverse(),
+      };
+      if ord != Ordering::Equal {
+        return ord;
+      }
+    }
+  }
+}
+
+impl Hash for PearVersion {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    for part in self.parts() {
+      part.hash(state);
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PearVersion {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(self.as_str())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PearVersion {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let raw = CompactString::deserialize(deserializer)?;
+    Self::new(raw.as_str()).map_err(serde::de::Error::custom)
+  }
+}
+
+/// Normalized version part
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum VersionPart<'a> {
+  /// Digits, without leading zeros
+  Number(&'a str),
+  /// Rank of the special form
+  Special(i8),
+}
+
+/// Rank of numbers when compared with special forms
+const NUMBER_RANK: i8 = 4;
+
+impl VersionPart<'_> {
+  fn special(word: &str) -> Self {
+    const FORMS: [(&str, i8); 9] = [
+      ("dev", 0),
+      ("alpha", 1),
+      ("a", 1),
+      ("beta", 2),
+      ("b", 2),
+      ("RC", 3),
+      ("rc", 3),
+      ("pl", 5),
+      ("p", 5),
+    ];
+    let rank = FORMS
+      .iter()
+      .find(|(prefix, _)| word.starts_with(prefix))
+      .map(|(_, rank)| *rank)
+      .unwrap_or(-1);
+    Self::Special(rank)
+  }
+
+  fn rank(self) -> i8 {
+    match self {
+      Self::Number(_) => NUMBER_RANK,
+      Self::Special(rank) => rank,
+    }
+  }
+
+  /// Compare this extra part with the end of a shorter version
+  fn cmp_missing(self) -> Ordering {
+    match self {
+      Self::Number(_) => Ordering::Greater,
+      Self::Special(rank) => rank.cmp(&NUMBER_RANK),
+    }
+  }
+
+  fn compare(self, other: Self) -> Ordering {
+    match (self, other) {
+      (Self::Number(l), Self::Number(r)) => l.len().cmp(&r.len()).then_with(|| l.cmp(r)),
+      _ => self.rank().cmp(&other.rank()),
+    }
+  }
+}
+
+/// Iterator over the normalized parts of a version string
+struct VersionParts<'a> {
+  rest: &'a str,
+}
+
+impl<'a> VersionParts<'a> {
+  fn new(raw: &'a str) -> Self {
+    Self { rest: raw }
+  }
+}
+
+impl<'a> Iterator for VersionParts<'a> {
+  type Item = VersionPart<'a>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let start = self.rest.find(|c: char| c.is_ascii_alphanumeric())?;
+    let rest = &self.rest[start..];
+    let is_digit = rest.as_bytes()[0].is_ascii_digit();
+    let len = rest
+      .find(|c: char| !c.is_ascii_alphanumeric() || c.is_ascii_digit() != is_digit)
+      .unwrap_or(rest.len());
+    let (part, rest) = rest.split_at(len);
+    self.rest = rest;
+    Some(if is_digit {
+      let trimmed = part.trim_start_matches('0');
+      VersionPart::Number(if trimmed.is_empty() { "0" } else { trimmed })
+    } else {
+      VersionPart::special(part)
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn v(raw: &str) -> PearVersion {
+    PearVersion::new(raw).unwrap()
+  }
+
+  #[test]
+  pub fn test_version_order() {
+    let ordered = [
+      "1.0.0dev1",
+      "1.0.0a1",
+      "1.0.0alpha2",
+      "1.0.0b1",
+      "1.0.0RC1",
+      "1.0.0RC3",
+      "1.0.0",
+      "1.0.0pl1",
+      "1.0.1",
+      "1.0.10",
+      "4.27.0RC3",
+      "4.27.0",
+    ];
+    for pair in ordered.windows(2) {
+      assert!(v(pair[0]) < v(pair[1]), "{} < {}", pair[0], pair[1]);
+    }
+  }
+
+  #[test]
+  pub fn test_version_equal() {
+    assert_eq!(v("1.0a1"), v("1.0-alpha.1"));
+    assert_eq!(v("1.0.0RC1"), v("1.0.0rc1"));
+    assert_eq!(v("1.01"), v("1.1"));
+    assert_ne!(v("1.0"), v("1.0.0"));
+    assert!(v("1.0") < v("1.0.0"));
+    assert!(v("1.0") > v("1.0RC1"));
+    assert_eq!(v("4.27.0RC3").to_string(), "4.27.0RC3");
+  }
+
+  #[test]
+  pub fn test_version_invalid() {
+    assert_eq!(PearVersion::new(""), Err(InvalidPearVersion));
+    assert_eq!(PearVersion::new("..."), Err(InvalidPearVersion));
+  }
+}
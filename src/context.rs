@@ -2,6 +2,7 @@ use std::ops::Deref;
 use url::Url;
 
 /// A very restricted version of frunk hlist to hold the context for PEAR client requests.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Context<TyPearUrl> {
   /// PEAR channel URL.
   pear_url: TyPearUrl,
@@ -22,17 +23,29 @@ impl EmptyContext {
 }
 
 impl Default for EmptyContext {
-    fn default() -> Self {
-        Self::new()
-    }
+  fn default() -> Self {
+    Self::new()
+  }
 }
 
 pub trait GetRef<T: ?Sized> {
   fn get_ref(&self) -> &T;
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct PearUrl(pub Url);
 
+impl PearUrl {
+  /// Create the channel URL from a bare host name, such as `pecl.php.net`.
+  ///
+  /// The channel is assumed to be served over HTTPS at the root of the host.
+  pub fn from_host(host: &str) -> Result<Self, url::ParseError> {
+    let mut url = Url::parse("https://localhost/")?;
+    url.set_host(Some(host))?;
+    Ok(Self(url))
+  }
+}
+
 impl Deref for PearUrl {
   type Target = Url;
 
@@ -46,3 +59,23 @@ impl GetRef<PearUrl> for Context<PearUrl> {
     &self.pear_url
   }
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::url_util::UrlExt;
+
+  #[test]
+  pub fn test_pear_url_from_host() {
+    let actual = PearUrl::from_host("pecl.php.net").unwrap();
+    assert_eq!(actual.as_str(), "https://pecl.php.net/");
+    assert_eq!(
+      actual.url_join_root(["channel.xml"]).as_str(),
+      "https://pecl.php.net/channel.xml"
+    );
+    assert_eq!(
+      actual.url_join(["p", "packages.xml"]).as_str(),
+      "https://pecl.php.net/rest/p/packages.xml"
+    );
+  }
+}
@@ -1,15 +1,23 @@
 pub use ::chrono;
 pub use ::compact_str;
-#[cfg(feature = "reqwest")]
-pub use ::reqwest;
 #[cfg(feature = "serde")]
 pub use ::serde;
 pub use ::tower_service;
 pub use ::url;
 
+#[cfg(feature = "archive")]
+pub mod archive;
 pub mod client;
 pub mod common;
 pub mod context;
+pub mod link;
+#[cfg(feature = "http")]
+pub mod mirror;
+pub mod php_serialize;
 pub mod query;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "archive")]
+pub mod static_channel;
 pub mod url_util;
 mod xml_util;
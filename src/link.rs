@@ -0,0 +1,238 @@
+//! Resolution of the `xlink:href` references found in REST documents
+//!
+//! Links are either absolute paths (`/rest/p/protobuf`) or relative to the document containing them
+//! (`package.4.27.0.xml` in `/rest/r/protobuf/4.27.0.xml`). They are resolved into absolute URLs, and links to known
+//! REST resources of the channel are mapped to the matching query.
+
+use crate::common::package::PackageInfo;
+use crate::common::release::{Release, ReleasePackage};
+use crate::common::stability::Stability;
+use crate::context::{EmptyContext, PearUrl};
+use crate::query::get_category_info::GetCategoryInfoQuery;
+use crate::query::get_category_list::GetCategoryListQuery;
+use crate::query::get_category_package_list::GetCategoryPackageListQuery;
+use crate::query::get_category_packages_info::GetCategoryPackagesInfoQuery;
+use crate::query::get_channel::GetChannelQuery;
+use crate::query::get_latest_version::GetLatestVersionQuery;
+use crate::query::get_maintainer_info::GetMaintainerInfoQuery;
+use crate::query::get_maintainer_list::GetMaintainerListQuery;
+use crate::query::get_package_info::GetPackageInfoQuery;
+use crate::query::get_package_list::GetPackageListQuery;
+use crate::query::get_package_maintainers::GetPackageMaintainersQuery;
+use crate::query::get_package_maintainers2::GetPackageMaintainers2Query;
+use crate::query::get_package_xml::GetPackageXmlQuery;
+use crate::query::get_release::GetReleaseQuery;
+use crate::query::get_release_dependencies::GetReleaseDependenciesQuery;
+use crate::query::get_release_list::GetReleaseListQuery;
+use crate::query::get_release_list2::GetReleaseList2Query;
+use crate::query::get_release_v2::GetReleaseV2Query;
+use crate::url_util::{php_urldecode, UrlExt};
+use compact_str::CompactString;
+use url::Url;
+
+/// Query matching a link to a REST resource of the channel
+///
+/// Use `set_context` on the inner query before sending it.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LinkQuery {
+  Channel(GetChannelQuery<EmptyContext>),
+  PackageList(GetPackageListQuery<EmptyContext>),
+  PackageInfo(GetPackageInfoQuery<EmptyContext>),
+  PackageMaintainers(GetPackageMaintainersQuery<EmptyContext>),
+  PackageMaintainers2(GetPackageMaintainers2Query<EmptyContext>),
+  ReleaseList(GetReleaseListQuery<EmptyContext>),
+  ReleaseList2(GetReleaseList2Query<EmptyContext>),
+  LatestVersion(GetLatestVersionQuery<EmptyContext>),
+  Release(GetReleaseQuery<EmptyContext>),
+  ReleaseV2(GetReleaseV2Query<EmptyContext>),
+  PackageXml(GetPackageXmlQuery<EmptyContext>),
+  ReleaseDependencies(GetReleaseDependenciesQuery<EmptyContext>),
+  CategoryList(GetCategoryListQuery<EmptyContext>),
+  CategoryInfo(GetCategoryInfoQuery<EmptyContext>),
+  CategoryPackageList(GetCategoryPackageListQuery<EmptyContext>),
+  CategoryPackagesInfo(GetCategoryPackagesInfoQuery<EmptyContext>),
+  MaintainerList(GetMaintainerListQuery<EmptyContext>),
+  MaintainerInfo(GetMaintainerInfoQuery<EmptyContext>),
+}
+
+/// Absolute link, with the matching query if it targets a known REST resource of the channel
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ResolvedLink {
+  pub url: Url,
+  pub query: Option<LinkQuery>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum ResolveLinkError {
+  #[error("invalid link {0:?}: {1}")]
+  Url(CompactString, String),
+}
+
+/// Resolve `href`, found in the document at `document`, against the channel `pear_url`
+pub fn resolve_link(pear_url: &PearUrl, document: &Url, href: &str) -> Result<ResolvedLink, ResolveLinkError> {
+  let url = document
+    .join(href.trim())
+    .map_err(|e| ResolveLinkError::Url(CompactString::new(href), e.to_string()))?;
+  let query = link_query(pear_url, &url);
+  Ok(ResolvedLink { url, query })
+}
+
+/// Map an absolute URL to the query for the matching REST resource of the channel
+///
+/// Returns `None` for URLs outside of the channel, or for unknown resources.
+pub fn link_query(pear_url: &PearUrl, url: &Url) -> Option<LinkQuery> {
+  if url.origin() != pear_url.origin() || url.query().is_some() {
+    return None;
+  }
+  let root = pear_url.path().trim_end_matches('/');
+  let path = url.path().strip_prefix(root)?.strip_prefix('/')?;
+  if path == "channel.xml" {
+    return Some(LinkQuery::Channel(GetChannelQuery::new()));
+  }
+  let path = path.strip_prefix("rest/")?;
+  let segments: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+  let decode = |segment: &str| php_urldecode(segment).map(CompactString::from);
+  let query = match segments.as_slice() {
+    ["p", "packages.xml"] => LinkQuery::PackageList(GetPackageListQuery::new()),
+    ["p", package] | ["p", package, "info.xml"] => LinkQuery::PackageInfo(GetPackageInfoQuery::new(decode(package)?)),
+    ["p", package, "maintainers.xml"] => {
+      LinkQuery::PackageMaintainers(GetPackageMaintainersQuery::new(decode(package)?))
+    }
+    ["p", package, "maintainers2.xml"] => {
+      LinkQuery::PackageMaintainers2(GetPackageMaintainers2Query::new(decode(package)?))
+    }
+    ["r", package] | ["r", package, "allreleases.xml"] => {
+      LinkQuery::ReleaseList(GetReleaseListQuery::new(decode(package)?))
+    }
+    ["r", package, "allreleases2.xml"] => LinkQuery::ReleaseList2(GetReleaseList2Query::new(decode(package)?)),
+    ["r", package, file] => release_file_query(decode(package)?, &decode(file)?)?,
+    ["c", "categories.xml"] => LinkQuery::CategoryList(GetCategoryListQuery::new()),
+    ["c", category] | ["c", category, "info.xml"] => {
+      LinkQuery::CategoryInfo(GetCategoryInfoQuery::new(decode(category)?))
+    }
+    ["c", category, "packages.xml"] => {
+      LinkQuery::CategoryPackageList(GetCategoryPackageListQuery::new(decode(category)?))
+    }
+    ["c", category, "packagesinfo.xml"] => {
+      LinkQuery::CategoryPackagesInfo(GetCategoryPackagesInfoQuery::new(decode(category)?))
+    }
+    ["m", "allmaintainers.xml"] => LinkQuery::MaintainerList(GetMaintainerListQuery::new()),
+    ["m", handle] | ["m", handle, "info.xml"] => {
+      LinkQuery::MaintainerInfo(GetMaintainerInfoQuery::new(decode(handle)?))
+    }
+    _ => return None,
+  };
+  Some(query)
+}
+
+/// Map a file of the `/rest/r/{package}/` directory to its query
+fn release_file_query(package: CompactString, file: &str) -> Option<LinkQuery> {
+  let latest = match file {
+    "latest.txt" => Some(None),
+    "stable.txt" => Some(Some(Stability::Stable)),
+    "beta.txt" => Some(Some(Stability::Beta)),
+    "alpha.txt" => Some(Some(Stability::Alpha)),
+    "devel.txt" => Some(Some(Stability::Devel)),
+    _ => None,
+  };
+  if let Some(stability) = latest {
+    return Some(LinkQuery::LatestVersion(GetLatestVersionQuery::new(package, stability)));
+  }
+  if let Some(version) = file.strip_prefix("deps.").and_then(|f| f.strip_suffix(".txt")) {
+    let version = CompactString::new(version);
+    return Some(LinkQuery::ReleaseDependencies(GetReleaseDependenciesQuery::new(
+      package, version,
+    )));
+  }
+  let version = file.strip_suffix(".xml")?;
+  Some(if let Some(version) = version.strip_prefix("package.") {
+    LinkQuery::PackageXml(GetPackageXmlQuery::new(package, CompactString::new(version)))
+  } else if let Some(version) = version.strip_prefix("v2.") {
+    LinkQuery::ReleaseV2(GetReleaseV2Query::new(package, CompactString::new(version)))
+  } else {
+    LinkQuery::Release(GetReleaseQuery::new(package, CompactString::new(version)))
+  })
+}
+
+/// URL of the channel root, used as the base for absolute path links
+fn root_url(pear_url: &PearUrl) -> Url {
+  pear_url.url_join_root([""])
+}
+
+impl ReleasePackage<CompactString> {
+  /// Resolve the link to the package info (`/rest/p/{package}`)
+  pub fn resolve_link(&self, pear_url: &PearUrl) -> Result<ResolvedLink, ResolveLinkError> {
+    resolve_link(pear_url, &root_url(pear_url), self.link.as_str())
+  }
+}
+
+impl PackageInfo<CompactString> {
+  /// Resolve the link to the release listing (`/rest/r/{package}`)
+  pub fn resolve_release_link(&self, pear_url: &PearUrl) -> Result<ResolvedLink, ResolveLinkError> {
+    resolve_link(pear_url, &root_url(pear_url), self.release_uri.as_str())
+  }
+}
+
+impl Release<CompactString> {
+  /// Resolve the link to the `package.xml` of this release, relative to `/rest/r/{package}/`
+  pub fn resolve_extracted_link(&self, pear_url: &PearUrl) -> Result<ResolvedLink, ResolveLinkError> {
+    let package = self.package.name.to_lowercase();
+    let document = pear_url.url_join(["r", package.as_str(), ""]);
+    resolve_link(pear_url, &document, self.extracted_link.as_str())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn pecl() -> PearUrl {
+    PearUrl::from_host("pecl.php.net").unwrap()
+  }
+
+  #[test]
+  pub fn test_release_links() {
+    let input = include_bytes!("../test-resources/get_release/pecl_protobuf_4.27.0/input.xml");
+    let release = Release::from_xml(input);
+
+    let actual = release.package.resolve_link(&pecl()).unwrap();
+    assert_eq!(actual.url.as_str(), "https://pecl.php.net/rest/p/protobuf");
+    let expected = LinkQuery::PackageInfo(GetPackageInfoQuery::new(CompactString::new("protobuf")));
+    assert_eq!(actual.query, Some(expected));
+
+    let actual = release.resolve_extracted_link(&pecl()).unwrap();
+    assert_eq!(
+      actual.url.as_str(),
+      "https://pecl.php.net/rest/r/protobuf/package.4.27.0.xml"
+    );
+    let expected = GetPackageXmlQuery::new(CompactString::new("protobuf"), CompactString::new("4.27.0"));
+    assert_eq!(actual.query, Some(LinkQuery::PackageXml(expected)));
+  }
+
+  #[test]
+  pub fn test_package_release_link() {
+    let input = include_bytes!("../test-resources/get_package_info/pecl_protobuf/input.xml");
+    let info = PackageInfo::from_xml(input);
+    let actual = info.resolve_release_link(&pecl()).unwrap();
+    assert_eq!(actual.url.as_str(), "https://pecl.php.net/rest/r/protobuf");
+    let expected = GetReleaseListQuery::new(CompactString::new("protobuf"));
+    assert_eq!(actual.query, Some(LinkQuery::ReleaseList(expected)));
+  }
+
+  #[test]
+  pub fn test_link_query() {
+    let pear_url = PearUrl(Url::parse("http://localhost:8080/mirror/").unwrap());
+    let document = pear_url.url_join(["c", "categories.xml"]);
+    let actual = resolve_link(&pear_url, &document, "Tools+and+Utilities/info.xml").unwrap();
+    let expected = GetCategoryInfoQuery::new(CompactString::new("Tools and Utilities"));
+    assert_eq!(actual.query, Some(LinkQuery::CategoryInfo(expected)));
+
+    let url = Url::parse("http://localhost:8080/mirror/rest/r/protobuf/beta.txt").unwrap();
+    let expected = GetLatestVersionQuery::new(CompactString::new("protobuf"), Some(Stability::Beta));
+    assert_eq!(link_query(&pear_url, &url), Some(LinkQuery::LatestVersion(expected)));
+
+    let url = Url::parse("https://pecl.php.net/rest/r/protobuf/beta.txt").unwrap();
+    assert_eq!(link_query(&pear_url, &url), None);
+  }
+}
@@ -0,0 +1,729 @@
+//! Channel mirror
+//!
+//! [`mirror_channel`] copies `channel.xml`, the REST documents and the release archives of a channel to a local
+//! directory, byte for byte and at the same paths as on the channel, so that the directory can be served as a channel
+//! (e.g. with the `DirectoryRepository` of the `server` feature). It copies the documents of the REST 1.0 to 1.3
+//! protocols:
+//!
+//! - the package list `rest/p/packages.xml`, and the `info.xml`, `maintainers.xml` and `maintainers2.xml` documents
+//!   of each package under `rest/p/{package}/`;
+//! - the release lists and latest versions of each package under `rest/r/{package}/`: `allreleases.xml`,
+//!   `allreleases2.xml`, `latest.txt`, `stable.txt`, `beta.txt`, `alpha.txt` and `devel.txt`;
+//! - the `{version}.xml`, `v2.{version}.xml`, `package.{version}.xml` and `deps.{version}.txt` documents of each
+//!   release, and its archive under `get/`;
+//! - the category list `rest/c/categories.xml` with the `info.xml`, `packages.xml` and `packagesinfo.xml` documents of
+//!   each category, and the maintainer list `rest/m/allmaintainers.xml` with the `info.xml` of each maintainer.
+//!
+//! Documents missing from the channel are skipped, e.g. `beta.txt` for a package without beta releases, or the REST 1.3
+//! documents of an older channel. `channel.xml` is copied as is: its REST base URLs still point to the mirrored
+//! channel.
+//!
+//! Archives are downloaded from the link of their release (`<g>`), as the PEAR installer does, and stored under `get/`
+//! with the file name of the link, even if the link points to another host.
+//!
+//! Releases are immutable: a release is only fetched if its `{version}.xml` document is missing from the mirror. This
+//! document is written after the archive, so an interrupted run is resumed by running it again. The listings are
+//! fetched on every run, and only written once all the releases they list are mirrored. In the same way, the package
+//! list is only written once all the packages it lists are mirrored, otherwise the previous copy is kept. The category
+//! and maintainer documents describe all the packages, they are only fetched and written by runs without any failure.
+//!
+//! The mirror is written with blocking [`std::fs`] calls, from within the returned future. On an async runtime, run it
+//! where blocking is allowed (e.g. `tokio::task::block_in_place`, as the `mirror` command does).
+
+use crate::client::http::{check_status, read_ok_body, HttpPearClientError, ReleaseArchiveStream};
+use crate::common::category::CategoryListing;
+use crate::common::channel::ChannelInfo;
+use crate::common::from_xml::FromXmlError;
+use crate::common::maintainer::MaintainerListing;
+use crate::common::package::{PackageInfo, PackageListing};
+use crate::common::release::{Release, ReleaseListing};
+use crate::context::PearUrl;
+use crate::query::download_release::DownloadReleaseQuery;
+use crate::url_util::{php_urlencode, url_join, url_join_php_encoded, url_join_root};
+use bytes::Bytes;
+use compact_str::{format_compact, CompactString, ToCompactString};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use http::{Method, Request, Response};
+use http_body::Body;
+use http_body_util::Full;
+use std::collections::BTreeSet;
+use std::error::Error as StdError;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tower_service::Service;
+use url::Url;
+
+/// Mirror settings
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MirrorConfig {
+  /// URL of the mirrored channel
+  pub pear_url: PearUrl,
+  /// Maximum number of requests in flight
+  pub concurrency: usize,
+}
+
+impl MirrorConfig {
+  /// Mirror the channel at `pear_url`, with up to 8 requests in flight
+  pub fn new(pear_url: PearUrl) -> Self {
+    Self {
+      pear_url,
+      concurrency: 8,
+    }
+  }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum MirrorError {
+  #[error("failed to fetch {0:?}")]
+  Fetch(CompactString, #[source] HttpPearClientError),
+  #[error("failed to parse {0:?}")]
+  Format(CompactString, #[source] FromXmlError),
+  #[error("name {0:?} can't be used as a path segment")]
+  UnsafeName(CompactString),
+  #[error("failed to write {0:?}: {1}")]
+  Write(CompactString, String),
+}
+
+/// Release downloaded during a mirror run
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MirroredRelease {
+  pub package: CompactString,
+  pub version: CompactString,
+  /// Archive size, in bytes
+  pub size: u64,
+}
+
+/// Package, release or channel document which could not be mirrored, it is retried on the next run
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MirrorFailure {
+  /// `None` for the category and maintainer documents of the channel
+  pub package: Option<CompactString>,
+  /// `None` if the documents of the package could not be fetched
+  pub version: Option<CompactString>,
+  pub error: MirrorError,
+}
+
+/// Summary of a mirror run
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MirrorReport {
+  /// Number of packages in the channel
+  pub packages: usize,
+  /// Number of releases listed by the channel
+  pub releases: usize,
+  /// Releases downloaded during this run, sorted
+  pub fetched: Vec<MirroredRelease>,
+  /// Number of releases which were already mirrored
+  pub skipped: usize,
+  /// Total size of the downloaded archives, in bytes
+  pub downloaded_bytes: u64,
+  /// Sorted failures
+  pub failures: Vec<MirrorFailure>,
+}
+
+impl MirrorReport {
+  /// Check that all the packages and releases of the channel are mirrored
+  pub fn is_complete(&self) -> bool {
+    self.failures.is_empty()
+  }
+}
+
+/// Documents of a package, written once its releases are mirrored
+struct PackageDocuments {
+  name: CompactString,
+  lower: CompactString,
+  /// Documents by path, `None` if the document is missing from the channel
+  documents: Vec<(CompactString, Option<Bytes>)>,
+  versions: Vec<CompactString>,
+}
+
+/// Mirror the channel from `config` into `output`, sending the requests through `transport`
+///
+/// Failures of single packages or releases are collected in the report; only the failure to fetch or write
+/// `channel.xml` or the package list is returned as an error.
+///
+/// The returned future does blocking filesystem I/O (see the [module documentation](self)).
+pub async fn mirror_channel<TyInner, TyBody>(
+  transport: TyInner,
+  config: &MirrorConfig,
+  output: &Path,
+) -> Result<MirrorReport, MirrorError>
+where
+  TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + Clone,
+  TyInner::Error: StdError,
+  TyBody: Body,
+  TyBody::Error: StdError,
+{
+  let mirror = &Mirror {
+    transport,
+    pear_url: &config.pear_url,
+    root: output,
+  };
+  let concurrency = config.concurrency.max(1);
+
+  let channel_path = CompactString::new("channel.xml");
+  let channel_body = mirror
+    .fetch(&channel_path, url_join_root(&config.pear_url, ["channel.xml"]))
+    .await?;
+  ChannelInfo::try_from_xml(&channel_body).map_err(|e| MirrorError::Format(channel_path.clone(), e))?;
+  let (listing_path, listing_body) = mirror.fetch_document(&["p", "packages.xml"]).await?;
+  let listing =
+    PackageListing::try_from_xml(&listing_body).map_err(|e| MirrorError::Format(listing_path.clone(), e))?;
+  let mut report = MirrorReport {
+    packages: listing.items.len(),
+    ..MirrorReport::default()
+  };
+
+  let packages: Vec<_> = stream::iter(&listing.items)
+    .map(|name| async move { (name, mirror.fetch_package(name).await) })
+    .buffer_unordered(concurrency)
+    .collect()
+    .await;
+  let mut documents = Vec::new();
+  let mut pending = Vec::new();
+  for (name, result) in packages {
+    let package = match result {
+      Ok(package) => package,
+      Err(error) => {
+        report.failures.push(MirrorFailure {
+          package: Some(name.clone()),
+          version: None,
+          error,
+        });
+        continue;
+      }
+    };
+    report.releases += package.versions.len();
+    for version in &package.versions {
+      if let Err(error) = check_segment(version) {
+        report.failures.push(MirrorFailure {
+          package: Some(package.name.clone()),
+          version: Some(version.clone()),
+          error,
+        });
+      } else if mirror.has_release(&package.lower, version) {
+        report.skipped += 1;
+      } else {
+        pending.push((package.name.clone(), package.lower.clone(), version.clone()));
+      }
+    }
+    documents.push(package);
+  }
+
+  let releases: Vec<_> = stream::iter(&pending)
+    .map(|(name, lower, version)| async move { (name, version, mirror.fetch_release(lower, version).await) })
+    .buffer_unordered(concurrency)
+    .collect()
+    .await;
+  for (name, version, result) in releases {
+    match result {
+      Ok(release) => {
+        report.downloaded_bytes += release.size;
+        report.fetched.push(release);
+      }
+      Err(error) => report.failures.push(MirrorFailure {
+        package: Some(name.clone()),
+        version: Some(version.clone()),
+        error,
+      }),
+    }
+  }
+
+  // keep the previous listings of incomplete packages, so the mirror never lists a missing release
+  let incomplete: BTreeSet<&CompactString> = report.failures.iter().filter_map(|f| f.package.as_ref()).collect();
+  let mut write_failures = Vec::new();
+  for package in &documents {
+    if incomplete.contains(&package.name) {
+      continue;
+    }
+    for (path, body) in &package.documents {
+      if let Err(error) = mirror.update(path, body.as_deref()) {
+        write_failures.push(MirrorFailure {
+          package: Some(package.name.clone()),
+          version: None,
+          error,
+        });
+        break;
+      }
+    }
+  }
+  report.failures.extend(write_failures);
+
+  // the category and maintainer documents list all the packages and releases, only update them on complete runs
+  if report.failures.is_empty() {
+    let result = match mirror.fetch_channel_documents(concurrency).await {
+      Ok(documents) => documents
+        .iter()
+        .try_for_each(|(path, body)| mirror.update(path, body.as_deref())),
+      Err(error) => Err(error),
+    };
+    if let Err(error) = result {
+      report.failures.push(MirrorFailure {
+        package: None,
+        version: None,
+        error,
+      });
+    }
+  }
+  // keep the previous package list while a package it lists is missing from the mirror
+  if listing.items.iter().all(|name| mirror.has_package(name)) {
+    mirror.write(&listing_path, &listing_body)?;
+  }
+  mirror.write(&channel_path, &channel_body)?;
+
+  report.fetched.sort();
+  report.failures.sort();
+  Ok(report)
+}
+
+struct Mirror<'a, TyInner> {
+  transport: TyInner,
+  pear_url: &'a PearUrl,
+  root: &'a Path,
+}
+
+impl<TyInner, TyBody> Mirror<'_, TyInner>
+where
+  TyInner: Service<Request<Full<Bytes>>, Response = Response<TyBody>> + Clone,
+  TyInner::Error: StdError,
+  TyBody: Body,
+  TyBody::Error: StdError,
+{
+  async fn get(&self, url: Url) -> Result<Response<TyBody>, HttpPearClientError> {
+    let mut transport = self.transport.clone();
+    futures::future::poll_fn(|cx| transport.poll_ready(cx))
+      .await
+      .map_err(|e| HttpPearClientError::PollReady(format!("{e:?}")))?;
+    let req = Request::builder()
+      .method(Method::GET)
+      .uri(url.as_str())
+      .body(Full::new(Bytes::new()))
+      .unwrap();
+    transport
+      .call(req)
+      .await
+      .map_err(|e| HttpPearClientError::Send(format!("{e:?}")))
+  }
+
+  /// Fetch a file, `path` is its path in the mirror
+  async fn fetch(&self, path: &str, url: Url) -> Result<Bytes, MirrorError> {
+    async { read_ok_body(self.get(url).await?).await }
+      .await
+      .map_err(|e| MirrorError::Fetch(CompactString::new(path), e))
+  }
+
+  /// Fetch a file which may be missing from the channel, returns `None` if it is missing
+  async fn fetch_optional(&self, path: &str, url: Url) -> Result<Option<Bytes>, MirrorError> {
+    match self.fetch(path, url).await {
+      Ok(body) => Ok(Some(body)),
+      Err(MirrorError::Fetch(_, HttpPearClientError::NotFound)) => Ok(None),
+      Err(e) => Err(e),
+    }
+  }
+
+  /// Fetch a REST document, returns its path in the mirror and its contents
+  async fn fetch_document(&self, segments: &[&str]) -> Result<(CompactString, Bytes), MirrorError> {
+    let path = format_compact!("rest/{}", segments.join("/"));
+    let body = self.fetch(&path, url_join(self.pear_url, segments)).await?;
+    Ok((path, body))
+  }
+
+  /// Fetch a REST document which may be missing from the channel
+  async fn fetch_optional_document(&self, segments: &[&str]) -> Result<(CompactString, Option<Bytes>), MirrorError> {
+    let path = format_compact!("rest/{}", segments.join("/"));
+    let body = self.fetch_optional(&path, url_join(self.pear_url, segments)).await?;
+    Ok((path, body))
+  }
+
+  /// Fetch the documents of a package: its info, maintainers, release lists and latest versions
+  async fn fetch_package(&self, name: &str) -> Result<PackageDocuments, MirrorError> {
+    // the REST paths use the lowercase name
+    let lower = name.to_lowercase().to_compact_string();
+    check_segment(&lower)?;
+    let (info_path, info) = self.fetch_document(&["p", &lower, "info.xml"]).await?;
+    PackageInfo::try_from_xml(&info).map_err(|e| MirrorError::Format(info_path.clone(), e))?;
+    let mut documents = vec![(info_path, Some(info))];
+    // packages without any release have no release list
+    let (path, body) = self.fetch_optional_document(&["r", &lower, "allreleases.xml"]).await?;
+    let versions = match &body {
+      Some(body) => {
+        let listing = ReleaseListing::try_from_xml(body).map_err(|e| MirrorError::Format(path.clone(), e))?;
+        listing
+          .items
+          .iter()
+          .map(|release| CompactString::new(release.version.as_str()))
+          .collect()
+      }
+      None => Vec::new(),
+    };
+    documents.push((path, body));
+    for segments in [
+      ["r", &lower, "allreleases2.xml"],
+      ["r", &lower, "latest.txt"],
+      ["r", &lower, "stable.txt"],
+      ["r", &lower, "beta.txt"],
+      ["r", &lower, "alpha.txt"],
+      ["r", &lower, "devel.txt"],
+      ["p", &lower, "maintainers.xml"],
+      ["p", &lower, "maintainers2.xml"],
+    ] {
+      documents.push(self.fetch_optional_document(&segments).await?);
+    }
+    Ok(PackageDocuments {
+      name: CompactString::new(name),
+      lower,
+      documents,
+      versions,
+    })
+  }
+
+  /// Fetch the category and maintainer documents of the channel
+  async fn fetch_channel_documents(
+    &self,
+    concurrency: usize,
+  ) -> Result<Vec<(CompactString, Option<Bytes>)>, MirrorError> {
+    let mut documents = Vec::new();
+    let mut targets = Vec::new();
+    let (path, categories) = self.fetch_optional_document(&["c", "categories.xml"]).await?;
+    if let Some(body) = &categories {
+      let listing = CategoryListing::try_from_xml(body).map_err(|e| MirrorError::Format(path.clone(), e))?;
+      for category in &listing.items {
+        // category directories are encoded like PHP `urlencode`
+        let dir = php_urlencode(&category.name);
+        check_segment(&dir)?;
+        for file in ["info.xml", "packages.xml", "packagesinfo.xml"] {
+          let url = url_join_php_encoded(self.pear_url, ["c", category.name.as_str(), file]);
+          targets.push((format_compact!("rest/c/{dir}/{file}"), url));
+        }
+      }
+    }
+    documents.push((path, categories));
+    let (path, maintainers) = self.fetch_optional_document(&["m", "allmaintainers.xml"]).await?;
+    if let Some(body) = &maintainers {
+      let listing = MaintainerListing::try_from_xml(body).map_err(|e| MirrorError::Format(path.clone(), e))?;
+      for maintainer in &listing.items {
+        check_segment(&maintainer.handle)?;
+        let url = url_join(self.pear_url, ["m", maintainer.handle.as_str(), "info.xml"]);
+        targets.push((format_compact!("rest/m/{}/info.xml", maintainer.handle), url));
+      }
+    }
+    documents.push((path, maintainers));
+    let fetched: Vec<_> = stream::iter(targets)
+      .map(|(path, url)| async move {
+        let body = self.fetch_optional(&path, url).await?;
+        Ok::<_, MirrorError>((path, body))
+      })
+      .buffer_unordered(concurrency)
+      .try_collect()
+      .await?;
+    documents.extend(fetched);
+    Ok(documents)
+  }
+
+  /// Download the documents and the archive of a release, then write its `{version}.xml` document
+  async fn fetch_release(&self, lower: &str, version: &str) -> Result<MirroredRelease, MirrorError> {
+    let (path, body) = self.fetch_document(&["r", lower, &format!("{version}.xml")]).await?;
+    let release =
+      Release::try_from_xml_with_base(&body, self.pear_url).map_err(|e| MirrorError::Format(path.clone(), e))?;
+    let mut documents = Vec::new();
+    for file in [
+      format!("v2.{version}.xml"),
+      format!("package.{version}.xml"),
+      format!("deps.{version}.txt"),
+    ] {
+      documents.push(self.fetch_optional_document(&["r", lower, &file]).await?);
+    }
+    let query = DownloadReleaseQuery::from_release(&release);
+    let file_name = query.file_name();
+    check_segment(&file_name)?;
+    let archive_path = format_compact!("get/{file_name}");
+    let fetch_error = |e| MirrorError::Fetch(archive_path.clone(), e);
+    let res = self.get(query.url(self.pear_url)).await.map_err(fetch_error)?;
+    check_status(&res).map_err(fetch_error)?;
+    let mut stream = ReleaseArchiveStream::new(res.into_body(), query.size);
+
+    let (target, part) = self.prepare(&archive_path)?;
+    let write_error = |e: std::io::Error| MirrorError::Write(archive_path.clone(), e.to_string());
+    let mut file = fs::File::create(&part).map_err(write_error)?;
+    let written: Result<(), MirrorError> = async {
+      while let Some(chunk) = stream.next().await {
+        file.write_all(&chunk.map_err(fetch_error)?).map_err(write_error)?;
+      }
+      file.sync_all().map_err(write_error)
+    }
+    .await;
+    drop(file);
+    if let Err(e) = written {
+      // do not leave a truncated archive behind
+      let _ = fs::remove_file(&part);
+      return Err(e);
+    }
+    fs::rename(&part, &target).map_err(write_error)?;
+    for (path, body) in &documents {
+      if let Some(body) = body {
+        self.write(path, body)?;
+      }
+    }
+    self.write(&path, &body)?;
+    Ok(MirroredRelease {
+      package: release.package.name,
+      version: CompactString::new(version),
+      size: stream.received(),
+    })
+  }
+}
+
+impl<TyInner> Mirror<'_, TyInner> {
+  fn has_package(&self, name: &str) -> bool {
+    let lower = name.to_lowercase();
+    check_segment(&lower).is_ok() && self.root.join(format!("rest/p/{lower}/info.xml")).is_file()
+  }
+
+  fn has_release(&self, lower: &str, version: &str) -> bool {
+    self.root.join(format!("rest/r/{lower}/{version}.xml")).is_file()
+  }
+
+  /// Write a file, or remove it if it is missing from the channel
+  fn update(&self, relative: &str, contents: Option<&[u8]>) -> Result<(), MirrorError> {
+    match contents {
+      Some(contents) => self.write(relative, contents),
+      None => match fs::remove_file(self.root.join(relative)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+          Err(MirrorError::Write(CompactString::new(relative), e.to_string()))
+        }
+        _ => Ok(()),
+      },
+    }
+  }
+
+  /// Write a file through a temporary file, so that it is never left truncated
+  fn write(&self, relative: &str, contents: &[u8]) -> Result<(), MirrorError> {
+    let (target, part) = self.prepare(relative)?;
+    fs::write(&part, contents)
+      .and_then(|()| fs::rename(&part, &target))
+      .map_err(|e| MirrorError::Write(CompactString::new(relative), e.to_string()))
+  }
+
+  /// Create the parent directory of a file, returns its path and the path of its temporary file
+  fn prepare(&self, relative: &str) -> Result<(PathBuf, PathBuf), MirrorError> {
+    let target = self.root.join(relative);
+    if let Some(parent) = target.parent() {
+      fs::create_dir_all(parent).map_err(|e| MirrorError::Write(CompactString::new(relative), e.to_string()))?;
+    }
+    let mut part = target.clone().into_os_string();
+    part.push(".part");
+    Ok((target, PathBuf::from(part)))
+  }
+}
+
+/// Check that a name read from the channel can be used as a single path segment
+fn check_segment(name: &str) -> Result<(), MirrorError> {
+  if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+    return Err(MirrorError::UnsafeName(CompactString::new(name)));
+  }
+  Ok(())
+}
+
+#[cfg(all(test, feature = "server", feature = "archive"))]
+mod test {
+  use super::*;
+  use crate::client::pear::PearClient;
+  use crate::common::stability::Stability;
+  use crate::server::{ChannelRepository, DirectoryRepository, MemoryRepository, PearServer};
+  use crate::static_channel::StaticChannelConfig;
+  use flate2::write::GzEncoder;
+  use flate2::Compression;
+
+  fn archive(version: &str) -> (CompactString, Bytes) {
+    let package_xml = format!(
+      r#"<?xml version="1.0" encoding="UTF-8"?>
+<package version="2.0" xmlns="http://pear.php.net/dtd/package-2.0">
+ <name>Demo</name>
+ <channel>pear.example.com</channel>
+ <summary>Demo</summary>
+ <description>Demo extension</description>
+ <lead>
+  <name>Demo Lead</name>
+  <user>lead</user>
+  <email>lead@example.com</email>
+  <active>yes</active>
+ </lead>
+ <date>2024-05-21</date>
+ <version>
+  <release>{version}</release>
+  <api>1.0.0</api>
+ </version>
+ <stability>
+  <release>stable</release>
+  <api>stable</api>
+ </stability>
+ <license>BSD-3-Clause</license>
+ <notes>-</notes>
+ <contents>
+  <dir name="/">
+   <file name="config.m4" role="src" />
+  </dir>
+ </contents>
+ <dependencies>
+  <required>
+   <php>
+    <min>8.0.0</min>
+   </php>
+   <pearinstaller>
+    <min>1.4.0</min>
+   </pearinstaller>
+  </required>
+ </dependencies>
+ <providesextension>demo</providesextension>
+ <extsrcrelease />
+</package>
+"#
+    );
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    let mut header = tar::Header::new_gnu();
+    header.set_path("package.xml").unwrap();
+    header.set_size(package_xml.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append(&header, package_xml.as_bytes()).unwrap();
+    let data = builder.into_inner().unwrap().finish().unwrap();
+    (format_compact!("Demo-{version}.tgz"), Bytes::from(data))
+  }
+
+  fn channel(versions: &[&str]) -> MemoryRepository {
+    let config = StaticChannelConfig::new(PearUrl(Url::parse("https://pear.example.com/").unwrap()));
+    MemoryRepository::from_archives(&config, versions.iter().map(|version| archive(version))).unwrap()
+  }
+
+  fn mirror(repository: &MemoryRepository, output: &Path) -> MirrorReport {
+    let config = MirrorConfig::new(PearUrl(Url::parse("https://pear.example.com/").unwrap()));
+    futures::executor::block_on(mirror_channel(PearServer::new(repository.clone()), &config, output)).unwrap()
+  }
+
+  fn fetched(report: &MirrorReport) -> Vec<&str> {
+    report.fetched.iter().map(|r| r.version.as_str()).collect()
+  }
+
+  #[test]
+  pub fn test_mirror_channel() {
+    let output = tempfile::tempdir().unwrap();
+    let repository = channel(&["1.0.0"]);
+    let report = mirror(&repository, output.path());
+    assert!(report.is_complete());
+    assert_eq!((report.packages, report.releases, report.skipped), (1, 1, 0));
+    assert_eq!(fetched(&report), ["1.0.0"]);
+    // the whole channel is mirrored
+    for path in repository.paths() {
+      let expected = repository.get(path).unwrap().unwrap();
+      assert_eq!(fs::read(output.path().join(path)).unwrap(), expected, "{path}");
+    }
+    assert_eq!(
+      report.downloaded_bytes,
+      repository.get("get/Demo-1.0.0.tgz").unwrap().unwrap().len() as u64
+    );
+
+    // only the new release is fetched
+    let repository = channel(&["1.0.0", "1.1.0"]);
+    let report = mirror(&repository, output.path());
+    assert_eq!(fetched(&report), ["1.1.0"]);
+    assert_eq!(report.skipped, 1);
+    let expected = repository.get("rest/r/demo/allreleases.xml").unwrap().unwrap();
+    assert_eq!(
+      fs::read(output.path().join("rest/r/demo/allreleases.xml")).unwrap(),
+      expected
+    );
+
+    // an interrupted release is fetched again
+    fs::remove_file(output.path().join("rest/r/demo/1.0.0.xml")).unwrap();
+    let report = mirror(&repository, output.path());
+    assert_eq!(fetched(&report), ["1.0.0"]);
+  }
+
+  #[test]
+  pub fn test_mirror_channel_keeps_listing_of_incomplete_packages() {
+    let output = tempfile::tempdir().unwrap();
+    let mut repository = channel(&["1.0.0", "1.1.0"]);
+    repository.remove("get/Demo-1.1.0.tgz");
+    let report = mirror(&repository, output.path());
+    assert_eq!(fetched(&report), ["1.0.0"]);
+    assert_eq!(
+      report.failures,
+      [MirrorFailure {
+        package: Some(CompactString::new("Demo")),
+        version: Some(CompactString::new("1.1.0")),
+        error: MirrorError::Fetch(CompactString::new("get/Demo-1.1.0.tgz"), HttpPearClientError::NotFound),
+      }]
+    );
+    assert!(!output.path().join("rest/r/demo/1.1.0.xml").exists());
+    assert!(!output.path().join("rest/r/demo/allreleases.xml").exists());
+    assert!(!output.path().join("get/Demo-1.1.0.tgz.part").exists());
+    // the package list is not written while it lists a package missing from the mirror
+    assert!(!output.path().join("rest/p/packages.xml").exists());
+    assert!(output.path().join("channel.xml").exists());
+
+    let repository = channel(&["1.0.0", "1.1.0"]);
+    let report = mirror(&repository, output.path());
+    assert!(report.is_complete());
+    assert_eq!(fetched(&report), ["1.1.0"]);
+    let expected = repository.get("rest/p/packages.xml").unwrap().unwrap();
+    assert_eq!(fs::read(output.path().join("rest/p/packages.xml")).unwrap(), expected);
+  }
+
+  #[test]
+  pub fn test_mirror_channel_follows_archive_links() {
+    let output = tempfile::tempdir().unwrap();
+    let mut repository = channel(&["1.0.0"]);
+    let release = repository.get("rest/r/demo/1.0.0.xml").unwrap().unwrap();
+    let release = String::from_utf8(release.to_vec()).unwrap().replace(
+      "https://pear.example.com/get/Demo-1.0.0",
+      "https://download.example.com/package/Demo-1.0.0",
+    );
+    repository.insert("rest/r/demo/1.0.0.xml", release);
+    let archive = repository.remove("get/Demo-1.0.0.tgz").unwrap();
+    // the server ignores the host, only the path tells the archive link apart from `/get/`
+    repository.insert("package/Demo-1.0.0.tgz", archive.clone());
+    let report = mirror(&repository, output.path());
+    assert!(report.is_complete(), "{:?}", report.failures);
+    assert_eq!(fs::read(output.path().join("get/Demo-1.0.0.tgz")).unwrap(), archive);
+  }
+
+  #[test]
+  pub fn test_mirror_channel_can_be_served() {
+    let output = tempfile::tempdir().unwrap();
+    let report = mirror(&channel(&["1.0.0", "1.1.0"]), output.path());
+    assert!(report.is_complete(), "{:?}", report.failures);
+
+    let server = PearServer::new(DirectoryRepository::new(output.path()));
+    let client = PearClient::http(server, PearUrl(Url::parse("https://pear.example.com/").unwrap()));
+    futures::executor::block_on(async {
+      let channel: ChannelInfo = client.channel().await.unwrap();
+      assert_eq!(channel.name, "pear.example.com");
+      assert_eq!(client.packages().await.unwrap().items, ["Demo"]);
+      let category = client.categories().await.unwrap().items[0].name.clone();
+      assert_eq!(client.category(&category).packages().await.unwrap().items.len(), 1);
+      let handle = client.maintainers().await.unwrap().items[0].handle.clone();
+      assert_eq!(client.maintainer(&handle).await.unwrap().handle, handle);
+
+      let package = client.package("demo");
+      assert_eq!(package.info().await.unwrap().name, "Demo");
+      assert_eq!(package.maintainers().await.unwrap().items.len(), 1);
+      assert_eq!(package.releases2().await.unwrap().items.len(), 2);
+      let latest = package.latest(Some(Stability::Stable)).await.unwrap();
+      assert_eq!(latest.as_str(), "1.1.0");
+      let release = package.release_v2("1.1.0").await.unwrap();
+      assert_eq!(release.php_version.as_str(), "8.0.0");
+      assert_eq!(package.package_xml("1.1.0").await.unwrap().name, "Demo");
+      let dependencies = package.dependencies("1.1.0").await.unwrap();
+      assert_eq!(dependencies.required.php.min.as_deref(), Some("8.0.0"));
+      let archive: Vec<Bytes> = client
+        .download(&release.release)
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+      assert_eq!(archive.concat().len() as u64, release.release.archive.size);
+    });
+  }
+}
@@ -0,0 +1,403 @@
+//! Encoder and decoder for the PHP `serialize()` format
+//!
+//! The PEAR REST API uses this format for the `deps.{version}.txt` files.
+//!
+//! <https://www.php.net/manual/en/function.serialize.php>
+
+use compact_str::CompactString;
+
+/// Decoded PHP value
+#[derive(Debug, Clone, PartialEq)]
+pub enum PhpValue {
+  Null,
+  Bool(bool),
+  Int(i64),
+  Float(f64),
+  /// PHP strings are byte strings, with no encoding
+  String(Vec<u8>),
+  /// Ordered map, PHP arrays are used both as lists and dictionaries
+  Array(Vec<(PhpKey, PhpValue)>),
+  Object {
+    class: Vec<u8>,
+    properties: Vec<(PhpKey, PhpValue)>,
+  },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PhpKey {
+  Int(i64),
+  String(Vec<u8>),
+}
+
+impl PhpKey {
+  pub fn as_bytes(&self) -> Option<&[u8]> {
+    match self {
+      Self::String(s) => Some(s.as_slice()),
+      Self::Int(_) => None,
+    }
+  }
+}
+
+impl PhpValue {
+  /// Get the entries of an array value
+  pub fn as_array(&self) -> Option<&[(PhpKey, PhpValue)]> {
+    match self {
+      Self::Array(entries) => Some(entries.as_slice()),
+      _ => None,
+    }
+  }
+
+  /// Get an array entry by string key
+  pub fn get(&self, key: &str) -> Option<&PhpValue> {
+    self
+      .as_array()?
+      .iter()
+      .find(|(k, _)| k.as_bytes() == Some(key.as_bytes()))
+      .map(|(_, v)| v)
+  }
+
+  /// Get the value as a string, integers are converted to their decimal representation
+  pub fn as_compact_str(&self) -> Option<CompactString> {
+    match self {
+      Self::String(s) => core::str::from_utf8(s).ok().map(CompactString::new),
+      Self::Int(i) => Some(CompactString::new(itoa::Buffer::new().format(*i))),
+      _ => None,
+    }
+  }
+
+  /// Check if the value is an array with integer keys, in ascending order
+  ///
+  /// The keys do not have to start at 0: the `package.xml` v1 parser of PEAR numbers its lists from 1.
+  pub fn is_list(&self) -> bool {
+    match self {
+      Self::Array(entries) => {
+        let mut previous = None;
+        entries.iter().all(|(k, _)| match k {
+          PhpKey::Int(i) if previous.map_or(true, |p| p < *i) => {
+            previous = Some(*i);
+            true
+          }
+          _ => false,
+        })
+      }
+      _ => false,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PhpUnserializeError {
+  #[error("unexpected end of input")]
+  UnexpectedEof,
+  #[error("unexpected byte at offset {0}")]
+  UnexpectedByte(usize),
+  #[error("invalid number at offset {0}")]
+  InvalidNumber(usize),
+  #[error("invalid array key at offset {0}")]
+  InvalidKey(usize),
+  #[error("trailing data at offset {0}")]
+  TrailingData(usize),
+  #[error("arrays nested more than {MAX_DEPTH} levels deep at offset {0}")]
+  TooDeep(usize),
+}
+
+/// Maximum nesting depth of arrays and objects, deeper inputs are rejected instead of overflowing the stack
+pub const MAX_DEPTH: usize = 64;
+
+/// Decode a complete PHP serialized value
+pub fn unserialize(input: &[u8]) -> Result<PhpValue, PhpUnserializeError> {
+  let mut parser = Parser {
+    input,
+    pos: 0,
+    depth: 0,
+  };
+  let value = parser.value()?;
+  if parser.pos != input.len() {
+    return Err(PhpUnserializeError::TrailingData(parser.pos));
+  }
+  Ok(value)
+}
+
+/// Encode a value in the PHP serialized format
+pub fn serialize(value: &PhpValue) -> Vec<u8> {
+  let mut out = Vec::new();
+  write_value(&mut out, value);
+  out
+}
+
+fn write_value(out: &mut Vec<u8>, value: &PhpValue) {
+  match value {
+    PhpValue::Null => out.extend_from_slice(b"N;"),
+    PhpValue::Bool(b) => out.extend_from_slice(if *b { b"b:1;" } else { b"b:0;" }),
+    PhpValue::Int(i) => {
+      out.extend_from_slice(b"i:");
+      out.extend_from_slice(itoa::Buffer::new().format(*i).as_bytes());
+      out.push(b';');
+    }
+    PhpValue::Float(f) => {
+      out.extend_from_slice(b"d:");
+      if f.is_nan() {
+        out.extend_from_slice(b"NAN");
+      } else if f.is_infinite() {
+        out.extend_from_slice(if *f > 0.0 { b"INF" } else { b"-INF" });
+      } else {
+        out.extend_from_slice(f.to_string().as_bytes());
+      }
+      out.push(b';');
+    }
+    PhpValue::String(s) => {
+      out.extend_from_slice(b"s:");
+      write_quoted(out, s);
+      out.push(b';');
+    }
+    PhpValue::Array(entries) => {
+      out.extend_from_slice(b"a:");
+      write_entries(out, entries);
+    }
+    PhpValue::Object { class, properties } => {
+      out.extend_from_slice(b"O:");
+      write_quoted(out, class);
+      out.push(b':');
+      write_entries(out, properties);
+    }
+  }
+}
+
+/// Write `{len}:"{bytes}"`
+fn write_quoted(out: &mut Vec<u8>, bytes: &[u8]) {
+  out.extend_from_slice(itoa::Buffer::new().format(bytes.len()).as_bytes());
+  out.extend_from_slice(b":\"");
+  out.extend_from_slice(bytes);
+  out.push(b'"');
+}
+
+/// Write `{len}:{entries}`
+fn write_entries(out: &mut Vec<u8>, entries: &[(PhpKey, PhpValue)]) {
+  out.extend_from_slice(itoa::Buffer::new().format(entries.len()).as_bytes());
+  out.extend_from_slice(b":{");
+  for (key, value) in entries {
+    match key {
+      PhpKey::Int(i) => write_value(out, &PhpValue::Int(*i)),
+      PhpKey::String(s) => {
+        out.extend_from_slice(b"s:");
+        write_quoted(out, s);
+        out.push(b';');
+      }
+    }
+    write_value(out, value);
+  }
+  out.push(b'}');
+}
+
+struct Parser<'a> {
+  input: &'a [u8],
+  pos: usize,
+  /// Number of arrays and objects being read
+  depth: usize,
+}
+
+impl<'a> Parser<'a> {
+  fn peek(&self) -> Result<u8, PhpUnserializeError> {
+    self
+      .input
+      .get(self.pos)
+      .copied()
+      .ok_or(PhpUnserializeError::UnexpectedEof)
+  }
+
+  fn expect(&mut self, byte: u8) -> Result<(), PhpUnserializeError> {
+    if self.peek()? != byte {
+      return Err(PhpUnserializeError::UnexpectedByte(self.pos));
+    }
+    self.pos += 1;
+    Ok(())
+  }
+
+  /// Read the raw bytes until `end` (excluded), and consume `end`
+  fn until(&mut self, end: u8) -> Result<&'a [u8], PhpUnserializeError> {
+    let start = self.pos;
+    let len = self.input[start..]
+      .iter()
+      .position(|b| *b == end)
+      .ok_or(PhpUnserializeError::UnexpectedEof)?;
+    self.pos = start + len + 1;
+    Ok(&self.input[start..start + len])
+  }
+
+  fn int_until(&mut self, end: u8) -> Result<i64, PhpUnserializeError> {
+    let start = self.pos;
+    let raw = self.until(end)?;
+    core::str::from_utf8(raw)
+      .ok()
+      .and_then(|s| s.parse::<i64>().ok())
+      .ok_or(PhpUnserializeError::InvalidNumber(start))
+  }
+
+  fn len_until(&mut self, end: u8) -> Result<usize, PhpUnserializeError> {
+    let start = self.pos;
+    let len = self.int_until(end)?;
+    usize::try_from(len).map_err(|_| PhpUnserializeError::InvalidNumber(start))
+  }
+
+  /// Read a `"..."` string with a known byte length
+  fn quoted(&mut self, len: usize) -> Result<Vec<u8>, PhpUnserializeError> {
+    self.expect(b'"')?;
+    let end = self.pos.checked_add(len).ok_or(PhpUnserializeError::UnexpectedEof)?;
+    let bytes = self
+      .input
+      .get(self.pos..end)
+      .ok_or(PhpUnserializeError::UnexpectedEof)?;
+    self.pos = end;
+    self.expect(b'"')?;
+    Ok(bytes.to_vec())
+  }
+
+  fn entries(&mut self, len: usize) -> Result<Vec<(PhpKey, PhpValue)>, PhpUnserializeError> {
+    if self.depth == MAX_DEPTH {
+      return Err(PhpUnserializeError::TooDeep(self.pos));
+    }
+    self.expect(b'{')?;
+    self.depth += 1;
+    let mut entries = Vec::with_capacity(len.min(1024));
+    for _ in 0..len {
+      let start = self.pos;
+      let key = match self.value()? {
+        PhpValue::Int(i) => PhpKey::Int(i),
+        PhpValue::String(s) => PhpKey::String(s),
+        _ => return Err(PhpUnserializeError::InvalidKey(start)),
+      };
+      let value = self.value()?;
+      entries.push((key, value));
+    }
+    self.expect(b'}')?;
+    self.depth -= 1;
+    Ok(entries)
+  }
+
+  fn value(&mut self) -> Result<PhpValue, PhpUnserializeError> {
+    let start = self.pos;
+    let tag = self.peek()?;
+    self.pos += 1;
+    if tag == b'N' {
+      self.expect(b';')?;
+      return Ok(PhpValue::Null);
+    }
+    self.expect(b':')?;
+    match tag {
+      b'b' => match self.until(b';')? {
+        b"0" => Ok(PhpValue::Bool(false)),
+        b"1" => Ok(PhpValue::Bool(true)),
+        _ => Err(PhpUnserializeError::UnexpectedByte(start + 2)),
+      },
+      b'i' => Ok(PhpValue::Int(self.int_until(b';')?)),
+      b'd' => {
+        let raw = self.until(b';')?;
+        let value = match raw {
+          b"INF" => f64::INFINITY,
+          b"-INF" => f64::NEG_INFINITY,
+          b"NAN" => f64::NAN,
+          raw => core::str::from_utf8(raw)
+            .ok()
+            .and_then(|s| s.parse::<f64>().ok())
+            .ok_or(PhpUnserializeError::InvalidNumber(start + 2))?,
+        };
+        Ok(PhpValue::Float(value))
+      }
+      b's' => {
+        let len = self.len_until(b':')?;
+        let s = self.quoted(len)?;
+        self.expect(b';')?;
+        Ok(PhpValue::String(s))
+      }
+      b'a' => {
+        let len = self.len_until(b':')?;
+        Ok(PhpValue::Array(self.entries(len)?))
+      }
+      b'O' => {
+        let class_len = self.len_until(b':')?;
+        let class = self.quoted(class_len)?;
+        self.expect(b':')?;
+        let len = self.len_until(b':')?;
+        let properties = self.entries(len)?;
+        Ok(PhpValue::Object { class, properties })
+      }
+      _ => Err(PhpUnserializeError::UnexpectedByte(start)),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  pub fn test_unserialize_scalars() {
+    assert_eq!(unserialize(b"N;"), Ok(PhpValue::Null));
+    assert_eq!(unserialize(b"b:0;"), Ok(PhpValue::Bool(false)));
+    assert_eq!(unserialize(b"i:-42;"), Ok(PhpValue::Int(-42)));
+    assert_eq!(unserialize(b"d:0.5;"), Ok(PhpValue::Float(0.5)));
+    assert_eq!(
+      unserialize("s:5:\"caf\u{e9}\";".as_bytes()),
+      Ok(PhpValue::String("caf\u{e9}".as_bytes().to_vec()))
+    );
+  }
+
+  #[test]
+  pub fn test_unserialize_array() {
+    let actual = unserialize(br#"a:2:{i:0;s:1:"a";s:3:"min";s:5:"8.0.0";}"#).unwrap();
+    let expected = PhpValue::Array(vec![
+      (PhpKey::Int(0), PhpValue::String(b"a".to_vec())),
+      (PhpKey::String(b"min".to_vec()), PhpValue::String(b"8.0.0".to_vec())),
+    ]);
+    assert_eq!(actual, expected);
+    assert_eq!(
+      actual.get("min").and_then(PhpValue::as_compact_str).as_deref(),
+      Some("8.0.0")
+    );
+    assert!(!actual.is_list());
+    assert!(unserialize(br#"a:2:{i:1;s:1:"a";i:2;s:1:"b";}"#).unwrap().is_list());
+    assert!(!unserialize(br#"a:2:{i:2;s:1:"a";i:1;s:1:"b";}"#).unwrap().is_list());
+  }
+
+  #[test]
+  pub fn test_unserialize_rejects_deep_nesting() {
+    let nested = |depth: usize| {
+      let mut input = "a:1:{i:0;".repeat(depth).into_bytes();
+      input.extend_from_slice(b"N;");
+      input.extend(std::iter::repeat(b'}').take(depth));
+      input
+    };
+    assert!(unserialize(&nested(MAX_DEPTH)).is_ok());
+    assert!(matches!(
+      unserialize(&nested(MAX_DEPTH + 1)),
+      Err(PhpUnserializeError::TooDeep(_))
+    ));
+    assert!(matches!(
+      unserialize(&nested(100_000)),
+      Err(PhpUnserializeError::TooDeep(_))
+    ));
+  }
+
+  #[test]
+  pub fn test_serialize_round_trip() {
+    let inputs: [&[u8]; 6] = [
+      b"N;",
+      b"b:1;",
+      b"d:0.5;",
+      b"d:-INF;",
+      br#"a:2:{i:0;s:1:"a";s:3:"min";a:1:{i:3;i:-42;}}"#,
+      br#"O:8:"stdClass":1:{s:1:"a";b:0;}"#,
+    ];
+    for input in inputs {
+      assert_eq!(serialize(&unserialize(input).unwrap()), input);
+    }
+  }
+
+  #[test]
+  pub fn test_unserialize_invalid() {
+    assert_eq!(unserialize(b"s:10:\"abc\";"), Err(PhpUnserializeError::UnexpectedEof));
+    assert_eq!(unserialize(b"i:1;i:2;"), Err(PhpUnserializeError::TrailingData(4)));
+    assert_eq!(unserialize(b"x:1;"), Err(PhpUnserializeError::UnexpectedByte(0)));
+  }
+}
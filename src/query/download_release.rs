@@ -0,0 +1,107 @@
+use crate::common::release::Release;
+use crate::context::{EmptyContext, PearUrl};
+use crate::url_util::url_join_root;
+use compact_str::{format_compact, CompactString};
+use url::Url;
+
+/// Download the archive of a release
+///
+/// The archive is downloaded from the release link (see [`Self::from_release`]), or from
+/// `/get/{package}-{version}.tgz` on the channel if the link is unknown. If `size` is set, the number of received bytes
+/// is checked against it.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DownloadReleaseQuery<Cx, Str = CompactString> {
+  pub context: Cx,
+  pub package: Str,
+  pub version: Str,
+  /// Archive link, from the `<g>` node of the release, without the `.tgz` extension
+  pub link: Option<Url>,
+  /// Expected size in bytes
+  pub size: Option<u64>,
+}
+
+pub type DownloadReleaseQueryView<'req, Cx, Str> = DownloadReleaseQuery<&'req Cx, Str>;
+
+impl<Cx, Str> DownloadReleaseQuery<Cx, Str> {
+  pub fn set_context<NewCx>(self, new_context: NewCx) -> DownloadReleaseQuery<NewCx, Str> {
+    DownloadReleaseQuery {
+      context: new_context,
+      package: self.package,
+      version: self.version,
+      link: self.link,
+      size: self.size,
+    }
+  }
+
+  /// Archive URL: the link with the `.tgz` extension, as requested by the PEAR installer, or
+  /// `/get/{package}-{version}.tgz` on the channel at `pear_url`
+  pub fn url(&self, pear_url: &PearUrl) -> Url
+  where
+    Str: AsRef<str>,
+  {
+    match &self.link {
+      Some(link) => {
+        let mut url = link.clone();
+        url.set_path(&format!("{}.tgz", link.path()));
+        url
+      }
+      None => url_join_root(pear_url, ["get", self.file_name().as_str()]),
+    }
+  }
+}
+
+impl<Cx, Str> DownloadReleaseQuery<Cx, Str>
+where
+  Str: AsRef<str>,
+{
+  pub fn as_view(&self) -> DownloadReleaseQueryView<'_, Cx, &str> {
+    DownloadReleaseQueryView {
+      context: &self.context,
+      package: self.package.as_ref(),
+      version: self.version.as_ref(),
+      link: self.link.clone(),
+      size: self.size,
+    }
+  }
+
+  /// Archive file name, e.g. `protobuf-4.27.0.tgz`
+  ///
+  /// This is the last segment of the link, as it appears in the URL, or `{package}-{version}.tgz`.
+  pub fn file_name(&self) -> CompactString {
+    let segment = self
+      .link
+      .as_ref()
+      .and_then(|link| link.path_segments()?.next_back())
+      .filter(|segment| !segment.is_empty());
+    match segment {
+      Some(segment) => format_compact!("{segment}.tgz"),
+      None => format_compact!("{}-{}.tgz", self.package.as_ref(), self.version.as_ref()),
+    }
+  }
+}
+
+impl DownloadReleaseQuery<EmptyContext> {
+  /// Download the archive of a release from `/get/{package}-{version}.tgz`, when its link is unknown
+  pub const fn new(package: CompactString, version: CompactString, size: Option<u64>) -> Self {
+    Self {
+      context: EmptyContext::new(),
+      package,
+      version,
+      link: None,
+      size,
+    }
+  }
+
+  /// Download the archive of `release` from its link, checking its size
+  pub fn from_release(release: &Release) -> Self {
+    Self {
+      link: Some(release.archive.link.clone()),
+      ..Self::new(
+        release.package.name.clone(),
+        CompactString::new(release.version.as_str()),
+        Some(release.archive.size),
+      )
+    }
+  }
+}
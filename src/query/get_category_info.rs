@@ -0,0 +1,45 @@
+use crate::context::EmptyContext;
+use compact_str::CompactString;
+
+/// Category information
+///
+/// <http://pear.php.net/dtd/rest.category.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GetCategoryInfoQuery<Cx, Str = CompactString> {
+  pub context: Cx,
+  /// Category name, it is encoded when building the URL
+  pub category: Str,
+}
+
+pub type GetCategoryInfoQueryView<'req, Cx, Str> = GetCategoryInfoQuery<&'req Cx, Str>;
+
+impl<Cx, Str> GetCategoryInfoQuery<Cx, Str> {
+  pub fn set_context<NewCx>(self, new_context: NewCx) -> GetCategoryInfoQuery<NewCx, Str> {
+    GetCategoryInfoQuery {
+      context: new_context,
+      category: self.category,
+    }
+  }
+}
+
+impl<Cx, Str> GetCategoryInfoQuery<Cx, Str>
+where
+  Str: AsRef<str>,
+{
+  pub fn as_view(&self) -> GetCategoryInfoQueryView<'_, Cx, &str> {
+    GetCategoryInfoQueryView {
+      context: &self.context,
+      category: self.category.as_ref(),
+    }
+  }
+}
+
+impl GetCategoryInfoQuery<EmptyContext> {
+  pub const fn new(category: CompactString) -> Self {
+    Self {
+      context: EmptyContext::new(),
+      category,
+    }
+  }
+}
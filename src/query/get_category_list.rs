@@ -0,0 +1,36 @@
+use crate::context::EmptyContext;
+
+/// List of all categories
+///
+/// <http://pear.php.net/dtd/rest.allcategories.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GetCategoryListQuery<Cx> {
+  pub context: Cx,
+}
+
+pub type GetCategoryListQueryView<'req, Cx> = GetCategoryListQuery<&'req Cx>;
+
+impl<Cx> GetCategoryListQuery<Cx> {
+  pub fn set_context<NewCx>(self, new_context: NewCx) -> GetCategoryListQuery<NewCx> {
+    GetCategoryListQuery { context: new_context }
+  }
+
+  pub fn as_view(&self) -> GetCategoryListQueryView<'_, Cx> {
+    GetCategoryListQueryView { context: &self.context }
+  }
+}
+
+impl GetCategoryListQuery<EmptyContext> {
+  pub const fn new() -> Self {
+    Self {
+      context: EmptyContext::new(),
+    }
+  }
+}
+
+impl Default for GetCategoryListQuery<EmptyContext> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
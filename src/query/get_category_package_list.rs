@@ -0,0 +1,45 @@
+use crate::context::EmptyContext;
+use compact_str::CompactString;
+
+/// List of the packages in a category
+///
+/// <http://pear.php.net/dtd/rest.categorypackages.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GetCategoryPackageListQuery<Cx, Str = CompactString> {
+  pub context: Cx,
+  /// Category name, it is encoded when building the URL
+  pub category: Str,
+}
+
+pub type GetCategoryPackageListQueryView<'req, Cx, Str> = GetCategoryPackageListQuery<&'req Cx, Str>;
+
+impl<Cx, Str> GetCategoryPackageListQuery<Cx, Str> {
+  pub fn set_context<NewCx>(self, new_context: NewCx) -> GetCategoryPackageListQuery<NewCx, Str> {
+    GetCategoryPackageListQuery {
+      context: new_context,
+      category: self.category,
+    }
+  }
+}
+
+impl<Cx, Str> GetCategoryPackageListQuery<Cx, Str>
+where
+  Str: AsRef<str>,
+{
+  pub fn as_view(&self) -> GetCategoryPackageListQueryView<'_, Cx, &str> {
+    GetCategoryPackageListQueryView {
+      context: &self.context,
+      category: self.category.as_ref(),
+    }
+  }
+}
+
+impl GetCategoryPackageListQuery<EmptyContext> {
+  pub const fn new(category: CompactString) -> Self {
+    Self {
+      context: EmptyContext::new(),
+      category,
+    }
+  }
+}
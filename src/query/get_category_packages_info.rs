@@ -0,0 +1,45 @@
+use crate::context::EmptyContext;
+use compact_str::CompactString;
+
+/// Information, releases and dependencies of all the packages in a category
+///
+/// <http://pear.php.net/dtd/rest.categorypackageinfo.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GetCategoryPackagesInfoQuery<Cx, Str = CompactString> {
+  pub context: Cx,
+  /// Category name, it is encoded when building the URL
+  pub category: Str,
+}
+
+pub type GetCategoryPackagesInfoQueryView<'req, Cx, Str> = GetCategoryPackagesInfoQuery<&'req Cx, Str>;
+
+impl<Cx, Str> GetCategoryPackagesInfoQuery<Cx, Str> {
+  pub fn set_context<NewCx>(self, new_context: NewCx) -> GetCategoryPackagesInfoQuery<NewCx, Str> {
+    GetCategoryPackagesInfoQuery {
+      context: new_context,
+      category: self.category,
+    }
+  }
+}
+
+impl<Cx, Str> GetCategoryPackagesInfoQuery<Cx, Str>
+where
+  Str: AsRef<str>,
+{
+  pub fn as_view(&self) -> GetCategoryPackagesInfoQueryView<'_, Cx, &str> {
+    GetCategoryPackagesInfoQueryView {
+      context: &self.context,
+      category: self.category.as_ref(),
+    }
+  }
+}
+
+impl GetCategoryPackagesInfoQuery<EmptyContext> {
+  pub const fn new(category: CompactString) -> Self {
+    Self {
+      context: EmptyContext::new(),
+      category,
+    }
+  }
+}
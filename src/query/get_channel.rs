@@ -0,0 +1,36 @@
+use crate::context::EmptyContext;
+
+/// Channel definition
+///
+/// <https://pear.php.net/dtd/channel-1.0.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GetChannelQuery<Cx> {
+  pub context: Cx,
+}
+
+pub type GetChannelQueryView<'req, Cx> = GetChannelQuery<&'req Cx>;
+
+impl<Cx> GetChannelQuery<Cx> {
+  pub fn set_context<NewCx>(self, new_context: NewCx) -> GetChannelQuery<NewCx> {
+    GetChannelQuery { context: new_context }
+  }
+
+  pub fn as_view(&self) -> GetChannelQueryView<'_, Cx> {
+    GetChannelQueryView { context: &self.context }
+  }
+}
+
+impl GetChannelQuery<EmptyContext> {
+  pub const fn new() -> Self {
+    Self {
+      context: EmptyContext::new(),
+    }
+  }
+}
+
+impl Default for GetChannelQuery<EmptyContext> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -0,0 +1,72 @@
+use crate::common::stability::Stability;
+use crate::context::EmptyContext;
+use compact_str::CompactString;
+
+/// Latest version of a package, from `latest.txt`, `stable.txt`, `beta.txt`, `alpha.txt` or `devel.txt`
+///
+/// The channel only considers releases at least as stable as the requested stability: `beta.txt` contains the latest
+/// release which is either `beta` or `stable`. If `stability` is `None`, the latest release of any stability is
+/// returned. Channels have no file for the other stabilities (`snapshot` and unknown values), they are rejected with
+/// [`UnsupportedLatestStability`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GetLatestVersionQuery<Cx, Str = CompactString> {
+  pub context: Cx,
+  pub package: Str,
+  pub stability: Option<Stability>,
+}
+
+pub type GetLatestVersionQueryView<'req, Cx, Str> = GetLatestVersionQuery<&'req Cx, Str>;
+
+impl<Cx, Str> GetLatestVersionQuery<Cx, Str> {
+  pub fn set_context<NewCx>(self, new_context: NewCx) -> GetLatestVersionQuery<NewCx, Str> {
+    GetLatestVersionQuery {
+      context: new_context,
+      package: self.package,
+      stability: self.stability.clone(),
+    }
+  }
+
+  /// Name of the file holding the latest version
+  ///
+  /// Channels only provide files for `stable`, `beta`, `alpha` and `devel`.
+  pub fn file_name(&self) -> Result<&'static str, UnsupportedLatestStability> {
+    match &self.stability {
+      None => Ok("latest.txt"),
+      Some(Stability::Stable) => Ok("stable.txt"),
+      Some(Stability::Beta) => Ok("beta.txt"),
+      Some(Stability::Alpha) => Ok("alpha.txt"),
+      Some(Stability::Devel) => Ok("devel.txt"),
+      Some(other) => Err(UnsupportedLatestStability(CompactString::new(other.as_str()))),
+    }
+  }
+}
+
+/// Stability without a latest version file on the channel
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+#[error("no latest version file for stability {0:?}, expected stable, beta, alpha or devel")]
+pub struct UnsupportedLatestStability(pub CompactString);
+
+impl<Cx, Str> GetLatestVersionQuery<Cx, Str>
+where
+  Str: AsRef<str>,
+{
+  pub fn as_view(&self) -> GetLatestVersionQueryView<'_, Cx, &str> {
+    GetLatestVersionQueryView {
+      context: &self.context,
+      package: self.package.as_ref(),
+      stability: self.stability.clone(),
+    }
+  }
+}
+
+impl GetLatestVersionQuery<EmptyContext> {
+  pub const fn new(package: CompactString, stability: Option<Stability>) -> Self {
+    Self {
+      context: EmptyContext::new(),
+      package,
+      stability,
+    }
+  }
+}
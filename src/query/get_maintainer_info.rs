@@ -0,0 +1,44 @@
+use crate::context::EmptyContext;
+use compact_str::CompactString;
+
+/// Maintainer information
+///
+/// <http://pear.php.net/dtd/rest.maintainer.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GetMaintainerInfoQuery<Cx, Str = CompactString> {
+  pub context: Cx,
+  pub handle: Str,
+}
+
+pub type GetMaintainerInfoQueryView<'req, Cx, Str> = GetMaintainerInfoQuery<&'req Cx, Str>;
+
+impl<Cx, Str> GetMaintainerInfoQuery<Cx, Str> {
+  pub fn set_context<NewCx>(self, new_context: NewCx) -> GetMaintainerInfoQuery<NewCx, Str> {
+    GetMaintainerInfoQuery {
+      context: new_context,
+      handle: self.handle,
+    }
+  }
+}
+
+impl<Cx, Str> GetMaintainerInfoQuery<Cx, Str>
+where
+  Str: AsRef<str>,
+{
+  pub fn as_view(&self) -> GetMaintainerInfoQueryView<'_, Cx, &str> {
+    GetMaintainerInfoQueryView {
+      context: &self.context,
+      handle: self.handle.as_ref(),
+    }
+  }
+}
+
+impl GetMaintainerInfoQuery<EmptyContext> {
+  pub const fn new(handle: CompactString) -> Self {
+    Self {
+      context: EmptyContext::new(),
+      handle,
+    }
+  }
+}
@@ -0,0 +1,36 @@
+use crate::context::EmptyContext;
+
+/// List of all maintainers
+///
+/// <http://pear.php.net/dtd/rest.allmaintainers.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GetMaintainerListQuery<Cx> {
+  pub context: Cx,
+}
+
+pub type GetMaintainerListQueryView<'req, Cx> = GetMaintainerListQuery<&'req Cx>;
+
+impl<Cx> GetMaintainerListQuery<Cx> {
+  pub fn set_context<NewCx>(self, new_context: NewCx) -> GetMaintainerListQuery<NewCx> {
+    GetMaintainerListQuery { context: new_context }
+  }
+
+  pub fn as_view(&self) -> GetMaintainerListQueryView<'_, Cx> {
+    GetMaintainerListQueryView { context: &self.context }
+  }
+}
+
+impl GetMaintainerListQuery<EmptyContext> {
+  pub const fn new() -> Self {
+    Self {
+      context: EmptyContext::new(),
+    }
+  }
+}
+
+impl Default for GetMaintainerListQuery<EmptyContext> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -0,0 +1,44 @@
+use crate::context::EmptyContext;
+use compact_str::CompactString;
+
+/// Maintainers of a package, with their `active` flag
+///
+/// <http://pear.php.net/dtd/rest.packagemaintainers.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GetPackageMaintainersQuery<Cx, Str = CompactString> {
+  pub context: Cx,
+  pub package: Str,
+}
+
+pub type GetPackageMaintainersQueryView<'req, Cx, Str> = GetPackageMaintainersQuery<&'req Cx, Str>;
+
+impl<Cx, Str> GetPackageMaintainersQuery<Cx, Str> {
+  pub fn set_context<NewCx>(self, new_context: NewCx) -> GetPackageMaintainersQuery<NewCx, Str> {
+    GetPackageMaintainersQuery {
+      context: new_context,
+      package: self.package,
+    }
+  }
+}
+
+impl<Cx, Str> GetPackageMaintainersQuery<Cx, Str>
+where
+  Str: AsRef<str>,
+{
+  pub fn as_view(&self) -> GetPackageMaintainersQueryView<'_, Cx, &str> {
+    GetPackageMaintainersQueryView {
+      context: &self.context,
+      package: self.package.as_ref(),
+    }
+  }
+}
+
+impl GetPackageMaintainersQuery<EmptyContext> {
+  pub const fn new(package: CompactString) -> Self {
+    Self {
+      context: EmptyContext::new(),
+      package,
+    }
+  }
+}
@@ -0,0 +1,44 @@
+use crate::context::EmptyContext;
+use compact_str::CompactString;
+
+/// Maintainers of a package, with their `active` flag and role
+///
+/// <http://pear.php.net/dtd/rest.packagemaintainers2.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GetPackageMaintainers2Query<Cx, Str = CompactString> {
+  pub context: Cx,
+  pub package: Str,
+}
+
+pub type GetPackageMaintainers2QueryView<'req, Cx, Str> = GetPackageMaintainers2Query<&'req Cx, Str>;
+
+impl<Cx, Str> GetPackageMaintainers2Query<Cx, Str> {
+  pub fn set_context<NewCx>(self, new_context: NewCx) -> GetPackageMaintainers2Query<NewCx, Str> {
+    GetPackageMaintainers2Query {
+      context: new_context,
+      package: self.package,
+    }
+  }
+}
+
+impl<Cx, Str> GetPackageMaintainers2Query<Cx, Str>
+where
+  Str: AsRef<str>,
+{
+  pub fn as_view(&self) -> GetPackageMaintainers2QueryView<'_, Cx, &str> {
+    GetPackageMaintainers2QueryView {
+      context: &self.context,
+      package: self.package.as_ref(),
+    }
+  }
+}
+
+impl GetPackageMaintainers2Query<EmptyContext> {
+  pub const fn new(package: CompactString) -> Self {
+    Self {
+      context: EmptyContext::new(),
+      package,
+    }
+  }
+}
@@ -0,0 +1,48 @@
+use crate::context::EmptyContext;
+use compact_str::CompactString;
+
+/// Package description (`package.xml`) of a release
+///
+/// <https://pear.php.net/dtd/package-2.1.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GetPackageXmlQuery<Cx, Str = CompactString> {
+  pub context: Cx,
+  pub package: Str,
+  pub version: Str,
+}
+
+pub type GetPackageXmlQueryView<'req, Cx, Str> = GetPackageXmlQuery<&'req Cx, Str>;
+
+impl<Cx, Str> GetPackageXmlQuery<Cx, Str> {
+  pub fn set_context<NewCx>(self, new_context: NewCx) -> GetPackageXmlQuery<NewCx, Str> {
+    GetPackageXmlQuery {
+      context: new_context,
+      package: self.package,
+      version: self.version,
+    }
+  }
+}
+
+impl<Cx, Str> GetPackageXmlQuery<Cx, Str>
+where
+  Str: AsRef<str>,
+{
+  pub fn as_view(&self) -> GetPackageXmlQueryView<'_, Cx, &str> {
+    GetPackageXmlQueryView {
+      context: &self.context,
+      package: self.package.as_ref(),
+      version: self.version.as_ref(),
+    }
+  }
+}
+
+impl GetPackageXmlQuery<EmptyContext> {
+  pub const fn new(package: CompactString, version: CompactString) -> Self {
+    Self {
+      context: EmptyContext::new(),
+      package,
+      version,
+    }
+  }
+}
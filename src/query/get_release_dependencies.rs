@@ -0,0 +1,48 @@
+use crate::context::EmptyContext;
+use compact_str::CompactString;
+
+/// Dependencies of a release, from `deps.{version}.txt`
+///
+/// The file is serialized with the PHP `serialize()` function.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GetReleaseDependenciesQuery<Cx, Str = CompactString> {
+  pub context: Cx,
+  pub package: Str,
+  pub version: Str,
+}
+
+pub type GetReleaseDependenciesQueryView<'req, Cx, Str> = GetReleaseDependenciesQuery<&'req Cx, Str>;
+
+impl<Cx, Str> GetReleaseDependenciesQuery<Cx, Str> {
+  pub fn set_context<NewCx>(self, new_context: NewCx) -> GetReleaseDependenciesQuery<NewCx, Str> {
+    GetReleaseDependenciesQuery {
+      context: new_context,
+      package: self.package,
+      version: self.version,
+    }
+  }
+}
+
+impl<Cx, Str> GetReleaseDependenciesQuery<Cx, Str>
+where
+  Str: AsRef<str>,
+{
+  pub fn as_view(&self) -> GetReleaseDependenciesQueryView<'_, Cx, &str> {
+    GetReleaseDependenciesQueryView {
+      context: &self.context,
+      package: self.package.as_ref(),
+      version: self.version.as_ref(),
+    }
+  }
+}
+
+impl GetReleaseDependenciesQuery<EmptyContext> {
+  pub const fn new(package: CompactString, version: CompactString) -> Self {
+    Self {
+      context: EmptyContext::new(),
+      package,
+      version,
+    }
+  }
+}
@@ -0,0 +1,48 @@
+use crate::context::EmptyContext;
+use compact_str::CompactString;
+
+/// Release information with the API and minimum PHP versions, from `v2.{version}.xml`
+///
+/// <https://pear.php.net/dtd/rest.release2.xsd>
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GetReleaseV2Query<Cx, Str = CompactString> {
+  pub context: Cx,
+  pub package: Str,
+  pub version: Str,
+}
+
+pub type GetReleaseV2QueryView<'req, Cx, Str> = GetReleaseV2Query<&'req Cx, Str>;
+
+impl<Cx, Str> GetReleaseV2Query<Cx, Str> {
+  pub fn set_context<NewCx>(self, new_context: NewCx) -> GetReleaseV2Query<NewCx, Str> {
+    GetReleaseV2Query {
+      context: new_context,
+      package: self.package,
+      version: self.version,
+    }
+  }
+}
+
+impl<Cx, Str> GetReleaseV2Query<Cx, Str>
+where
+  Str: AsRef<str>,
+{
+  pub fn as_view(&self) -> GetReleaseV2QueryView<'_, Cx, &str> {
+    GetReleaseV2QueryView {
+      context: &self.context,
+      package: self.package.as_ref(),
+      version: self.version.as_ref(),
+    }
+  }
+}
+
+impl GetReleaseV2Query<EmptyContext> {
+  pub const fn new(package: CompactString, version: CompactString) -> Self {
+    Self {
+      context: EmptyContext::new(),
+      package,
+      version,
+    }
+  }
+}
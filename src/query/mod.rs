@@ -1,8 +1,21 @@
-pub mod get_package_list;
-pub mod get_release_list2;
-pub mod get_release_list;
+pub mod download_release;
+pub mod get_category_info;
+pub mod get_category_list;
+pub mod get_category_package_list;
+pub mod get_category_packages_info;
+pub mod get_channel;
+pub mod get_latest_version;
+pub mod get_maintainer_info;
+pub mod get_maintainer_list;
 pub mod get_package_info;
+pub mod get_package_list;
+pub mod get_package_maintainers;
+pub mod get_package_maintainers2;
+pub mod get_package_xml;
 pub mod get_release;
+pub mod get_release_dependencies;
+pub mod get_release_list;
+pub mod get_release_list2;
+pub mod get_release_v2;
 // todo:
-// - https://pecl.php.net/channel.xml
 // - https://pecl.php.net/rest/r/protobuf/4.27.0.xml
@@ -0,0 +1,343 @@
+//! Channel server
+//!
+//! [`PearServer`] is a [`Service`] serving the files of a channel (`channel.xml`, the REST tree under `rest/` and the
+//! archives under `get/`) from a [`ChannelRepository`]. It can be used as the transport of
+//! [`HttpPearClient`](crate::client::http::HttpPearClient) in tests, or run behind hyper to host a small private
+//! channel (see the `local_server` example).
+//!
+//! The repository uses the layout of [`generate_static_channel`](crate::static_channel::generate_static_channel).
+//! Request paths are matched as they are sent, without percent-decoding: category directories are already encoded
+//! like PHP `urlencode`.
+
+use bytes::Bytes;
+use compact_str::CompactString;
+use core::convert::Infallible;
+use core::task::{Context, Poll};
+use futures::future::{ready, Ready};
+use http::header::{ALLOW, CONTENT_LENGTH, CONTENT_TYPE};
+use http::{HeaderValue, Method, Request, Response, StatusCode};
+use http_body_util::Full;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use tower_service::Service;
+
+/// Read access to the files of a channel
+pub trait ChannelRepository {
+  /// Contents of the file at `path`, relative to the channel root (e.g. `rest/p/packages.xml`)
+  ///
+  /// Returns `Ok(None)` if there is no such file.
+  fn get(&self, path: &str) -> io::Result<Option<Bytes>>;
+}
+
+impl<R: ChannelRepository + ?Sized> ChannelRepository for Arc<R> {
+  fn get(&self, path: &str) -> io::Result<Option<Bytes>> {
+    (**self).get(path)
+  }
+}
+
+/// Channel files stored in a directory
+///
+/// Files are read synchronously, on each request.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DirectoryRepository {
+  root: PathBuf,
+}
+
+impl DirectoryRepository {
+  pub fn new(root: impl Into<PathBuf>) -> Self {
+    Self { root: root.into() }
+  }
+
+  pub fn root(&self) -> &Path {
+    &self.root
+  }
+}
+
+impl ChannelRepository for DirectoryRepository {
+  fn get(&self, path: &str) -> io::Result<Option<Bytes>> {
+    let relative = Path::new(path);
+    if !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+      return Ok(None);
+    }
+    let target = self.root.join(relative);
+    match fs::metadata(&target) {
+      Ok(metadata) if metadata.is_file() => fs::read(&target).map(|data| Some(Bytes::from(data))),
+      Ok(_) => Ok(None),
+      // also covers paths going through a regular file
+      Err(_) if !target.exists() => Ok(None),
+      Err(e) => Err(e),
+    }
+  }
+}
+
+/// Channel files stored in memory, by path
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MemoryRepository {
+  files: BTreeMap<CompactString, Bytes>,
+}
+
+impl MemoryRepository {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Generate a channel from release archives, provided as `(file name, contents)` pairs
+  ///
+  /// See [`generate_channel_files`](crate::static_channel::generate_channel_files).
+  #[cfg(feature = "archive")]
+  pub fn from_archives<I>(
+    config: &crate::static_channel::StaticChannelConfig,
+    archives: I,
+  ) -> Result<Self, crate::static_channel::GenerateChannelError>
+  where
+    I: IntoIterator<Item = (CompactString, Bytes)>,
+  {
+    crate::static_channel::generate_channel_files(config, archives).map(Self::from)
+  }
+
+  /// Add or replace the file at `path`, relative to the channel root
+  pub fn insert(&mut self, path: impl Into<CompactString>, contents: impl Into<Bytes>) -> Option<Bytes> {
+    self.files.insert(path.into(), contents.into())
+  }
+
+  pub fn remove(&mut self, path: &str) -> Option<Bytes> {
+    self.files.remove(path)
+  }
+
+  /// Paths of the files, sorted
+  pub fn paths(&self) -> impl Iterator<Item = &str> {
+    self.files.keys().map(CompactString::as_str)
+  }
+}
+
+impl From<BTreeMap<CompactString, Bytes>> for MemoryRepository {
+  fn from(files: BTreeMap<CompactString, Bytes>) -> Self {
+    Self { files }
+  }
+}
+
+impl ChannelRepository for MemoryRepository {
+  fn get(&self, path: &str) -> io::Result<Option<Bytes>> {
+    Ok(self.files.get(path).cloned())
+  }
+}
+
+/// Service serving the files of a channel over HTTP
+///
+/// Only `GET` and `HEAD` requests are supported. Missing files are reported with `404 Not Found`, and repository errors
+/// with `500 Internal Server Error`.
+#[derive(Debug)]
+pub struct PearServer<R> {
+  repository: Arc<R>,
+  base_path: CompactString,
+}
+
+impl<R> Clone for PearServer<R> {
+  fn clone(&self) -> Self {
+    Self {
+      repository: Arc::clone(&self.repository),
+      base_path: self.base_path.clone(),
+    }
+  }
+}
+
+impl<R> PearServer<R> {
+  /// Serve `repository` at the root path (`/channel.xml`, `/rest/...`)
+  pub fn new(repository: R) -> Self {
+    Self {
+      repository: Arc::new(repository),
+      base_path: CompactString::new("/"),
+    }
+  }
+
+  /// Serve the channel under a sub-path, e.g. `/pear/` for a channel at `https://example.com/pear/`
+  pub fn set_base_path(self, base_path: &str) -> Self {
+    let mut base = CompactString::new("/");
+    base.push_str(base_path.trim_matches('/'));
+    if base.len() > 1 {
+      base.push('/');
+    }
+    Self {
+      base_path: base,
+      ..self
+    }
+  }
+
+  pub fn repository(&self) -> &R {
+    &self.repository
+  }
+
+  pub fn base_path(&self) -> &str {
+    &self.base_path
+  }
+}
+
+impl<R: ChannelRepository> PearServer<R> {
+  /// Build the response to a request, the body is ignored
+  pub fn respond(&self, method: &Method, path: &str) -> Response<Full<Bytes>> {
+    if method != Method::GET && method != Method::HEAD {
+      let mut res = text_response(StatusCode::METHOD_NOT_ALLOWED, "method not allowed\n");
+      res.headers_mut().insert(ALLOW, HeaderValue::from_static("GET, HEAD"));
+      return res;
+    }
+    let relative = match path.strip_prefix(self.base_path.as_str()) {
+      Some(relative) if is_safe_path(relative) => relative,
+      _ => return text_response(StatusCode::NOT_FOUND, "not found\n"),
+    };
+    match self.repository.get(relative) {
+      Ok(Some(contents)) => {
+        let len = contents.len();
+        let body = if method == Method::HEAD { Bytes::new() } else { contents };
+        let mut res = Response::new(Full::new(body));
+        let headers = res.headers_mut();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type(relative)));
+        headers.insert(CONTENT_LENGTH, HeaderValue::from(len));
+        res
+      }
+      Ok(None) => text_response(StatusCode::NOT_FOUND, "not found\n"),
+      Err(e) => text_response(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        &format!("failed to read {relative:?}: {e}\n"),
+      ),
+    }
+  }
+}
+
+impl<R, TyBody> Service<Request<TyBody>> for PearServer<R>
+where
+  R: ChannelRepository,
+{
+  type Response = Response<Full<Bytes>>;
+  type Error = Infallible;
+  type Future = Ready<Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    Poll::Ready(Ok(()))
+  }
+
+  fn call(&mut self, req: Request<TyBody>) -> Self::Future {
+    ready(Ok(self.respond(req.method(), req.uri().path())))
+  }
+}
+
+/// Check that a relative request path only contains non-empty segments, and no `.` or `..` segments
+fn is_safe_path(path: &str) -> bool {
+  !path.is_empty()
+    && path
+      .split('/')
+      .all(|segment| !matches!(segment, "" | "." | "..") && !segment.contains('\\'))
+}
+
+fn content_type(path: &str) -> &'static str {
+  match path.rsplit_once('.').map(|(_, ext)| ext) {
+    Some("xml") => "text/xml",
+    Some("txt") => "text/plain",
+    Some("tgz") => "application/x-gzip",
+    _ => "application/octet-stream",
+  }
+}
+
+fn text_response(status: StatusCode, body: &str) -> Response<Full<Bytes>> {
+  let mut res = Response::new(Full::new(Bytes::copy_from_slice(body.as_bytes())));
+  *res.status_mut() = status;
+  res
+    .headers_mut()
+    .insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+  res
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::client::http::HttpPearClientError;
+  use crate::client::pear::PearClient;
+  use crate::common::package::PackageListing;
+  use crate::common::release::ReleaseListing;
+  use crate::common::stability::Stability;
+  use crate::context::PearUrl;
+  use url::Url;
+
+  fn pear_url() -> PearUrl {
+    PearUrl(Url::parse("https://pear.example.com/").unwrap())
+  }
+
+  fn repository() -> MemoryRepository {
+    let mut repository = MemoryRepository::new();
+    repository.insert(
+      "rest/p/packages.xml",
+      PackageListing {
+        category: CompactString::new("pear.example.com"),
+        items: vec![CompactString::new("Demo")],
+      }
+      .to_xml(),
+    );
+    repository.insert(
+      "rest/r/demo/allreleases.xml",
+      ReleaseListing::from_xml(include_bytes!(
+        "../test-resources/get_release_list/pecl_protobuf/input.xml"
+      ))
+      .to_xml(),
+    );
+    repository.insert("rest/r/demo/stable.txt", "4.27.0");
+    repository
+  }
+
+  #[test]
+  pub fn test_pear_server_with_http_client() {
+    let client = PearClient::http(PearServer::new(repository()), pear_url());
+    futures::executor::block_on(async {
+      let packages: PackageListing = client.packages().await.unwrap();
+      assert_eq!(packages.items, ["Demo"]);
+      let package = client.package("demo");
+      let releases: ReleaseListing = package.releases().await.unwrap();
+      assert_eq!(releases.latest(&Stability::Stable).unwrap().version.as_str(), "4.27.0");
+      let latest = package.latest(Some(Stability::Stable)).await.unwrap();
+      assert_eq!(latest.as_str(), "4.27.0");
+      let info = package.info().await;
+      assert_eq!(info, Err(HttpPearClientError::NotFound));
+    });
+  }
+
+  #[test]
+  pub fn test_pear_server_responses() {
+    let server = PearServer::new(repository()).set_base_path("pear");
+    let res = server.respond(&Method::GET, "/pear/rest/r/demo/stable.txt");
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()[CONTENT_TYPE], "text/plain");
+    assert_eq!(res.headers()[CONTENT_LENGTH], "6");
+    assert_eq!(
+      server.respond(&Method::HEAD, "/pear/rest/p/packages.xml").status(),
+      StatusCode::OK
+    );
+    assert_eq!(
+      server.respond(&Method::GET, "/rest/p/packages.xml").status(),
+      StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+      server.respond(&Method::GET, "/pear/rest/r/../p/packages.xml").status(),
+      StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+      server.respond(&Method::POST, "/pear/rest/p/packages.xml").status(),
+      StatusCode::METHOD_NOT_ALLOWED
+    );
+  }
+
+  #[test]
+  pub fn test_directory_repository() {
+    let root = tempfile::tempdir().unwrap();
+    fs::create_dir_all(root.path().join("rest/p")).unwrap();
+    fs::write(root.path().join("rest/p/packages.xml"), "packages").unwrap();
+    let repository = DirectoryRepository::new(root.path());
+    assert_eq!(
+      repository.get("rest/p/packages.xml").unwrap(),
+      Some(Bytes::from_static(b"packages"))
+    );
+    assert_eq!(repository.get("rest/p").unwrap(), None);
+    assert_eq!(repository.get("rest/p/packages.xml/info.xml").unwrap(), None);
+    assert_eq!(repository.get("../packages.xml").unwrap(), None);
+  }
+}
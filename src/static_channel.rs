@@ -0,0 +1,744 @@
+//! Static channel generator
+//!
+//! Builds all the files of a channel from a directory of release archives (`.tgz`): `channel.xml`, the REST tree under
+//! `rest/` and a copy of the archives under `get/`. The output can be served by any static file server, and is
+//! consumed both by [`HttpPearClient`](crate::client::http::HttpPearClient) and by the `pear`/`pecl` installers.
+//! [`generate_channel_files`] builds the same files in memory.
+//!
+//! The documents are built from the `package.xml` file (version 2.0 or later) of each archive. `package.xml` does not
+//! contain the category of the package, so all the packages are put in the category from the configuration.
+
+use crate::archive::{read_release_package_xml, ArchivePackageXml, ExtractArchiveError};
+use crate::common::category::{
+  CategoryInfo, CategoryListing, CategoryPackageInfo, CategoryPackageListing, CategoryPackageRef, CategoryPackagesInfo,
+  CategoryRef, CategoryReleaseDependencies,
+};
+use crate::common::channel::{ChannelInfo, ChannelServer, RestBaseUrl};
+use crate::common::maintainer::{Maintainer, MaintainerInfo, MaintainerListing, PackageMaintainer, PackageMaintainers};
+use crate::common::package::{PackageInfo, PackageListing};
+use crate::common::package_xml::{MaintainerRole, PackageXml};
+use crate::common::release::{
+  parse_release_date, Release, ReleaseArchive, ReleaseListing, ReleaseListing2, ReleasePackage, ReleaseV2,
+  ShortRelease, ShortRelease2,
+};
+use crate::common::stability::Stability;
+use crate::common::version::PearVersion;
+use crate::context::PearUrl;
+use crate::url_util::{php_urlencode, url_join, url_join_root};
+use bytes::Bytes;
+use chrono::NaiveDateTime;
+use compact_str::{format_compact, CompactString, ToCompactString};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Channel settings which are not found in the release archives
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StaticChannelConfig {
+  /// Channel name, archives built for another channel are rejected
+  pub name: CompactString,
+  pub summary: CompactString,
+  pub suggested_alias: Option<CompactString>,
+  /// Public URL of the channel, the REST base URL is `{url}rest/`
+  pub url: PearUrl,
+  /// Category of all the packages
+  pub category: CompactString,
+}
+
+impl StaticChannelConfig {
+  /// Configuration for a channel served at `url`, named after its host (and port, if any)
+  pub fn new(url: PearUrl) -> Self {
+    let host = url.host_str().unwrap_or_default();
+    let name = match url.port() {
+      Some(port) => format_compact!("{host}:{port}"),
+      None => CompactString::new(host),
+    };
+    Self {
+      summary: name.clone(),
+      name,
+      suggested_alias: None,
+      url,
+      category: CompactString::new("Default"),
+    }
+  }
+}
+
+/// Summary of a generated channel
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GeneratedChannel {
+  /// Package names, sorted
+  pub packages: Vec<CompactString>,
+  /// Number of releases, across all packages
+  pub releases: usize,
+  /// Paths of the written files, relative to the output directory
+  pub files: Vec<PathBuf>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, thiserror::Error)]
+pub enum GenerateChannelError {
+  #[error("failed to list the archives in {0:?}: {1}")]
+  ReadDir(CompactString, String),
+  #[error("failed to read archive {0:?}")]
+  Archive(CompactString, #[source] ExtractArchiveError),
+  #[error("archive {0:?} only contains a package.xml version 1.0, version 2.0 or later is required")]
+  UnsupportedPackageXml(CompactString),
+  #[error("archive {file:?} belongs to channel {actual:?}, expected {expected:?}")]
+  ChannelMismatch {
+    file: CompactString,
+    expected: CompactString,
+    actual: Option<CompactString>,
+  },
+  #[error("archive {0:?} has an invalid version")]
+  InvalidVersion(CompactString),
+  #[error("archive {0:?} has an invalid release date")]
+  InvalidDate(CompactString),
+  #[error("name {0:?} can't be used as a path segment")]
+  UnsafeName(CompactString),
+  #[error("release {package} {version} is provided by more than one archive")]
+  DuplicateRelease {
+    package: CompactString,
+    version: CompactString,
+  },
+  #[error("failed to write {0:?}: {1}")]
+  Write(CompactString, String),
+}
+
+/// Contents of an archive, read from a file or already in memory
+enum ArchiveData {
+  File(PathBuf),
+  Memory(Bytes),
+}
+
+/// Release read from an archive
+struct ArchiveRelease {
+  archive: ArchiveData,
+  size: u64,
+  package_xml: Box<PackageXml>,
+  raw_package_xml: Vec<u8>,
+  version: PearVersion,
+  stability: Stability,
+  time: NaiveDateTime,
+}
+
+/// Generate a static channel in `output`, from the `.tgz` archives in `input`
+///
+/// Existing files in `output` are overwritten, other files are kept.
+pub fn generate_static_channel(
+  config: &StaticChannelConfig,
+  input: &Path,
+  output: &Path,
+) -> Result<GeneratedChannel, GenerateChannelError> {
+  let mut releases = Vec::new();
+  for archive in list_archives(input)? {
+    releases.push(read_release(config, display(&archive), ArchiveData::File(archive))?);
+  }
+  let mut out = OutputTree {
+    root: output,
+    files: Vec::new(),
+  };
+  let (packages, releases) = generate(config, releases, &mut out)?;
+  Ok(GeneratedChannel {
+    packages,
+    releases,
+    files: out.files,
+  })
+}
+
+/// Generate the files of a static channel in memory, from archives provided as `(file name, contents)` pairs
+///
+/// The file names are only used in error messages. The result maps the path of each file, relative to the channel
+/// root (e.g. `rest/p/packages.xml`), to its contents.
+pub fn generate_channel_files<I>(
+  config: &StaticChannelConfig,
+  archives: I,
+) -> Result<BTreeMap<CompactString, Bytes>, GenerateChannelError>
+where
+  I: IntoIterator<Item = (CompactString, Bytes)>,
+{
+  let releases = archives
+    .into_iter()
+    .map(|(file, data)| read_release(config, file, ArchiveData::Memory(data)))
+    .collect::<Result<Vec<_>, _>>()?;
+  let mut out = BTreeMap::new();
+  generate(config, releases, &mut out)?;
+  Ok(out)
+}
+
+/// Write all the files of the channel, returns the package names and the number of releases
+fn generate<O: ChannelOutput>(
+  config: &StaticChannelConfig,
+  releases: Vec<ArchiveRelease>,
+  out: &mut O,
+) -> Result<(Vec<CompactString>, usize), GenerateChannelError> {
+  // lowercase package name => releases, the REST paths use the lowercase name
+  let mut packages: BTreeMap<CompactString, Vec<ArchiveRelease>> = BTreeMap::new();
+  for release in releases {
+    packages
+      .entry(release.package_xml.name.to_lowercase().to_compact_string())
+      .or_default()
+      .push(release);
+  }
+  for releases in packages.values_mut() {
+    // most recent first, as in `allreleases.xml`
+    releases.sort_by(|a, b| b.version.cmp(&a.version));
+    if let Some(pair) = releases.windows(2).find(|pair| pair[0].version == pair[1].version) {
+      return Err(GenerateChannelError::DuplicateRelease {
+        package: pair[0].package_xml.name.clone(),
+        version: pair[0].package_xml.version.release.clone(),
+      });
+    }
+  }
+
+  let rest = url_join(&config.url, [""]);
+  // path prefix of the links in the REST documents, e.g. `/rest/`
+  let rest_path = rest.path();
+  let channel = config.name.clone();
+  let category = config.category.clone();
+  let category_dir = php_urlencode(&category);
+  check_segment(&category_dir)?;
+
+  out.write(
+    "channel.xml",
+    ChannelInfo {
+      name: channel.clone(),
+      suggested_alias: config.suggested_alias.clone(),
+      summary: config.summary.clone(),
+      local_alias: None,
+      validate_package: None,
+      primary: ChannelServer {
+        port: config.url.port(),
+        ssl: config.url.scheme() == "https",
+        rest: ["REST1.0", "REST1.1", "REST1.3"]
+          .into_iter()
+          .map(|protocol| RestBaseUrl {
+            protocol: CompactString::new(protocol),
+            url: rest.to_compact_string(),
+          })
+          .collect(),
+      },
+      mirrors: Vec::new(),
+    }
+    .to_xml(),
+  )?;
+
+  let mut names = Vec::new();
+  let mut category_packages = Vec::new();
+  let mut packages_info = Vec::new();
+  let mut maintainers: BTreeMap<CompactString, MaintainerInfo> = BTreeMap::new();
+  let mut release_count = 0;
+  for (lower, releases) in &packages {
+    check_segment(lower)?;
+    let latest = &releases[0].package_xml;
+    let name = latest.name.clone();
+    let package_link = format_compact!("{rest_path}p/{lower}");
+
+    let info = PackageInfo {
+      name: name.clone(),
+      channel: channel.clone(),
+      category: category.clone(),
+      license: latest.license.name.clone(),
+      license_uri: latest.license.uri.clone(),
+      summary: latest.summary.clone(),
+      description: latest.description.clone(),
+      release_uri: format_compact!("{rest_path}r/{lower}"),
+      parent_package: None,
+      deprecation: None,
+    };
+    out.write(&format!("rest/p/{lower}/info.xml"), info.to_xml())?;
+
+    let package_maintainers = PackageMaintainers {
+      package: name.clone(),
+      channel: channel.clone(),
+      items: latest
+        .maintainers
+        .iter()
+        .map(|m| PackageMaintainer {
+          handle: m.user.clone(),
+          active: m.active.unwrap_or(true),
+          role: Some(m.role),
+        })
+        .collect(),
+    };
+    out.write(
+      &format!("rest/p/{lower}/maintainers2.xml"),
+      package_maintainers.to_xml(),
+    )?;
+    let package_maintainers = PackageMaintainers {
+      items: package_maintainers
+        .items
+        .into_iter()
+        .map(|m| PackageMaintainer { role: None, ..m })
+        .collect(),
+      ..package_maintainers
+    };
+    out.write(&format!("rest/p/{lower}/maintainers.xml"), package_maintainers.to_xml())?;
+    for m in &latest.maintainers {
+      check_segment(&m.user)?;
+      maintainers.entry(m.user.clone()).or_insert_with(|| MaintainerInfo {
+        handle: m.user.clone(),
+        name: m.name.clone(),
+        url: None,
+      });
+    }
+
+    let listing = ReleaseListing {
+      package: name.clone(),
+      channel: channel.clone(),
+      items: releases
+        .iter()
+        .map(|r| ShortRelease {
+          version: r.version.clone(),
+          stability: r.stability.clone(),
+        })
+        .collect(),
+    };
+    out.write(&format!("rest/r/{lower}/allreleases.xml"), listing.to_xml())?;
+    out.write(&format!("rest/r/{lower}/latest.txt"), releases[0].version.as_str())?;
+    for stability in [Stability::Stable, Stability::Beta, Stability::Alpha, Stability::Devel] {
+      if let Some(release) = listing.latest(&stability) {
+        out.write(&format!("rest/r/{lower}/{stability}.txt"), release.version.as_str())?;
+      }
+    }
+
+    let mut items2 = Vec::new();
+    let mut dependencies = Vec::new();
+    for release in releases {
+      let package_xml = &release.package_xml;
+      let version = release.version.as_str();
+      check_segment(version)?;
+      let archive_name = format!("{name}-{version}");
+      // the installer appends the archive extension to the download link
+      let archive_link = url_join_root(&config.url, ["get", archive_name.as_str()]);
+      let php_version = php_version(package_xml);
+      let api_version = PearVersion::new(&package_xml.version.api).unwrap_or_else(|_| release.version.clone());
+      let maintainer = package_xml
+        .maintainers
+        .iter()
+        .find(|m| m.role == MaintainerRole::Lead)
+        .or(package_xml.maintainers.first())
+        .map(|m| m.user.clone())
+        .unwrap_or_default();
+      let release_v2 = ReleaseV2 {
+        release: Release {
+          package: ReleasePackage {
+            name: name.clone(),
+            link: package_link.clone(),
+          },
+          channel: channel.clone(),
+          version: release.version.clone(),
+          status: release.stability.clone(),
+          license: package_xml.license.name.clone(),
+          maintainer,
+          summary: package_xml.summary.clone(),
+          description: package_xml.description.clone(),
+          time: release.time,
+          release_notes: package_xml.notes.clone(),
+          archive: ReleaseArchive {
+            size: release.size,
+            link: archive_link,
+          },
+          extracted_link: format_compact!("package.{version}.xml"),
+        },
+        api_version,
+        php_version: php_version.clone(),
+      };
+      out.write(&format!("rest/r/{lower}/{version}.xml"), release_v2.release.to_xml())?;
+      out.write(&format!("rest/r/{lower}/v2.{version}.xml"), release_v2.to_xml())?;
+      out.write(
+        &format!("rest/r/{lower}/package.{version}.xml"),
+        &release.raw_package_xml,
+      )?;
+      out.write(
+        &format!("rest/r/{lower}/deps.{version}.txt"),
+        package_xml.dependencies.to_php_serialized(),
+      )?;
+      out.copy(&format!("get/{archive_name}.tgz"), &release.archive)?;
+
+      items2.push(ShortRelease2 {
+        version: release.version.clone(),
+        stability: release.stability.clone(),
+        php_version,
+      });
+      dependencies.push(CategoryReleaseDependencies {
+        version: release.version.clone(),
+        dependencies: package_xml.dependencies.clone(),
+      });
+      release_count += 1;
+    }
+    let listing2 = ReleaseListing2 {
+      package: name.clone(),
+      channel: channel.clone(),
+      items: items2,
+    };
+    out.write(&format!("rest/r/{lower}/allreleases2.xml"), listing2.to_xml())?;
+
+    category_packages.push(CategoryPackageRef {
+      name: name.clone(),
+      link: package_link,
+    });
+    packages_info.push(CategoryPackageInfo {
+      info,
+      releases: listing.items,
+      dependencies,
+    });
+    names.push(name);
+  }
+
+  out.write(
+    "rest/p/packages.xml",
+    PackageListing {
+      category: channel.clone(),
+      items: names.clone(),
+    }
+    .to_xml(),
+  )?;
+
+  out.write(
+    "rest/c/categories.xml",
+    CategoryListing {
+      channel: channel.clone(),
+      items: vec![CategoryRef {
+        name: category.clone(),
+        link: format_compact!("{rest_path}c/{category_dir}/info.xml"),
+      }],
+    }
+    .to_xml(),
+  )?;
+  out.write(
+    &format!("rest/c/{category_dir}/info.xml"),
+    CategoryInfo {
+      name: category.clone(),
+      channel: channel.clone(),
+      alias: category.clone(),
+      description: category.clone(),
+    }
+    .to_xml(),
+  )?;
+  out.write(
+    &format!("rest/c/{category_dir}/packages.xml"),
+    CategoryPackageListing {
+      items: category_packages,
+    }
+    .to_xml(),
+  )?;
+  out.write(
+    &format!("rest/c/{category_dir}/packagesinfo.xml"),
+    CategoryPackagesInfo { items: packages_info }.to_xml(),
+  )?;
+
+  out.write(
+    "rest/m/allmaintainers.xml",
+    MaintainerListing {
+      items: maintainers
+        .keys()
+        .map(|handle| Maintainer {
+          handle: handle.clone(),
+          link: format_compact!("{rest_path}m/{handle}"),
+        })
+        .collect(),
+    }
+    .to_xml(),
+  )?;
+  for (handle, info) in &maintainers {
+    out.write(&format!("rest/m/{handle}/info.xml"), info.to_xml())?;
+  }
+
+  Ok((names, release_count))
+}
+
+/// List the `.tgz` files of a directory, sorted by name
+fn list_archives(input: &Path) -> Result<Vec<PathBuf>, GenerateChannelError> {
+  let read_error = |e: std::io::Error| GenerateChannelError::ReadDir(display(input), e.to_string());
+  let mut archives = Vec::new();
+  for entry in fs::read_dir(input).map_err(read_error)? {
+    let path = entry.map_err(read_error)?.path();
+    if path.is_file() && path.extension().is_some_and(|ext| ext == "tgz") {
+      archives.push(path);
+    }
+  }
+  archives.sort();
+  Ok(archives)
+}
+
+fn read_release(
+  config: &StaticChannelConfig,
+  file: CompactString,
+  archive: ArchiveData,
+) -> Result<ArchiveRelease, GenerateChannelError> {
+  use GenerateChannelError as E;
+
+  let read_error = |e: std::io::Error| E::Archive(file.clone(), ExtractArchiveError::Read(e.to_string()));
+  let (size, package_xml_file) = match &archive {
+    ArchiveData::File(path) => {
+      let input = fs::File::open(path).map_err(read_error)?;
+      let size = input.metadata().map_err(read_error)?.len();
+      (size, read_release_package_xml(input))
+    }
+    ArchiveData::Memory(data) => (data.len() as u64, read_release_package_xml(data.as_ref())),
+  };
+  let package_xml_file = package_xml_file.map_err(|e| E::Archive(file.clone(), e))?;
+  let package_xml = match package_xml_file.package_xml {
+    ArchivePackageXml::V2(package_xml) => package_xml,
+    ArchivePackageXml::V1(_) => return Err(E::UnsupportedPackageXml(file)),
+  };
+  if package_xml.channel.as_deref() != Some(config.name.as_str()) {
+    return Err(E::ChannelMismatch {
+      file,
+      expected: config.name.clone(),
+      actual: package_xml.channel.clone(),
+    });
+  }
+  let version = PearVersion::new(&package_xml.version.release).map_err(|_| E::InvalidVersion(file.clone()))?;
+  let date = match &package_xml.time {
+    Some(time) => format!("{} {}", package_xml.date, time),
+    None => package_xml.date.to_string(),
+  };
+  let time = parse_release_date(&date).ok_or_else(|| E::InvalidDate(file.clone()))?;
+  Ok(ArchiveRelease {
+    archive,
+    size,
+    stability: Stability::new(&package_xml.stability.release),
+    package_xml,
+    raw_package_xml: package_xml_file.raw,
+    version,
+    time,
+  })
+}
+
+/// Minimum PHP version of a release, `0` if there is no lower bound
+fn php_version(package_xml: &PackageXml) -> PearVersion {
+  package_xml
+    .dependencies
+    .required
+    .php
+    .min
+    .as_deref()
+    .and_then(|min| PearVersion::new(min).ok())
+    .unwrap_or_else(|| PearVersion::new("0").expect("`0` is a valid version"))
+}
+
+/// Check that a name read from an archive can be used as a single path segment
+fn check_segment(name: &str) -> Result<(), GenerateChannelError> {
+  if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+    return Err(GenerateChannelError::UnsafeName(CompactString::new(name)));
+  }
+  Ok(())
+}
+
+fn display(path: &Path) -> CompactString {
+  CompactString::new(path.to_string_lossy())
+}
+
+/// Destination of the generated files
+trait ChannelOutput {
+  fn write(&mut self, relative: &str, contents: impl AsRef<[u8]>) -> Result<(), GenerateChannelError>;
+
+  fn copy(&mut self, relative: &str, archive: &ArchiveData) -> Result<(), GenerateChannelError>;
+}
+
+/// Output directory, tracking the written files
+struct OutputTree<'a> {
+  root: &'a Path,
+  files: Vec<PathBuf>,
+}
+
+impl OutputTree<'_> {
+  /// Create the parent directory of a file, and record it
+  fn prepare(&mut self, relative: &str) -> Result<PathBuf, GenerateChannelError> {
+    let target = self.root.join(relative);
+    if let Some(parent) = target.parent() {
+      fs::create_dir_all(parent)
+        .map_err(|e| GenerateChannelError::Write(CompactString::new(relative), e.to_string()))?;
+    }
+    self.files.push(PathBuf::from(relative));
+    Ok(target)
+  }
+}
+
+impl ChannelOutput for OutputTree<'_> {
+  fn write(&mut self, relative: &str, contents: impl AsRef<[u8]>) -> Result<(), GenerateChannelError> {
+    let target = self.prepare(relative)?;
+    fs::write(target, contents).map_err(|e| GenerateChannelError::Write(CompactString::new(relative), e.to_string()))
+  }
+
+  fn copy(&mut self, relative: &str, archive: &ArchiveData) -> Result<(), GenerateChannelError> {
+    let target = self.prepare(relative)?;
+    let result = match archive {
+      ArchiveData::File(source) => fs::copy(source, target).map(drop),
+      ArchiveData::Memory(data) => fs::write(target, data),
+    };
+    result.map_err(|e| GenerateChannelError::Write(CompactString::new(relative), e.to_string()))
+  }
+}
+
+/// In-memory output, by relative path
+impl ChannelOutput for BTreeMap<CompactString, Bytes> {
+  fn write(&mut self, relative: &str, contents: impl AsRef<[u8]>) -> Result<(), GenerateChannelError> {
+    self.insert(CompactString::new(relative), Bytes::copy_from_slice(contents.as_ref()));
+    Ok(())
+  }
+
+  fn copy(&mut self, relative: &str, archive: &ArchiveData) -> Result<(), GenerateChannelError> {
+    let data = match archive {
+      ArchiveData::File(source) => fs::read(source)
+        .map(Bytes::from)
+        .map_err(|e| GenerateChannelError::Write(CompactString::new(relative), e.to_string()))?,
+      ArchiveData::Memory(data) => data.clone(),
+    };
+    self.insert(CompactString::new(relative), data);
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::common::dependency::ReleaseDependencies;
+  use flate2::write::GzEncoder;
+  use flate2::Compression;
+  use url::Url;
+
+  fn package_xml(version: &str, stability: &str) -> String {
+    format!(
+      r#"<?xml version="1.0" encoding="UTF-8"?>
+<package version="2.0" xmlns="http://pear.php.net/dtd/package-2.0">
+ <name>Demo</name>
+ <channel>pear.example.com</channel>
+ <summary>Demo</summary>
+ <description>Demo extension</description>
+ <lead>
+  <name>Demo Lead</name>
+  <user>lead</user>
+  <email>lead@example.com</email>
+  <active>yes</active>
+ </lead>
+ <date>2024-05-21</date>
+ <time>10:20:30</time>
+ <version>
+  <release>{version}</release>
+  <api>1.0.0</api>
+ </version>
+ <stability>
+  <release>{stability}</release>
+  <api>stable</api>
+ </stability>
+ <license uri="https://opensource.org/licenses/BSD-3-Clause">BSD-3-Clause</license>
+ <notes>-</notes>
+ <contents>
+  <dir name="/">
+   <file name="config.m4" role="src" />
+  </dir>
+ </contents>
+ <dependencies>
+  <required>
+   <php>
+    <min>8.0.0</min>
+   </php>
+   <pearinstaller>
+    <min>1.4.0</min>
+   </pearinstaller>
+  </required>
+ </dependencies>
+ <providesextension>demo</providesextension>
+ <extsrcrelease />
+</package>
+"#
+    )
+  }
+
+  fn write_archive(dir: &Path, file_name: &str, package_xml: &str) {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for (path, data) in [
+      ("package.xml", package_xml.as_bytes()),
+      ("Demo-1.0.0/config.m4", b"config"),
+    ] {
+      let mut header = tar::Header::new_gnu();
+      header.set_path(path).unwrap();
+      header.set_size(data.len() as u64);
+      header.set_mode(0o644);
+      header.set_cksum();
+      builder.append(&header, data).unwrap();
+    }
+    fs::write(dir.join(file_name), builder.into_inner().unwrap().finish().unwrap()).unwrap();
+  }
+
+  fn config() -> StaticChannelConfig {
+    StaticChannelConfig::new(PearUrl(Url::parse("https://pear.example.com/").unwrap()))
+  }
+
+  #[test]
+  pub fn test_generate_static_channel() {
+    let input = tempfile::tempdir().unwrap();
+    write_archive(input.path(), "Demo-1.0.0.tgz", &package_xml("1.0.0", "stable"));
+    write_archive(input.path(), "Demo-1.1.0RC1.tgz", &package_xml("1.1.0RC1", "beta"));
+    let output = tempfile::tempdir().unwrap();
+    let actual = generate_static_channel(&config(), input.path(), output.path()).unwrap();
+    assert_eq!(actual.packages, vec![CompactString::new("Demo")]);
+    assert_eq!(actual.releases, 2);
+    let read = |path: &str| fs::read(output.path().join(path)).unwrap();
+
+    let channel = ChannelInfo::from_xml(&read("channel.xml"));
+    assert_eq!(channel.name.as_str(), "pear.example.com");
+    assert_eq!(channel.pear_url(), Some(config().url));
+
+    let listing = ReleaseListing::from_xml(&read("rest/r/demo/allreleases.xml"));
+    let versions: Vec<&str> = listing.items.iter().map(|r| r.version.as_str()).collect();
+    assert_eq!(versions, ["1.1.0RC1", "1.0.0"]);
+    assert_eq!(read("rest/r/demo/latest.txt"), b"1.1.0RC1");
+    assert_eq!(read("rest/r/demo/stable.txt"), b"1.0.0");
+    assert_eq!(read("rest/r/demo/beta.txt"), b"1.1.0RC1");
+
+    let release = Release::from_xml(&read("rest/r/demo/1.0.0.xml"));
+    assert_eq!(release.maintainer.as_str(), "lead");
+    assert_eq!(release.time.to_string(), "2024-05-21 10:20:30");
+    assert_eq!(release.archive.link.as_str(), "https://pear.example.com/get/Demo-1.0.0");
+    assert_eq!(release.archive.size, read("get/Demo-1.0.0.tgz").len() as u64);
+    assert_eq!(
+      read("rest/r/demo/package.1.0.0.xml"),
+      package_xml("1.0.0", "stable").as_bytes()
+    );
+    let release = ReleaseV2::from_xml(&read("rest/r/demo/v2.1.0.0.xml"));
+    assert_eq!(release.php_version.as_str(), "8.0.0");
+    let deps = ReleaseDependencies::from_php_serialized(&read("rest/r/demo/deps.1.0.0.txt")).unwrap();
+    assert_eq!(deps.required.pear_installer.min.as_deref(), Some("1.4.0"));
+
+    let info = PackageInfo::from_xml(&read("rest/p/demo/info.xml"));
+    assert_eq!(info.category.as_str(), "Default");
+    let maintainers = PackageMaintainers::from_xml(&read("rest/p/demo/maintainers2.xml"));
+    assert_eq!(maintainers.items[0].role, Some(MaintainerRole::Lead));
+    let maintainer = MaintainerInfo::from_xml(&read("rest/m/lead/info.xml"));
+    assert_eq!(maintainer.name.as_str(), "Demo Lead");
+    let packages = CategoryPackagesInfo::from_xml(&read("rest/c/Default/packagesinfo.xml"));
+    assert_eq!(packages.items[0].dependencies.len(), 2);
+    assert_eq!(PackageListing::from_xml(&read("rest/p/packages.xml")).items, ["Demo"]);
+  }
+
+  #[test]
+  pub fn test_generate_channel_files() {
+    let input = tempfile::tempdir().unwrap();
+    write_archive(input.path(), "Demo-1.0.0.tgz", &package_xml("1.0.0", "stable"));
+    let output = tempfile::tempdir().unwrap();
+    let generated = generate_static_channel(&config(), input.path(), output.path()).unwrap();
+    let archive = Bytes::from(fs::read(input.path().join("Demo-1.0.0.tgz")).unwrap());
+    let actual = generate_channel_files(&config(), [(CompactString::new("Demo-1.0.0.tgz"), archive)]).unwrap();
+    assert_eq!(actual.len(), generated.files.len());
+    for file in &generated.files {
+      let path = file.to_str().unwrap();
+      assert_eq!(actual[path], fs::read(output.path().join(file)).unwrap(), "{path}");
+    }
+  }
+
+  #[test]
+  pub fn test_generate_static_channel_rejects_other_channels() {
+    let input = tempfile::tempdir().unwrap();
+    let package_xml = package_xml("1.0.0", "stable").replace("pear.example.com", "pecl.php.net");
+    write_archive(input.path(), "Demo-1.0.0.tgz", &package_xml);
+    let output = tempfile::tempdir().unwrap();
+    let actual = generate_static_channel(&config(), input.path(), output.path());
+    assert!(matches!(
+      actual,
+      Err(GenerateChannelError::ChannelMismatch { actual: Some(actual), .. }) if actual == "pecl.php.net"
+    ));
+  }
+}
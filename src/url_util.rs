@@ -7,18 +7,111 @@ where
 {
   let mut res: Url = url.clone();
   {
-    let mut p = res.path_segments_mut().expect("GitLab URL has path segments");
+    let mut p = res.path_segments_mut().expect("PEAR URL has path segments");
+    p.pop_if_empty();
     p.extend(["rest"]);
     p.extend(segments);
   }
   res
 }
 
+/// Join segments to the channel URL, without the `rest` prefix (e.g. for `channel.xml`)
+pub fn url_join_root<I>(url: &Url, segments: I) -> Url
+where
+  I: IntoIterator,
+  I::Item: AsRef<str>,
+{
+  let mut res: Url = url.clone();
+  {
+    let mut p = res.path_segments_mut().expect("PEAR URL has path segments");
+    p.pop_if_empty();
+    p.extend(segments);
+  }
+  res
+}
+
+/// Join segments under the `rest` prefix, encoding each segment with [`php_urlencode`]
+///
+/// PEAR servers store some resources under their `urlencode`d name (e.g. categories), so `Tools and Utilities` must
+/// be requested as `Tools+and+Utilities` instead of `Tools%20and%20Utilities`.
+pub fn url_join_php_encoded<I>(url: &Url, segments: I) -> Url
+where
+  I: IntoIterator,
+  I::Item: AsRef<str>,
+{
+  let mut res: Url = url.clone();
+  let mut path = String::from(res.path().trim_end_matches('/'));
+  path.push_str("/rest");
+  for segment in segments {
+    path.push('/');
+    path.push_str(&php_urlencode(segment.as_ref()));
+  }
+  res.set_path(&path);
+  res
+}
+
+/// Encode a string like the PHP `urlencode` function
+///
+/// Alphanumeric characters and `-_.` are kept, spaces are encoded as `+` and all other bytes are percent-encoded.
+pub fn php_urlencode(input: &str) -> String {
+  const HEX: &[u8; 16] = b"0123456789ABCDEF";
+  let mut out = String::with_capacity(input.len());
+  for b in input.bytes() {
+    match b {
+      b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' => out.push(char::from(b)),
+      b' ' => out.push('+'),
+      b => {
+        out.push('%');
+        out.push(char::from(HEX[usize::from(b >> 4)]));
+        out.push(char::from(HEX[usize::from(b & 0x0f)]));
+      }
+    }
+  }
+  out
+}
+
+/// Decode a string encoded with the PHP `urlencode` function
+///
+/// `+` is decoded as a space. Returns `None` if the decoded bytes are not valid UTF-8.
+pub fn php_urldecode(input: &str) -> Option<String> {
+  let bytes = input.as_bytes();
+  let mut out: Vec<u8> = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'+' => out.push(b' '),
+      b'%' => {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| core::str::from_utf8(h).ok());
+        match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+          Some(b) => {
+            out.push(b);
+            i += 2;
+          }
+          None => out.push(b'%'),
+        }
+      }
+      b => out.push(b),
+    }
+    i += 1;
+  }
+  String::from_utf8(out).ok()
+}
+
 pub trait UrlExt {
   fn url_join<I>(&self, segments: I) -> Self
   where
     I: IntoIterator,
     I::Item: AsRef<str>;
+
+  fn url_join_root<I>(&self, segments: I) -> Self
+  where
+    I: IntoIterator,
+    I::Item: AsRef<str>;
+
+  fn url_join_php_encoded<I>(&self, segments: I) -> Self
+  where
+    I: IntoIterator,
+    I::Item: AsRef<str>;
 }
 
 impl UrlExt for Url {
@@ -29,4 +122,75 @@ impl UrlExt for Url {
   {
     url_join(self, segments)
   }
+
+  fn url_join_root<I>(&self, segments: I) -> Self
+  where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+  {
+    url_join_root(self, segments)
+  }
+
+  fn url_join_php_encoded<I>(&self, segments: I) -> Self
+  where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+  {
+    url_join_php_encoded(self, segments)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  pub fn test_url_join_with_and_without_trailing_slash() {
+    for base in ["https://example.com/pear/", "https://example.com/pear"] {
+      let base = Url::parse(base).unwrap();
+      assert_eq!(
+        base.url_join(["p", "packages.xml"]).as_str(),
+        "https://example.com/pear/rest/p/packages.xml"
+      );
+      assert_eq!(
+        base.url_join_root(["channel.xml"]).as_str(),
+        "https://example.com/pear/channel.xml"
+      );
+    }
+    for base in ["https://pecl.php.net/", "https://pecl.php.net"] {
+      let base = Url::parse(base).unwrap();
+      assert_eq!(
+        base.url_join(["p", "packages.xml"]).as_str(),
+        "https://pecl.php.net/rest/p/packages.xml"
+      );
+      assert_eq!(
+        base.url_join_root(["channel.xml"]).as_str(),
+        "https://pecl.php.net/channel.xml"
+      );
+    }
+  }
+
+  #[test]
+  pub fn test_url_join_php_encoded() {
+    let base = Url::parse("https://pecl.php.net/").unwrap();
+    let actual = base.url_join_php_encoded(["c", "Tools and Utilities", "info.xml"]);
+    assert_eq!(
+      actual.as_str(),
+      "https://pecl.php.net/rest/c/Tools+and+Utilities/info.xml"
+    );
+    let actual = base.url_join_php_encoded(["c", "Files/Formats & Co", "info.xml"]);
+    assert_eq!(
+      actual.as_str(),
+      "https://pecl.php.net/rest/c/Files%2FFormats+%26+Co/info.xml"
+    );
+  }
+
+  #[test]
+  pub fn test_php_urldecode() {
+    assert_eq!(
+      php_urldecode("Files%2FFormats+%26+Co").as_deref(),
+      Some("Files/Formats & Co")
+    );
+    assert_eq!(php_urldecode("100%").as_deref(), Some("100%"));
+  }
 }
@@ -1,8 +1,21 @@
-use std::cell::RefCell;
+use crate::common::from_xml::FromXmlError;
 use compact_str::CompactString;
-use markup5ever_rcdom::{Handle, Node, NodeData};
-use xml5ever::{Attribute, local_name, ns, QualName, namespace_url, namespace_prefix};
+use markup5ever_rcdom::{Handle, Node, NodeData, RcDom};
+use std::cell::RefCell;
+use xml5ever::driver::{parse_document, XmlParseOpts};
 use xml5ever::tendril::StrTendril;
+use xml5ever::tendril::TendrilSink;
+use xml5ever::{local_name, namespace_prefix, namespace_url, ns, Attribute, QualName};
+
+/// Parse an XML document into a DOM tree.
+pub(crate) fn parse_rc_dom(mut input: &[u8]) -> Result<RcDom, FromXmlError> {
+  let input = &mut input;
+  let sink = RcDom::default();
+  parse_document(sink, XmlParseOpts::default())
+    .from_utf8()
+    .read_from(input)
+    .map_err(|e| FromXmlError::Xml(e.to_string()))
+}
 
 /// Get the root element node out of the document node.
 pub(crate) fn find_root(doc: &Node, expected_name: &str) -> Result<Handle, ()> {
@@ -11,7 +24,7 @@ pub(crate) fn find_root(doc: &Node, expected_name: &str) -> Result<Handle, ()> {
   let mut root: Option<&Handle> = None;
   for handle in children.iter() {
     let node: &Node = handle;
-    if let NodeData::Element { name, ..} = &node.data {
+    if let NodeData::Element { name, .. } = &node.data {
       if name.prefix.is_none() && name.local.eq_str_ignore_ascii_case(expected_name) {
         let old = root.replace(handle);
         if old.is_some() {
@@ -51,7 +64,10 @@ pub(crate) fn get_text(node: &Node) -> Result<CompactString, ()> {
 #[error("duplicate attribute")]
 pub struct DuplicateAttribute;
 
-pub(crate) fn get_attr<'a>(attrs: &'a [Attribute], name: &QualName) -> Result<Option<&'a Attribute>, ()> {
+pub(crate) fn get_attr<'a>(
+  attrs: &'a [Attribute],
+  name: &QualName,
+) -> Result<Option<&'a Attribute>, DuplicateAttribute> {
   let mut result: Option<&Attribute> = None;
   for attr in attrs {
     if attr.name != *name {
@@ -59,7 +75,7 @@ pub(crate) fn get_attr<'a>(attrs: &'a [Attribute], name: &QualName) -> Result<Op
     }
     let old = result.replace(attr);
     if old.is_some() {
-      return Err(());
+      return Err(DuplicateAttribute);
     }
   }
   Ok(result)
@@ -71,6 +87,202 @@ pub const XLINK_HREF: QualName = QualName {
   local: local_name!("href"),
 };
 
-pub(crate) fn get_link_attr(attrs: &[Attribute]) -> Result<Option<&'_ Attribute>, ()> {
+pub(crate) fn get_link_attr(attrs: &[Attribute]) -> Result<Option<&'_ Attribute>, DuplicateAttribute> {
   get_attr(attrs, &XLINK_HREF)
 }
+
+/// Get an attribute without namespace prefix, by local name.
+pub(crate) fn get_plain_attr<'a>(
+  attrs: &'a [Attribute],
+  name: &str,
+) -> Result<Option<&'a Attribute>, DuplicateAttribute> {
+  let mut result: Option<&Attribute> = None;
+  for attr in attrs {
+    if !(attr.name.prefix.is_none() && attr.name.local.eq_str_ignore_ascii_case(name)) {
+      continue;
+    }
+    let old = result.replace(attr);
+    if old.is_some() {
+      return Err(DuplicateAttribute);
+    }
+  }
+  Ok(result)
+}
+
+/// Writer for the documents of the REST interface, using the layout of the official channel server: one element per
+/// line, indented with a single space per level.
+pub(crate) struct RestXmlWriter {
+  out: String,
+  /// Names of the open elements, starting with the root
+  open: Vec<&'static str>,
+}
+
+impl RestXmlWriter {
+  /// Write the XML declaration and open the root element, with the usual namespace declarations
+  ///
+  /// `schema_location` is written as-is, since channels do not use the same spacing for every document.
+  pub(crate) fn new(root: &'static str, namespace: &str, schema_location: &str) -> Self {
+    Self::with_root_attrs(
+      root,
+      &[
+        ("xmlns", namespace),
+        ("xmlns:xsi", "http://www.w3.org/2001/XMLSchema-instance"),
+        ("xmlns:xlink", "http://www.w3.org/1999/xlink"),
+        ("xsi:schemaLocation", schema_location),
+      ],
+    )
+  }
+
+  /// Write the XML declaration and open the root element, with custom attributes
+  pub(crate) fn with_root_attrs(root: &'static str, attrs: &[(&str, &str)]) -> Self {
+    let mut writer = Self {
+      out: String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"),
+      open: Vec::new(),
+    };
+    writer.open(root, attrs);
+    writer
+  }
+
+  /// Open a child element, its children are written on the next lines
+  pub(crate) fn open(&mut self, name: &'static str, attrs: &[(&str, &str)]) -> &mut Self {
+    self.indent();
+    self.start_tag(name, attrs);
+    self.out.push_str(">\n");
+    self.open.push(name);
+    self
+  }
+
+  /// Close the last element opened with [`Self::open`]
+  pub(crate) fn close(&mut self) -> &mut Self {
+    let name = self.open.pop().expect("no open element");
+    self.indent();
+    self.end_tag(name);
+    self.out.push('\n');
+    self
+  }
+
+  /// Write `<name>text</name>` on its own line
+  pub(crate) fn text(&mut self, name: &str, text: &str) -> &mut Self {
+    self.text_with_attrs(name, &[], text)
+  }
+
+  /// Write `<name>text</name>` on its own line, without indentation
+  pub(crate) fn unindented_text(&mut self, name: &str, text: &str) -> &mut Self {
+    self.inline_text(name, text);
+    self.out.push('\n');
+    self
+  }
+
+  /// Write `<name attr="value">text</name>` on its own line
+  pub(crate) fn text_with_attrs(&mut self, name: &str, attrs: &[(&str, &str)], text: &str) -> &mut Self {
+    self.indent();
+    self.start_tag(name, attrs);
+    self.out.push('>');
+    push_escaped(&mut self.out, text, false);
+    self.end_tag(name);
+    self.out.push('\n');
+    self
+  }
+
+  /// Write `<name xlink:href="href">text</name>` on its own line
+  pub(crate) fn link_text(&mut self, name: &str, href: &str, text: &str) -> &mut Self {
+    self.text_with_attrs(name, &[("xlink:href", href)], text)
+  }
+
+  /// Write the empty element `<name xlink:href="href"/>` on its own line
+  pub(crate) fn link(&mut self, name: &str, href: &str) -> &mut Self {
+    self.indent();
+    self.start_tag(name, &[("xlink:href", href)]);
+    self.out.push_str("/>\n");
+    self
+  }
+
+  /// Write `<name>` followed by `(child, text)` elements, all on a single line
+  pub(crate) fn group(&mut self, name: &str, children: &[(&str, &str)]) -> &mut Self {
+    self.indent();
+    self.start_tag(name, &[]);
+    self.out.push('>');
+    for (child, text) in children {
+      self.inline_text(child, text);
+    }
+    self.end_tag(name);
+    self.out.push('\n');
+    self
+  }
+
+  /// Close all the open elements and return the document
+  ///
+  /// There is no newline after the end of the root element.
+  pub(crate) fn finish(mut self) -> String {
+    while self.open.len() > 1 {
+      self.close();
+    }
+    if let Some(root) = self.open.pop() {
+      self.end_tag(root);
+    }
+    self.out
+  }
+
+  fn indent(&mut self) {
+    for _ in 0..self.open.len() {
+      self.out.push(' ');
+    }
+  }
+
+  fn start_tag(&mut self, name: &str, attrs: &[(&str, &str)]) {
+    self.out.push('<');
+    self.out.push_str(name);
+    for (attr, value) in attrs {
+      self.out.push(' ');
+      self.out.push_str(attr);
+      self.out.push_str("=\"");
+      push_escaped(&mut self.out, value, true);
+      self.out.push('"');
+    }
+  }
+
+  fn end_tag(&mut self, name: &str) {
+    self.out.push_str("</");
+    self.out.push_str(name);
+    self.out.push('>');
+  }
+
+  fn inline_text(&mut self, name: &str, text: &str) {
+    self.start_tag(name, &[]);
+    self.out.push('>');
+    push_escaped(&mut self.out, text, false);
+    self.end_tag(name);
+  }
+}
+
+/// Append `input` to `out`, escaping XML special characters
+///
+/// Quotes are only escaped in attribute values.
+pub(crate) fn push_escaped(out: &mut String, input: &str, attribute: bool) {
+  for c in input.chars() {
+    match c {
+      '&' => out.push_str("&amp;"),
+      '<' => out.push_str("&lt;"),
+      '>' => out.push_str("&gt;"),
+      '"' if attribute => out.push_str("&quot;"),
+      c => out.push(c),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  pub fn test_rest_xml_writer_escapes_text() {
+    let mut writer = RestXmlWriter::new("a", "urn:test", "urn:test test.xsd");
+    writer
+      .text("p", "a < b && \"c\"")
+      .open("g", &[])
+      .link("x", "?a=1&b=\"2\"");
+    let actual = writer.finish();
+    let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<a xmlns=\"urn:test\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" xsi:schemaLocation=\"urn:test test.xsd\">\n <p>a &lt; b &amp;&amp; \"c\"</p>\n <g>\n  <x xlink:href=\"?a=1&amp;b=&quot;2&quot;\"/>\n </g>\n</a>";
+    assert_eq!(actual, expected);
+  }
+}